@@ -1,7 +1,14 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use crate::animation::{chance_over, project_sky, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::weather::{Aircraft, SkyPosition};
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::{Duration, Instant};
+
+/// Without fresh reports for this long the receiver is treated as down.
+const STALE_TRAFFIC_SECS: u64 = 60;
 
 #[derive(Clone)]
 struct Airplane {
@@ -10,11 +17,33 @@ struct Airplane {
     speed: f32,
 }
 
+/// Aircraft reported by a local ADS-B receiver around the observer.
+struct LiveTraffic {
+    latitude: f64,
+    longitude: f64,
+    radius_km: f64,
+    aircraft: Vec<Aircraft>,
+    /// Frame time the latest report was first drawn at, stamped on the next
+    /// update so it follows the frame clock.
+    received: Option<Instant>,
+}
+
+struct TrackedPlane {
+    x: u16,
+    y: u16,
+    label: String,
+    heading_right: bool,
+}
+
 pub struct AirplaneSystem {
     planes: Vec<Airplane>,
     terminal_width: u16,
     terminal_height: u16,
-    spawn_cooldown: u16,
+    spawn_cooldown: f32,
+    /// Whether new ones still appear, from 0 to 1.
+    level: f32,
+    live: Option<LiveTraffic>,
+    tracked: Vec<TrackedPlane>,
 }
 
 impl AirplaneSystem {
@@ -23,24 +52,65 @@ impl AirplaneSystem {
             planes: Vec::with_capacity(2),
             terminal_width,
             terminal_height,
-            spawn_cooldown: 0,
+            spawn_cooldown: 0.0,
+            level: 1.0,
+            live: None,
+            tracked: Vec::new(),
+        }
+    }
+
+    /// Shows real aircraft within `radius_km` of the observer instead of
+    /// random planes.
+    pub fn set_live_traffic(&mut self, latitude: f64, longitude: f64, radius_km: f64) {
+        self.live = Some(LiveTraffic {
+            latitude,
+            longitude,
+            radius_km,
+            aircraft: Vec::new(),
+            received: None,
+        });
+        self.planes.clear();
+    }
+
+    pub fn update_traffic(&mut self, aircraft: Vec<Aircraft>) {
+        if let Some(live) = &mut self.live {
+            live.aircraft = aircraft;
+            live.received = None;
         }
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        now: Instant,
+        terminal_width: u16,
+        terminal_height: u16,
+        horizon_y: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        if let Some(live) = &mut self.live {
+            let received = *live.received.get_or_insert(now);
+            let age = now.saturating_duration_since(received);
+            self.tracked = Self::track(live, age, terminal_width, horizon_y);
+            return;
+        }
+
+        let frames = reference_frames(dt);
         for plane in &mut self.planes {
-            plane.x += plane.speed;
+            plane.x += plane.speed * frames;
         }
 
         self.planes.retain(|p| p.x < terminal_width as f32);
 
-        self.spawn_cooldown = self.spawn_cooldown.saturating_sub(1);
-        if self.spawn_cooldown == 0 && rng.random::<f32>() < 0.001 {
+        self.spawn_cooldown = (self.spawn_cooldown - frames).max(0.0);
+        if self.spawn_cooldown == 0.0
+            && rng.random::<f32>() < chance_over(0.001 * self.level, frames)
+        {
             self.spawn_plane(rng);
-            self.spawn_cooldown = 600 + (rng.random::<u16>() % 300);
+            self.spawn_cooldown = 600.0 + (rng.random::<u16>() % 300) as f32;
         }
     }
 
@@ -51,7 +121,80 @@ impl AirplaneSystem {
         self.planes.push(Airplane { x: 0.0, y, speed });
     }
 
+    /// Places each reported aircraft by bearing and elevation, moved on
+    /// along its track for the `age` of the report. Nearer aircraft come
+    /// last so they are drawn on top.
+    fn track(live: &LiveTraffic, age: Duration, width: u16, horizon_y: u16) -> Vec<TrackedPlane> {
+        let age = age.as_secs_f64();
+        if age > STALE_TRAFFIC_SECS as f64 {
+            return Vec::new();
+        }
+
+        let mut sightings: Vec<_> = live
+            .aircraft
+            .iter()
+            .filter_map(|aircraft| {
+                let now = aircraft.advanced(age);
+                let sight = now.sight_line(live.latitude, live.longitude);
+                if sight.distance_km > live.radius_km {
+                    return None;
+                }
+                let position = SkyPosition {
+                    elevation: sight.elevation,
+                    azimuth: sight.bearing,
+                };
+                let (x, y) = project_sky(position, live.latitude, width, horizon_y)?;
+
+                let ahead = now.advanced(10.0).sight_line(live.latitude, live.longitude);
+                let turn = (ahead.bearing - sight.bearing + 540.0).rem_euclid(360.0) - 180.0;
+                let label = now
+                    .callsign
+                    .clone()
+                    .unwrap_or_else(|| now.hex.to_uppercase());
+                let plane = TrackedPlane {
+                    x,
+                    y,
+                    label,
+                    heading_right: turn > 0.0,
+                };
+                Some((sight.distance_km, plane))
+            })
+            .collect();
+
+        sightings.sort_by(|a, b| b.0.total_cmp(&a.0));
+        sightings.into_iter().map(|(_, plane)| plane).collect()
+    }
+
+    fn render_tracked(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for plane in &self.tracked {
+            let sprite = if plane.heading_right { "-=>" } else { "<=-" };
+            let left = plane.x.saturating_sub(1);
+            for (i, ch) in sprite.chars().enumerate() {
+                let x = left + i as u16;
+                if x < self.terminal_width {
+                    renderer.render_char(x, plane.y, ch, Color::White)?;
+                }
+            }
+
+            let label_y = plane.y + 1;
+            let label_x = (plane.x as i32 - plane.label.len() as i32 / 2).max(0) as u16;
+            if label_y < self.terminal_height {
+                for (i, ch) in plane.label.chars().enumerate() {
+                    let x = label_x + i as u16;
+                    if x < self.terminal_width {
+                        renderer.render_char(x, label_y, ch, Color::DarkGrey)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        if self.live.is_some() {
+            return self.render_tracked(renderer);
+        }
+
         let airplane_art = [
             "           _",
             "         -=\\`\\",
@@ -98,3 +241,40 @@ impl AirplaneSystem {
         Ok(())
     }
 }
+
+impl Effect for AirplaneSystem {
+    fn name(&self) -> &'static str {
+        "airplanes"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.precipitating() && !ctx.conditions.is_foggy
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.planes.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(
+            dt,
+            ctx.now,
+            ctx.width,
+            ctx.height,
+            ctx.geometry.horizon_y,
+            &mut rng,
+        );
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
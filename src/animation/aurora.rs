@@ -0,0 +1,227 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use crate::render::{ColorSupport, TerminalRenderer};
+use crossterm::style::Color;
+use rand::Rng;
+use std::io;
+use std::time::Duration;
+
+/// Aurora is never drawn closer to the equator than this.
+pub const MIN_LATITUDE: f64 = 55.0;
+/// Seconds for the display to brighten fully or fade out.
+const FADE_SECONDS: f32 = 20.0;
+/// `--simulate aurora` shows a strong storm seen from northern Norway.
+const SIMULATED_KP: f64 = 7.0;
+const SIMULATED_LATITUDE: f64 = 69.6;
+
+pub struct AuroraSystem {
+    kp: Option<f64>,
+    latitude: f64,
+    intensity: f32,
+    simulated: bool,
+    /// How long the display has been running, which sets the ripples.
+    clock: Duration,
+}
+
+impl AuroraSystem {
+    pub fn new() -> Self {
+        Self {
+            kp: None,
+            latitude: 0.0,
+            intensity: 0.0,
+            simulated: false,
+            clock: Duration::ZERO,
+        }
+    }
+
+    pub fn set_kp(&mut self, kp: f64) {
+        if !self.simulated {
+            self.kp = Some(kp);
+        }
+    }
+
+    pub fn set_latitude(&mut self, latitude: f64) {
+        self.latitude = latitude;
+    }
+
+    /// Shows a storm-strength display regardless of the location.
+    pub fn simulate(&mut self) {
+        self.simulated = true;
+        self.kp = Some(SIMULATED_KP);
+        self.intensity = 1.0;
+    }
+
+    /// How bright the display is at a latitude, from 0 to 1. The auroral
+    /// oval sits overhead near 66° in quiet conditions and moves about 2°
+    /// toward the equator per Kp step; it glows on the horizon from about 5°
+    /// further out.
+    pub fn strength(kp: f64, latitude: f64) -> f32 {
+        let latitude = latitude.abs();
+        if latitude < MIN_LATITUDE {
+            return 0.0;
+        }
+        let horizon_edge = 61.0 - 2.0 * kp;
+        ((latitude - horizon_edge) / 10.0).clamp(0.0, 1.0) as f32
+    }
+
+    /// Fades toward the current target. Daylight hides the aurora outright;
+    /// clouds block it from 30% cover and fully at 90%.
+    pub fn update(&mut self, dt: Duration, is_day: bool, cloud_cover: f64) {
+        self.clock += dt;
+
+        let latitude = if self.simulated {
+            SIMULATED_LATITUDE
+        } else {
+            self.latitude
+        };
+        let clear_sky = ((90.0 - cloud_cover) / 60.0).clamp(0.0, 1.0) as f32;
+        let target = match self.kp {
+            Some(kp) if !is_day => Self::strength(kp, latitude) * clear_sky,
+            _ => 0.0,
+        };
+
+        let step = dt.as_secs_f32() / FADE_SECONDS;
+        self.intensity = if self.intensity < target {
+            (self.intensity + step).min(target)
+        } else {
+            (self.intensity - step).max(target)
+        };
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.intensity > 0.0
+    }
+
+    /// Draws rippling curtains of rays in the upper sky, green at their
+    /// lower edge and purple above.
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+    ) -> io::Result<()> {
+        let support = renderer.color_support();
+        if self.intensity <= 0.0 || support == ColorSupport::None || horizon_y < 6 {
+            return Ok(());
+        }
+
+        let t = self.clock.as_secs_f32();
+        let base = horizon_y as f32 * 0.45;
+        let max_length = (horizon_y as f32 * 0.35).max(2.0);
+
+        for x in 0..width {
+            let xf = x as f32;
+            // Two slow waves: one folds the curtain, one brightens rays
+            let fold = (xf * 0.07 + t * 0.3).sin() * 2.0 + (xf * 0.023 - t * 0.11).sin() * 1.5;
+            let ray = 0.5 + 0.5 * ((xf * 0.19 - t * 0.7).sin() * (xf * 0.041 + t * 0.2).cos());
+            let strength = self.intensity * (0.35 + 0.65 * ray);
+
+            let bottom = base + fold;
+            let length = (max_length * strength).round();
+            if length < 1.0 {
+                continue;
+            }
+
+            for step in 0..length as u16 {
+                let y = bottom - step as f32;
+                if y < 1.0 {
+                    break;
+                }
+                // Faint displays only show as scattered rays
+                let speckle = ((x as u32 * 37 + step as u32 * 11) % 100) as f32 / 100.0;
+                if speckle >= strength + 0.2 {
+                    continue;
+                }
+
+                let height = step as f32 / length;
+                let ch = match height {
+                    h if h < 0.3 => '|',
+                    h if h < 0.7 => ':',
+                    _ => '.',
+                };
+                renderer.render_char(x, y as u16, ch, self.color(height, strength, support))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn color(&self, height: f32, strength: f32, support: ColorSupport) -> Color {
+        let upper = height >= 0.6;
+        match support {
+            ColorSupport::TrueColor => {
+                let (r, g, b) = if upper {
+                    (150, 60, 200)
+                } else {
+                    (40, 255, 120)
+                };
+                let scale = (0.25 + 0.75 * strength) * (1.0 - height * 0.5);
+                Color::Rgb {
+                    r: (r as f32 * scale) as u8,
+                    g: (g as f32 * scale) as u8,
+                    b: (b as f32 * scale) as u8,
+                }
+            }
+            ColorSupport::Ansi256 => Color::AnsiValue(match (upper, strength > 0.6) {
+                (false, true) => 48,
+                (false, false) => 29,
+                (true, true) => 134,
+                (true, false) => 97,
+            }),
+            ColorSupport::Basic | ColorSupport::None => {
+                if upper {
+                    Color::DarkMagenta
+                } else if strength > 0.6 {
+                    Color::Green
+                } else {
+                    Color::DarkGreen
+                }
+            }
+        }
+    }
+}
+
+impl Default for AuroraSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for AuroraSystem {
+    fn name(&self) -> &'static str {
+        "aurora"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, _rng: &mut dyn Rng) {
+        let cloud_cover = ctx.weather.map_or(0.0, |weather| weather.cloud_cover);
+        self.set_latitude(ctx.location.latitude);
+        self.update(dt, ctx.conditions.is_day, cloud_cover);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        if ctx.conditions.is_day || !self.is_visible() {
+            return Ok(());
+        }
+        self.render(renderer, ctx.width, ctx.geometry.horizon_y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aurora_strength_by_kp_and_latitude() {
+        assert_eq!(AuroraSystem::strength(9.0, 45.0), 0.0);
+        assert_eq!(AuroraSystem::strength(0.0, 60.0), 0.0);
+        assert!(AuroraSystem::strength(3.0, 60.0) > 0.0);
+        assert!(AuroraSystem::strength(6.0, 60.0) > AuroraSystem::strength(3.0, 60.0));
+        assert_eq!(
+            AuroraSystem::strength(4.0, -64.0),
+            AuroraSystem::strength(4.0, 64.0)
+        );
+    }
+}
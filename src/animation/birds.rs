@@ -1,69 +1,546 @@
+use super::Wind;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
+use crate::weather::SkyPosition;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
+
+const MAX_BIRDS: usize = 12;
+/// Flockmates further apart than this, in cells, ignore each other.
+const NEIGHBOR_RADIUS: f32 = 10.0;
+/// Flockmates closer than this steer apart.
+const SEPARATION_RADIUS: f32 = 2.5;
+const SEPARATION_WEIGHT: f32 = 0.08;
+const ALIGNMENT_WEIGHT: f32 = 0.05;
+const COHESION_WEIGHT: f32 = 0.004;
+/// Birds put down on the nearest shelter from a near gale on.
+const SHELTER_BEAUFORT: u8 = 7;
+/// Chance per frame that a flying bird decides to land.
+const PERCH_CHANCE: f32 = 0.002;
+/// Birds fly low when rain is forecast within this many hours.
+const RAIN_LOOKAHEAD_HOURS: usize = 3;
+/// Bats fly until the sun is this far below the horizon.
+const DUSK_SUN_ELEVATION: f64 = -10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Species {
+    Sparrow,
+    Swallow,
+    Crow,
+    Gull,
+    Bat,
+}
+
+impl Species {
+    /// Wings up and wings down.
+    fn sprites(self) -> [&'static str; 2] {
+        match self {
+            Self::Sparrow => ["v", "-"],
+            Self::Swallow => ["w", "~"],
+            Self::Crow => ["\\v/", "-v-"],
+            Self::Gull => ["\\_/", "~v~"],
+            Self::Bat => ["/^\\", "\\^/"],
+        }
+    }
+
+    fn sitting(self) -> &'static str {
+        match self {
+            Self::Crow | Self::Gull => "O",
+            _ => "o",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::Sparrow => Color::Yellow,
+            Self::Swallow => Color::Blue,
+            Self::Crow => Color::DarkGrey,
+            Self::Gull => Color::White,
+            Self::Bat => Color::DarkMagenta,
+        }
+    }
+
+    /// Cells per frame in level flight.
+    fn cruise_speed(self) -> f32 {
+        match self {
+            Self::Sparrow => 0.25,
+            Self::Swallow => 0.45,
+            Self::Crow => 0.2,
+            Self::Gull => 0.3,
+            Self::Bat => 0.3,
+        }
+    }
+
+    fn flock_size(self) -> std::ops::RangeInclusive<usize> {
+        match self {
+            Self::Sparrow | Self::Swallow => 3..=7,
+            Self::Crow | Self::Bat => 2..=4,
+            Self::Gull => 1..=3,
+        }
+    }
+
+    fn random_bird(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..10) {
+            0..=3 => Self::Sparrow,
+            4..=5 => Self::Swallow,
+            6..=7 => Self::Crow,
+            _ => Self::Gull,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flight {
+    Flying,
+    /// Heading for the perch with this index.
+    Landing(usize),
+    Perched {
+        perch: usize,
+        frames_left: f32,
+    },
+}
 
 struct Bird {
     x: f32,
     y: f32,
-    speed: f32,
-    character: char,
+    vx: f32,
+    vy: f32,
+    /// Which way the flock is travelling across the screen, -1 or 1.
+    heading: f32,
+    species: Species,
+    flight: Flight,
     flap_state: bool, // true = wings up, false = wings down/flat
-    flap_timer: u8,
+    flap_timer: f32,
+}
+
+impl Bird {
+    fn perch(&self) -> Option<usize> {
+        match self.flight {
+            Flight::Flying => None,
+            Flight::Landing(perch) | Flight::Perched { perch, .. } => Some(perch),
+        }
+    }
 }
 
+/// Flocks that steer by separation, alignment and cohesion. They fly low
+/// before rain, sit out strong wind on the lowest perches, stop to rest on
+/// the roof, fence and trees, and give way to bats at dusk.
 pub struct BirdSystem {
     birds: Vec<Bird>,
+    wind: Wind,
+    perches: Vec<(u16, u16)>,
+    horizon_y: u16,
+    rain_expected: bool,
+    dusk: bool,
     terminal_width: u16,
     terminal_height: u16,
+    /// Whether new flocks still come by, from 0 to 1.
+    level: f32,
 }
 
 impl BirdSystem {
     pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
         Self {
-            birds: Vec::with_capacity(3),
+            birds: Vec::with_capacity(MAX_BIRDS),
+            wind: Wind::default(),
+            perches: Vec::new(),
+            horizon_y: terminal_height,
+            rain_expected: false,
+            dusk: false,
             terminal_width,
             terminal_height,
+            level: 1.0,
         }
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn set_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
+    /// Perches move when the terminal is resized; birds sitting on the old
+    /// ones take off.
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.horizon_y = geometry.horizon_y;
+        if self.perches != geometry.perches {
+            self.perches = geometry.perches.clone();
+            for bird in &mut self.birds {
+                bird.flight = Flight::Flying;
+            }
+        }
+    }
+
+    /// Low pressure ahead of rain keeps insects, and the birds after them,
+    /// near the ground.
+    pub fn set_rain_expected(&mut self, rain_expected: bool) {
+        self.rain_expected = rain_expected;
+    }
+
+    pub fn set_dusk(&mut self, dusk: bool) {
+        self.dusk = dusk;
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let flow = self.wind.horizontal();
+        let beaufort = self.wind.beaufort();
+        // From a strong breeze on, birds labour: hard flapping, buffeted up and down
+        let struggling = beaufort >= 6;
+        let sheltering = beaufort >= SHELTER_BEAUFORT;
+        let flap_interval = if struggling { 2.0 } else { 5.0 };
+        let cruise_y = self.cruise_row();
+        let frames = reference_frames(dt);
+
+        self.steer(cruise_y, frames, rng);
+        self.choose_perches(sheltering, frames, rng);
+
+        let horizon_y = self.horizon_y as f32;
         for bird in &mut self.birds {
-            bird.x += bird.speed;
-            bird.flap_timer += 1;
-            if bird.flap_timer > 5 {
+            match bird.flight {
+                Flight::Perched { perch, frames_left } => {
+                    // Birds sit tight through a gale
+                    if sheltering {
+                        continue;
+                    }
+                    if frames_left > 0.0 {
+                        bird.flight = Flight::Perched {
+                            perch,
+                            frames_left: frames_left - frames,
+                        };
+                        continue;
+                    }
+                    bird.flight = Flight::Flying;
+                    bird.vx = bird.heading * bird.species.cruise_speed();
+                    bird.vy = -0.3;
+                }
+                Flight::Landing(perch) => {
+                    let (px, py) = self.perches[perch];
+                    let (dx, dy) = (px as f32 - bird.x, py as f32 - bird.y);
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    let speed = bird.species.cruise_speed();
+                    if distance <= (speed * frames).max(0.5) {
+                        bird.x = px as f32;
+                        bird.y = py as f32;
+                        bird.flight = Flight::Perched {
+                            perch,
+                            frames_left: rng.random_range(300.0..1200.0),
+                        };
+                        continue;
+                    }
+                    bird.vx = dx / distance * speed;
+                    bird.vy = dy / distance * speed;
+                }
+                Flight::Flying => {
+                    bird.vx += flow * 0.002 * frames;
+                    if struggling {
+                        bird.vy +=
+                            (rng.random::<f32>() - 0.5) * (beaufort - 5) as f32 * 0.05 * frames;
+                    }
+                }
+            }
+
+            bird.x += bird.vx * frames;
+            bird.y = (bird.y + bird.vy * frames).clamp(0.0, (horizon_y - 1.0).max(0.0));
+            bird.flap_timer += frames;
+            if bird.flap_timer > flap_interval {
                 bird.flap_state = !bird.flap_state;
-                bird.flap_timer = 0;
+                bird.flap_timer = 0.0;
+            }
+        }
+
+        let width = terminal_width as f32;
+        self.birds.retain(|b| b.x > -4.0 && b.x < width + 4.0);
+
+        let flying = self.birds.iter().any(|b| b.flight == Flight::Flying);
+        if !flying
+            && !sheltering
+            && self.birds.len() < MAX_BIRDS
+            && rng.random::<f32>() < chance_over(0.01 * self.level, frames)
+        {
+            self.spawn_flock(cruise_y, rng);
+        }
+    }
+
+    /// Row flocks keep to: high up in fair weather, close to the ground
+    /// before rain. Bats hunt around the rooftops.
+    fn cruise_row(&self) -> f32 {
+        let horizon_y = self.horizon_y.min(self.terminal_height) as f32;
+        if self.dusk {
+            (horizon_y - 12.0).max(horizon_y * 0.5)
+        } else if self.rain_expected {
+            (horizon_y - 4.0).max(0.0)
+        } else {
+            self.terminal_height as f32 / 6.0
+        }
+    }
+
+    /// Boids rules for the flying birds, plus a pull toward the cruising row
+    /// and the flock's direction of travel.
+    fn steer(&mut self, cruise_y: f32, frames: f32, rng: &mut impl Rng) {
+        let steering: Vec<(f32, f32)> = self
+            .birds
+            .iter()
+            .enumerate()
+            .map(|(i, bird)| {
+                if bird.flight != Flight::Flying {
+                    return (0.0, 0.0);
+                }
+
+                let (mut sep_x, mut sep_y) = (0.0, 0.0);
+                let (mut sum_vx, mut sum_vy, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0, 0.0);
+                let mut neighbors = 0;
+                for (j, other) in self.birds.iter().enumerate() {
+                    if i == j || other.flight != Flight::Flying || other.species != bird.species {
+                        continue;
+                    }
+                    // Cells are twice as tall as wide
+                    let dx = bird.x - other.x;
+                    let dy = (bird.y - other.y) * 2.0;
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.1);
+                    if distance > NEIGHBOR_RADIUS {
+                        continue;
+                    }
+                    if distance < SEPARATION_RADIUS {
+                        sep_x += dx / (distance * distance);
+                        sep_y += dy / (distance * distance) / 2.0;
+                    }
+                    sum_vx += other.vx;
+                    sum_vy += other.vy;
+                    sum_x += other.x;
+                    sum_y += other.y;
+                    neighbors += 1;
+                }
+
+                let mut ax = sep_x * SEPARATION_WEIGHT;
+                let mut ay = sep_y * SEPARATION_WEIGHT;
+                if neighbors > 0 {
+                    let n = neighbors as f32;
+                    ax += (sum_vx / n - bird.vx) * ALIGNMENT_WEIGHT;
+                    ay += (sum_vy / n - bird.vy) * ALIGNMENT_WEIGHT;
+                    ax += (sum_x / n - bird.x) * COHESION_WEIGHT;
+                    ay += (sum_y / n - bird.y) * COHESION_WEIGHT;
+                }
+
+                let cruise = bird.species.cruise_speed();
+                ax += (bird.heading * cruise - bird.vx) * 0.05;
+                ay += (cruise_y - bird.y) * 0.002 - bird.vy * 0.05;
+
+                // Bats jink after insects
+                if bird.species == Species::Bat {
+                    ax += (rng.random::<f32>() - 0.5) * 0.2;
+                    ay += (rng.random::<f32>() - 0.5) * 0.2;
+                }
+                (ax, ay)
+            })
+            .collect();
+
+        for (bird, (ax, ay)) in self.birds.iter_mut().zip(steering) {
+            if bird.flight != Flight::Flying {
+                continue;
             }
-            bird.character = if bird.flap_state { 'v' } else { '-' };
+            bird.vx += ax * frames;
+            bird.vy += ay * frames;
+
+            let cruise = bird.species.cruise_speed();
+            let speed = (bird.vx * bird.vx + bird.vy * bird.vy).sqrt().max(0.001);
+            let clamped = speed.clamp(cruise * 0.5, cruise * 1.5);
+            bird.vx *= clamped / speed;
+            bird.vy *= clamped / speed;
         }
+    }
+
+    /// Sends some flying birds to a free perch: now and then to rest, and
+    /// all of them to the lowest perches when the wind is too strong.
+    fn choose_perches(&mut self, sheltering: bool, frames: f32, rng: &mut impl Rng) {
+        for i in 0..self.birds.len() {
+            let bird = &self.birds[i];
+            if bird.flight != Flight::Flying || bird.species == Species::Bat {
+                continue;
+            }
+            if !sheltering && rng.random::<f32>() >= chance_over(PERCH_CHANCE, frames) {
+                continue;
+            }
+
+            let taken: Vec<usize> = self.birds.iter().filter_map(Bird::perch).collect();
+            let (x, y) = (bird.x, bird.y);
+            let free = self
+                .perches
+                .iter()
+                .enumerate()
+                .filter(|(p, _)| !taken.contains(p));
+            let perch = if sheltering {
+                // Lowest first: walls and fences break the wind
+                free.max_by(|(_, a), (_, b)| {
+                    a.1.cmp(&b.1)
+                        .then(distance(**b, x, y).total_cmp(&distance(**a, x, y)))
+                })
+            } else {
+                free.min_by(|(_, a), (_, b)| distance(**a, x, y).total_cmp(&distance(**b, x, y)))
+            };
 
-        self.birds.retain(|b| b.x < terminal_width as f32);
-        if self.birds.len() < 3 && rng.random::<f32>() < 0.01 {
-            let y = (rng.random::<u16>() % (terminal_height / 3)) as f32;
-            let speed = 0.2 + (rng.random::<f32>() * 0.2);
+            if let Some((perch, _)) = perch {
+                self.birds[i].flight = Flight::Landing(perch);
+            }
+        }
+    }
+
+    fn spawn_flock(&mut self, cruise_y: f32, rng: &mut impl Rng) {
+        let species = if self.dusk {
+            Species::Bat
+        } else {
+            Species::random_bird(rng)
+        };
+        let heading = if rng.random::<bool>() { 1.0 } else { -1.0 };
+        let start_x = if heading > 0.0 {
+            -2.0
+        } else {
+            self.terminal_width as f32 + 2.0
+        };
+        let spread = (self.terminal_height as f32 / 8.0).max(1.0);
+        let start_y = (cruise_y + rng.random_range(-spread..=spread)).max(0.0);
+        let cruise = species.cruise_speed();
+
+        let count = rng
+            .random_range(species.flock_size())
+            .min(MAX_BIRDS - self.birds.len());
+        for i in 0..count {
             self.birds.push(Bird {
-                x: 0.0,
-                y,
-                speed,
-                character: 'v',
-                flap_state: true,
-                flap_timer: 0,
+                x: start_x - heading * (i as f32 * 2.0 + rng.random::<f32>() * 2.0),
+                y: (start_y + rng.random_range(-1.5..1.5)).max(0.0),
+                vx: heading * cruise,
+                vy: 0.0,
+                heading,
+                species,
+                flight: Flight::Flying,
+                flap_state: rng.random::<bool>(),
+                flap_timer: rng.random_range(0.0..5.0),
             });
         }
     }
 
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         for bird in &self.birds {
-            let x = bird.x as u16;
-            let y = bird.y as u16;
-            if x < self.terminal_width && y < self.terminal_height {
-                renderer.render_char(x, y, bird.character, Color::Yellow)?;
+            let sprite = match bird.flight {
+                Flight::Perched { .. } => bird.species.sitting(),
+                _ => bird.species.sprites()[usize::from(!bird.flap_state)],
+            };
+            let left = bird.x.round() as i32 - sprite.len() as i32 / 2;
+            let y = bird.y.round() as u16;
+            if y >= self.terminal_height {
+                continue;
+            }
+            for (i, ch) in sprite.chars().enumerate() {
+                let x = left + i as i32;
+                if x >= 0 && x < self.terminal_width as i32 {
+                    renderer.render_char(x as u16, y, ch, bird.species.color())?;
+                }
             }
         }
         Ok(())
     }
 }
+
+fn distance((px, py): (u16, u16), x: f32, y: f32) -> f32 {
+    let dx = px as f32 - x;
+    let dy = (py as f32 - y) * 2.0;
+    (dx * dx + dy * dy).sqrt()
+}
+
+impl Effect for BirdSystem {
+    fn name(&self) -> &'static str {
+        "birds"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.precipitating() && (ctx.conditions.is_day || is_dusk(ctx.sun))
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    /// Birds on the wing finish their flight; perched ones are gone with
+    /// the light.
+    fn is_idle(&self) -> bool {
+        self.birds.iter().all(|bird| bird.flight != Flight::Flying)
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        let rain_expected = ctx.weather.is_some_and(|weather| {
+            weather
+                .hourly_forecast
+                .iter()
+                .flatten()
+                .take(RAIN_LOOKAHEAD_HOURS)
+                .any(|hour| hour.condition.is_raining() || hour.precipitation_probability >= 60.0)
+        });
+        self.set_rain_expected(rain_expected);
+        self.set_dusk(is_dusk(ctx.sun));
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
+
+/// Bats come out in the evening twilight.
+fn is_dusk(sun: SkyPosition) -> bool {
+    sun.azimuth > 180.0 && (DUSK_SUN_ELEVATION..3.0).contains(&sun.elevation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::REFERENCE_FPS;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_birds_shelter_on_lowest_perch_in_a_gale() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut geometry = SceneGeometry::new(80, 30, 7);
+        geometry.perches = vec![(20, 10), (60, 25)];
+
+        let mut birds = BirdSystem::new(80, 37);
+        birds.set_geometry(&geometry);
+        birds.spawn_flock(8.0, &mut rng);
+        birds.birds.truncate(1);
+        birds.birds[0].x = 30.0;
+        birds.set_wind(Wind::new(18.0, 270.0));
+
+        let frame = Duration::from_secs_f32(1.0 / REFERENCE_FPS);
+        for _ in 0..400 {
+            birds.update(frame, 80, 37, &mut rng);
+        }
+
+        assert_eq!(birds.birds.len(), 1);
+        assert!(matches!(
+            birds.birds[0].flight,
+            Flight::Perched { perch: 1, .. }
+        ));
+        assert_eq!((birds.birds[0].x, birds.birds[0].y), (60.0, 25.0));
+    }
+}
@@ -1,36 +1,42 @@
+use super::Wind;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::reference_frames;
 use crate::render::TerminalRenderer;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
 
 const MAX_PARTICLES: usize = 200;
 
 struct SmokeParticle {
     x: f32,
     y: f32,
-    age: u32,
-    max_age: u32,
+    age: f32,
+    max_age: f32,
     drift: f32,
 }
 
 impl SmokeParticle {
     fn new(chimney_x: u16, chimney_y: u16, rng: &mut impl Rng) -> Self {
         let drift = (rng.random::<f32>() - 0.5) * 0.15;
-        let max_age = 30 + (rng.random::<u32>() % 15);
+        let max_age = 30.0 + (rng.random::<u32>() % 15) as f32;
 
         Self {
             x: chimney_x as f32 + (rng.random::<f32>() - 0.5) * 2.0,
             y: chimney_y as f32,
-            age: 0,
+            age: 0.0,
             max_age,
             drift,
         }
     }
 
-    fn update(&mut self) {
-        self.age += 1;
-        self.y -= 0.2;
-        self.x += self.drift;
+    fn update(&mut self, flow: f32, frames: f32) {
+        self.age += frames;
+        // Wind flattens the plume: older puffs rise less and bend further downwind
+        let bend = flow * 0.04 * (0.3 + self.age / self.max_age);
+        self.y -= 0.2 / (1.0 + flow.abs() / 8.0) * frames;
+        self.x += (self.drift + bend) * frames;
     }
 
     fn is_alive(&self) -> bool {
@@ -38,7 +44,7 @@ impl SmokeParticle {
     }
 
     fn get_color(&self) -> Color {
-        let life_ratio = self.age as f32 / self.max_age as f32;
+        let life_ratio = self.age / self.max_age;
         if life_ratio < 0.3 {
             Color::White
         } else if life_ratio < 0.6 {
@@ -51,31 +57,52 @@ impl SmokeParticle {
 
 pub struct ChimneySmoke {
     particles: Vec<SmokeParticle>,
-    spawn_counter: u32,
-    spawn_rate: u32,
+    spawn_counter: f32,
+    spawn_rate: f32,
+    wind: Wind,
+    /// Only a lit fireplace makes smoke; what is already out drifts away.
+    burning: bool,
 }
 
 impl ChimneySmoke {
     pub fn new() -> Self {
         Self {
             particles: Vec::with_capacity(MAX_PARTICLES),
-            spawn_counter: 0,
-            spawn_rate: 8,
+            spawn_counter: 0.0,
+            spawn_rate: 8.0,
+            wind: Wind::default(),
+            burning: false,
         }
     }
 
-    pub fn update(&mut self, chimney_x: u16, chimney_y: u16, rng: &mut impl Rng) {
+    pub fn set_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
+    pub fn set_burning(&mut self, burning: bool) {
+        self.burning = burning;
+    }
+
+    pub fn update(&mut self, dt: Duration, chimney_x: u16, chimney_y: u16, rng: &mut impl Rng) {
+        let frames = reference_frames(dt);
+        let flow = self.wind.horizontal();
         for particle in &mut self.particles {
-            particle.update();
+            particle.update(flow, frames);
         }
 
         self.particles.retain(|p| p.is_alive() && p.y >= 0.0);
 
-        self.spawn_counter += 1;
-        if self.spawn_counter >= self.spawn_rate && self.particles.len() < MAX_PARTICLES {
-            self.spawn_counter = 0;
-            self.particles
-                .push(SmokeParticle::new(chimney_x, chimney_y, rng));
+        self.spawn_counter += frames;
+        if !self.burning {
+            self.spawn_counter = self.spawn_counter.min(self.spawn_rate);
+            return;
+        }
+        while self.spawn_counter >= self.spawn_rate {
+            self.spawn_counter -= self.spawn_rate;
+            if self.particles.len() < MAX_PARTICLES {
+                self.particles
+                    .push(SmokeParticle::new(chimney_x, chimney_y, rng));
+            }
         }
     }
 
@@ -85,7 +112,7 @@ impl ChimneySmoke {
             let y = particle.y as i16;
 
             if x >= 0 && y >= 0 {
-                let display_char = match particle.age {
+                let display_char = match particle.age as u32 {
                     0..=6 => 'o',
                     7..=14 => '.',
                     15..=25 => '~',
@@ -104,3 +131,33 @@ impl Default for ChimneySmoke {
         Self::new()
     }
 }
+
+impl Effect for ChimneySmoke {
+    fn name(&self) -> &'static str {
+        "chimney_smoke"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Scene
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.conditions.is_raining
+            && !ctx.conditions.is_thunderstorm
+            && ctx.geometry.chimney.is_some()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        if let Some((chimney_x, chimney_y)) = ctx.geometry.chimney {
+            self.update(dt, chimney_x, chimney_y, &mut rng);
+        }
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+}
@@ -1,94 +1,229 @@
+use super::Wind;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::weather::WeatherData;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 static CLOUD_SHAPES: OnceLock<Vec<Vec<String>>> = OnceLock::new();
+static LARGE_CLOUD_SHAPES: OnceLock<Vec<Vec<String>>> = OnceLock::new();
+
+/// Cloud cover in percent, split by altitude as reported by Open-Meteo.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CloudCover {
+    pub total: f32,
+    pub low: f32,
+    pub mid: f32,
+    pub high: f32,
+}
+
+impl CloudCover {
+    pub fn from_weather(weather: &WeatherData) -> Self {
+        match (
+            weather.cloud_cover_low,
+            weather.cloud_cover_mid,
+            weather.cloud_cover_high,
+        ) {
+            (Some(low), Some(mid), Some(high)) => Self {
+                total: weather.cloud_cover.clamp(0.0, 100.0) as f32,
+                low: low.clamp(0.0, 100.0) as f32,
+                mid: mid.clamp(0.0, 100.0) as f32,
+                high: high.clamp(0.0, 100.0) as f32,
+            },
+            _ => Self::estimated(weather.cloud_cover as f32),
+        }
+    }
+
+    /// Splits a bare total into layers when the provider doesn't report them.
+    /// Heavier skies are increasingly a mid-level sheet, so the stratus share
+    /// ramps from 30% of the total at half cover to all of it at 90%.
+    pub fn estimated(total: f32) -> Self {
+        let total = total.clamp(0.0, 100.0);
+        let overcast = ((total - 50.0) / 40.0).clamp(0.0, 1.0);
+        Self {
+            total,
+            low: total * 0.7,
+            mid: total * (0.3 + 0.7 * overcast),
+            high: total * 0.4,
+        }
+    }
+
+    fn for_layer(&self, layer: CloudLayer) -> f32 {
+        match layer {
+            CloudLayer::Cumulus => self.low,
+            CloudLayer::Stratus => self.mid,
+            CloudLayer::Cirrus => self.high,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CloudLayer {
+    Cumulus,
+    Stratus,
+    Cirrus,
+}
+
+impl CloudLayer {
+    const ALL: [CloudLayer; 3] = [CloudLayer::Cirrus, CloudLayer::Stratus, CloudLayer::Cumulus];
+
+    /// Vertical band (as fractions of the terminal height) the layer lives in.
+    fn band(&self) -> (f32, f32) {
+        match self {
+            CloudLayer::Cirrus => (0.0, 0.12),
+            CloudLayer::Stratus => (0.08, 0.25),
+            CloudLayer::Cumulus => (0.15, 0.33),
+        }
+    }
+
+    /// Higher clouds ride faster winds aloft.
+    fn wind_factor(&self) -> f32 {
+        match self {
+            CloudLayer::Cirrus => 0.08,
+            CloudLayer::Stratus => 0.03,
+            CloudLayer::Cumulus => 0.05,
+        }
+    }
+
+    fn speed_range(&self) -> (f32, f32) {
+        match self {
+            CloudLayer::Cirrus => (0.18, 0.28),
+            CloudLayer::Stratus => (0.03, 0.06),
+            CloudLayer::Cumulus => (0.06, 0.14),
+        }
+    }
+
+    fn max_count(&self, cover: f32, width: u16) -> usize {
+        let fraction = cover / 100.0;
+        let count = match self {
+            CloudLayer::Cumulus => (width as f32 / 16.0) * fraction,
+            CloudLayer::Stratus if cover < 20.0 => 0.0,
+            CloudLayer::Stratus => 4.0 * fraction,
+            CloudLayer::Cirrus => (width as f32 / 25.0) * fraction,
+        };
+        if cover < 5.0 {
+            0
+        } else {
+            count.ceil() as usize
+        }
+    }
+}
 
 struct Cloud {
     x: f32,
     y: f32,
     speed: f32,
     shape: Vec<String>,
-    color: Color,
+    layer: CloudLayer,
+}
+
+impl Cloud {
+    fn width(&self) -> f32 {
+        self.shape
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0) as f32
+    }
 }
 
 pub struct CloudSystem {
     clouds: Vec<Cloud>,
+    cover: CloudCover,
+    precipitating: bool,
+    wind: Wind,
+    needs_fill: bool,
+    /// A report has set the cover, so later changes drift in rather than
+    /// filling the sky at once.
+    reported: bool,
     terminal_width: u16,
     terminal_height: u16,
 }
 
-impl CloudSystem {
-    pub fn set_cloud_color(&mut self, is_clear: bool) {
-        let color = if is_clear {
-            Color::White
-        } else {
-            Color::DarkGrey
-        };
-
-        for cloud in &mut self.clouds {
-            cloud.color = color;
-        }
-    }
-}
-
 impl CloudSystem {
     pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let mut rng = rand::rng();
-        // Add a few initial clouds
-        let count = std::cmp::max(1, terminal_width / 20);
-
-        let max_capacity = (terminal_width / 20) as usize;
-        let mut clouds = Vec::with_capacity(max_capacity);
-
-        for _ in 0..count {
-            clouds.push(Self::create_random_cloud(
-                terminal_width,
-                terminal_height,
-                true,
-                Color::White,
-                &mut rng,
-            ));
-        }
+        let max_capacity = (terminal_width / 10) as usize;
 
         Self {
-            clouds,
+            clouds: Vec::with_capacity(max_capacity),
+            cover: CloudCover::default(),
+            precipitating: false,
+            wind: Wind::default(),
+            needs_fill: false,
+            reported: false,
             terminal_width,
             terminal_height,
         }
     }
 
-    fn create_random_cloud(
-        width: u16,
-        height: u16,
-        random_x: bool,
-        color: Color,
-        rng: &mut impl Rng,
-    ) -> Cloud {
-        let shapes = CLOUD_SHAPES.get_or_init(Self::create_cloud_shapes);
+    pub fn set_cover(&mut self, cover: CloudCover) {
+        if cover != self.cover {
+            self.cover = cover;
+            self.needs_fill = !self.reported;
+        }
+        self.reported = true;
+    }
 
-        let shape_idx = (rng.random::<u32>() as usize) % shapes.len();
-        let shape = shapes[shape_idx].clone();
+    pub fn set_precipitating(&mut self, precipitating: bool) {
+        self.precipitating = precipitating;
+    }
+
+    pub fn set_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
+    /// Drift direction: calm air keeps the familiar left-to-right drift.
+    fn drift_sign(&self) -> f32 {
+        let flow = self.wind.horizontal();
+        if flow.abs() < 0.5 { 1.0 } else { flow.signum() }
+    }
+
+    fn create_cloud(&self, layer: CloudLayer, random_x: bool, rng: &mut impl Rng) -> Cloud {
+        let cover = self.cover.for_layer(layer);
+        let shape = match layer {
+            CloudLayer::Cumulus => {
+                let shapes = if cover >= 50.0 {
+                    LARGE_CLOUD_SHAPES.get_or_init(Self::create_large_cloud_shapes)
+                } else {
+                    CLOUD_SHAPES.get_or_init(Self::create_cloud_shapes)
+                };
+                shapes[(rng.random::<u32>() as usize) % shapes.len()].clone()
+            }
+            CloudLayer::Stratus => {
+                let max_len = (self.terminal_width as f32 * 0.6 * cover / 100.0) as usize;
+                let len = 16 + (rng.random::<u32>() as usize) % max_len.max(1);
+                Self::create_stratus_bank(len)
+            }
+            CloudLayer::Cirrus => Self::create_cirrus_streak(rng),
+        };
 
-        let y_range = height / 3;
-        let y = (rng.random::<u16>() % std::cmp::max(1, y_range)) as f32;
+        let (band_top, band_bottom) = layer.band();
+        let top = self.terminal_height as f32 * band_top;
+        let bottom = (self.terminal_height as f32 * band_bottom).max(top + 1.0);
+        let y = (top + rng.random::<f32>() * (bottom - top)).floor();
 
+        let width = shape.iter().map(|l| l.chars().count()).max().unwrap_or(0) as f32;
         let x = if random_x {
-            (rng.random::<u16>() % width) as f32
+            rng.random::<f32>() * (self.terminal_width as f32 + width) - width
+        } else if self.drift_sign() > 0.0 {
+            -width
         } else {
-            -(shape[0].len() as f32)
+            self.terminal_width as f32 - 1.0
         };
 
-        let speed = 0.05 + (rng.random::<f32>() * 0.1);
+        let (min_speed, max_speed) = layer.speed_range();
+        let speed = min_speed + rng.random::<f32>() * (max_speed - min_speed);
 
         Cloud {
             x,
             y,
             speed,
             shape,
-            color,
+            layer,
         }
     }
 
@@ -121,58 +256,208 @@ impl CloudSystem {
         shapes.to_vec()
     }
 
+    fn create_large_cloud_shapes() -> Vec<Vec<String>> {
+        let shapes = [
+            vec![
+                "        .--.    .-.       ".to_string(),
+                "     .-(    ).-(   )-.    ".to_string(),
+                "   .(    .      .     ).  ".to_string(),
+                "  (    .     .      .    )".to_string(),
+                "   `-.___.-.___.--.___.-' ".to_string(),
+            ],
+            vec![
+                "       _  _    _         ".to_string(),
+                "    .-( `   )-( )_.      ".to_string(),
+                "   (    .       `  )_    ".to_string(),
+                "  (  .      .        `). ".to_string(),
+                "   `-.__.(___.-.___.___) ".to_string(),
+            ],
+        ];
+
+        shapes.to_vec()
+    }
+
+    fn create_stratus_bank(len: usize) -> Vec<String> {
+        vec![
+            format!(" .{}. ", "-~".repeat(len / 2)),
+            format!("({})", "_".repeat(len / 2 * 2 + 2)),
+        ]
+    }
+
+    fn create_cirrus_streak(rng: &mut impl Rng) -> Vec<String> {
+        let len = 8 + (rng.random::<u32>() as usize) % 14;
+        let strokes = ['-', '~', '-', ' ', '\''];
+        let line: String = (0..len)
+            .map(|i| {
+                if i == 0 || i == len - 1 {
+                    '-'
+                } else {
+                    strokes[(rng.random::<u32>() as usize) % strokes.len()]
+                }
+            })
+            .collect();
+        vec![line]
+    }
+
+    fn layer_color(&self, layer: CloudLayer) -> Color {
+        let cover = self.cover.for_layer(layer);
+        match layer {
+            CloudLayer::Cirrus => {
+                if self.precipitating {
+                    Color::Grey
+                } else {
+                    Color::White
+                }
+            }
+            _ if self.precipitating || self.cover.total >= 90.0 => Color::DarkGrey,
+            _ if cover < 35.0 => Color::White,
+            _ if cover < 70.0 => Color::Grey,
+            _ => Color::DarkGrey,
+        }
+    }
+
+    /// Thin layers let the sky show through; thick ones hide it.
+    fn is_opaque(&self, layer: CloudLayer) -> bool {
+        layer != CloudLayer::Cirrus && self.cover.for_layer(layer) >= 50.0
+    }
+
     pub fn update(
         &mut self,
+        dt: Duration,
         terminal_width: u16,
         terminal_height: u16,
-        is_clear: bool,
-        cloud_color: Color,
         rng: &mut impl Rng,
     ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let frames = reference_frames(dt);
+        let sign = self.drift_sign();
+        let flow = self.wind.horizontal().abs();
         for cloud in &mut self.clouds {
-            cloud.x += cloud.speed;
+            cloud.x += sign * (cloud.speed + flow * cloud.layer.wind_factor()) * frames;
         }
 
-        self.clouds.retain(|c| c.x < terminal_width as f32);
+        self.clouds
+            .retain(|c| c.x < terminal_width as f32 && c.x > -c.width() - 1.0);
 
-        let max_clouds = if is_clear {
-            (terminal_width / 40) as usize
-        } else {
-            (terminal_width / 20) as usize
-        };
+        for layer in CloudLayer::ALL {
+            let cover = self.cover.for_layer(layer);
+            let max_clouds = layer.max_count(cover, terminal_width);
+            let mut count = self.clouds.iter().filter(|c| c.layer == layer).count();
 
-        let spawn_chance = if is_clear { 0.002 } else { 0.005 };
+            if count > max_clouds {
+                // Cover dropped: let the surplus drift off rather than popping out
+                for cloud in self.clouds.iter_mut().filter(|c| c.layer == layer) {
+                    if count <= max_clouds {
+                        break;
+                    }
+                    cloud.speed = cloud.speed.max(0.3);
+                    count -= 1;
+                }
+                continue;
+            }
 
-        if self.clouds.len() < max_clouds && rng.random::<f32>() < spawn_chance {
-            self.clouds.push(Self::create_random_cloud(
-                terminal_width,
-                terminal_height,
-                false,
-                cloud_color,
-                rng,
-            ));
+            if self.needs_fill {
+                while count < max_clouds {
+                    let cloud = self.create_cloud(layer, true, rng);
+                    self.clouds.push(cloud);
+                    count += 1;
+                }
+            } else {
+                let spawn_chance = chance_over(0.002 + 0.008 * cover / 100.0, frames);
+                if count < max_clouds && rng.random::<f32>() < spawn_chance {
+                    let cloud = self.create_cloud(layer, false, rng);
+                    self.clouds.push(cloud);
+                }
+            }
         }
+
+        self.needs_fill = false;
     }
 
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
-        for cloud in &self.clouds {
-            for (i, line) in cloud.shape.iter().enumerate() {
-                let y = cloud.y as i16 + i as i16;
-                let x = cloud.x as i16;
-
-                if y >= 0 && y < self.terminal_height as i16 {
-                    renderer.render_line_colored(
-                        std::cmp::max(0, x) as u16,
-                        y as u16,
-                        line,
-                        cloud.color,
-                    )?;
+        for layer in CloudLayer::ALL {
+            let color = self.layer_color(layer);
+            let opaque = self.is_opaque(layer);
+
+            for cloud in self.clouds.iter().filter(|c| c.layer == layer) {
+                for (i, line) in cloud.shape.iter().enumerate() {
+                    let y = cloud.y as i16 + i as i16;
+                    if y < 0 || y >= self.terminal_height as i16 {
+                        continue;
+                    }
+
+                    for (j, ch) in line.chars().enumerate() {
+                        let x = cloud.x as i16 + j as i16;
+                        if x < 0 || x >= self.terminal_width as i16 {
+                            continue;
+                        }
+                        if ch != ' ' || opaque {
+                            renderer.render_char(x as u16, y as u16, ch, color)?;
+                        }
+                    }
                 }
             }
         }
         Ok(())
     }
 }
+
+impl Effect for CloudSystem {
+    fn name(&self) -> &'static str {
+        "clouds"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.set_precipitating(ctx.precipitating());
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimated_layers_change_smoothly_with_total_cover() {
+        assert_eq!(CloudCover::estimated(40.0).mid, 12.0);
+        assert_eq!(CloudCover::estimated(95.0).mid, 95.0);
+
+        let mids: Vec<f32> = (0..=1000)
+            .map(|tenths| CloudCover::estimated(tenths as f32 / 10.0).mid)
+            .collect();
+        assert!(
+            mids.windows(2)
+                .all(|pair| (0.0..0.5).contains(&(pair[1] - pair[0])))
+        );
+    }
+
+    #[test]
+    fn test_only_the_first_report_fills_the_sky() {
+        let mut rng = rand::rng();
+        let mut clouds = CloudSystem::new(120, 40);
+
+        clouds.set_cover(CloudCover::estimated(40.0));
+        clouds.update(Duration::ZERO, 120, 40, &mut rng);
+        let filled = clouds.clouds.len();
+        assert!(filled > 0);
+
+        // An overcast update has to drift in from upwind
+        clouds.set_cover(CloudCover::estimated(100.0));
+        clouds.update(Duration::ZERO, 120, 40, &mut rng);
+        assert_eq!(clouds.clouds.len(), filled);
+    }
+}
@@ -0,0 +1,356 @@
+//! The common interface of everything drawn around the scene, and the
+//! ordered registry the animation manager keeps them in.
+
+use super::{Wind, animation_step, fade_toward};
+use crate::render::TerminalRenderer;
+use crate::scene::{SceneGeometry, Season};
+use crate::weather::{SkyPosition, WeatherConditions, WeatherData, WeatherLocation};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::any::Any;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Effects that can be switched off under `[effects]` in the config.
+pub const EFFECT_NAMES: &[&str] = &[
+    "aurora",
+    "stars",
+    "moon",
+    "ufo",
+    "fireflies",
+    "birds",
+    "sun",
+    "rainbow",
+    "sprites",
+    "clouds",
+    "fireworks",
+    "airplanes",
+    "chimney_smoke",
+    "street",
+    "rain",
+    "hail",
+    "thunderstorm",
+    "snow",
+    "fog",
+    "leaves",
+    "scripts",
+    "smog",
+];
+
+/// Where an effect is drawn relative to the scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectLayer {
+    /// Behind the landscape.
+    Sky,
+    /// Over the landscape, behind the street and the weather.
+    Scene,
+    /// In front of everything else.
+    Foreground,
+}
+
+/// Everything an effect may look at while updating and rendering a frame.
+pub struct EffectContext<'a> {
+    pub conditions: &'a WeatherConditions,
+    /// The latest report, once one has arrived.
+    pub weather: Option<&'a WeatherData>,
+    pub location: &'a WeatherLocation,
+    pub geometry: &'a SceneGeometry,
+    pub season: Season,
+    pub width: u16,
+    pub height: u16,
+    pub now: Instant,
+    pub utc: DateTime<Utc>,
+    /// When rain or a storm was last seen, in real time however fast the
+    /// animation runs.
+    pub last_rain: Option<Instant>,
+    /// Where the sun is for the location right now.
+    pub sun: SkyPosition,
+    pub show_sun: bool,
+    pub show_fireflies: bool,
+    pub show_smog: bool,
+    /// How fast animation time runs against real time.
+    pub speed_multiplier: f32,
+}
+
+impl EffectContext<'_> {
+    /// Rain, storms or snow are falling.
+    pub fn precipitating(&self) -> bool {
+        self.conditions.is_raining || self.conditions.is_thunderstorm || self.conditions.is_snowing
+    }
+}
+
+pub trait Effect: AsAny + 'static {
+    /// The key that switches the effect under `[effects]`.
+    fn name(&self) -> &'static str;
+
+    fn layer(&self) -> EffectLayer;
+
+    /// Whether the effect runs at all this frame.
+    fn is_active(&self, _ctx: &EffectContext) -> bool {
+        true
+    }
+
+    /// How long the effect takes to set in or die away when it becomes
+    /// active or inactive. Zero switches it at once.
+    fn fade_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// How far the effect has faded in, from 0 to 1, set before each update.
+    fn set_level(&mut self, _level: f32) {}
+
+    /// Nothing is left on screen, so a faded-out effect can stop running.
+    fn is_idle(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, rng: &mut dyn Rng);
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()>;
+
+    fn set_wind(&mut self, _wind: Wind) {}
+
+    fn set_geometry(&mut self, _geometry: &SceneGeometry) {}
+}
+
+/// Lets the registry hand out effects by their concrete type.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+struct Entry {
+    effect: Box<dyn Effect>,
+    enabled: bool,
+    /// How far the effect has faded in; unset until the first frame, which
+    /// starts every effect where the conditions put it.
+    level: Option<f32>,
+    /// When the effect last ran, cleared while it is inactive so it does
+    /// not catch up on time it sat out.
+    last_update: Option<Instant>,
+}
+
+impl Entry {
+    /// Fades the effect toward `active` and returns the animation time to
+    /// advance it by, or `None` while it is faded out and idle. Switching
+    /// an effect off in the config hides it at once.
+    fn advance(&mut self, active: bool, now: Instant, speed_multiplier: f32) -> Option<Duration> {
+        let target = if self.enabled && active { 1.0 } else { 0.0 };
+        let elapsed = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        let dt = animation_step(elapsed, speed_multiplier);
+
+        let level = match self.level {
+            Some(level) if self.enabled => fade_toward(level, target, dt, self.effect.fade_time()),
+            _ => target,
+        };
+        self.level = Some(level);
+
+        if level == 0.0 && target == 0.0 && (!self.enabled || self.effect.is_idle()) {
+            self.last_update = None;
+            return None;
+        }
+        self.last_update = Some(now);
+        self.effect.set_level(level);
+        Some(dt)
+    }
+}
+
+/// Effects in drawing order.
+#[derive(Default)]
+pub struct EffectRegistry {
+    entries: Vec<Entry>,
+}
+
+impl EffectRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an effect drawn after those already registered on its layer.
+    pub fn register(&mut self, effect: impl Effect) {
+        self.entries.push(Entry {
+            effect: Box::new(effect),
+            enabled: true,
+            level: None,
+            last_update: None,
+        });
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        for entry in &mut self.entries {
+            if entry.effect.name() == name {
+                entry.enabled = enabled;
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|entry| entry.effect.name())
+    }
+
+    /// The first effect of type `T`.
+    pub fn get<T: Effect>(&self) -> Option<&T> {
+        self.entries
+            .iter()
+            .find_map(|entry| entry.effect.as_ref().as_any().downcast_ref())
+    }
+
+    /// Every effect of type `T`.
+    pub fn iter_mut<T: Effect>(&mut self) -> impl Iterator<Item = &mut T> {
+        self.entries
+            .iter_mut()
+            .filter_map(|entry| entry.effect.as_mut().as_any_mut().downcast_mut())
+    }
+
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(&mut dyn Effect)) {
+        for entry in &mut self.entries {
+            f(entry.effect.as_mut());
+        }
+    }
+
+    /// Updates and draws the effects on one layer that are active or still
+    /// fading out.
+    pub fn run(
+        &mut self,
+        layer: EffectLayer,
+        renderer: &mut TerminalRenderer,
+        ctx: &EffectContext,
+        rng: &mut dyn Rng,
+    ) -> io::Result<()> {
+        for entry in &mut self.entries {
+            if entry.effect.layer() != layer {
+                continue;
+            }
+            let active = entry.effect.is_active(ctx);
+            let Some(dt) = entry.advance(active, ctx.now, ctx.speed_multiplier) else {
+                continue;
+            };
+            entry.effect.update(dt, ctx, rng);
+            entry.effect.render(renderer, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        name: &'static str,
+    }
+
+    impl Effect for Counter {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn layer(&self) -> EffectLayer {
+            EffectLayer::Sky
+        }
+
+        fn update(&mut self, _dt: Duration, _ctx: &EffectContext, _rng: &mut dyn Rng) {}
+
+        fn render(&self, _renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registry_finds_and_switches_effects() {
+        let mut registry = EffectRegistry::new();
+        registry.register(Counter { name: "ufo" });
+        registry.register(Counter { name: "birds" });
+
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["ufo", "birds"]);
+        assert_eq!(registry.get::<Counter>().map(|c| c.name), Some("ufo"));
+        assert_eq!(registry.iter_mut::<Counter>().count(), 2);
+
+        registry.set_enabled("ufo", false);
+        let enabled: Vec<_> = registry
+            .entries
+            .iter()
+            .map(|entry| (entry.effect.name(), entry.enabled))
+            .collect();
+        assert_eq!(enabled, [("ufo", false), ("birds", true)]);
+    }
+
+    struct Drizzle {
+        level: f32,
+    }
+
+    impl Effect for Drizzle {
+        fn name(&self) -> &'static str {
+            "rain"
+        }
+
+        fn layer(&self) -> EffectLayer {
+            EffectLayer::Foreground
+        }
+
+        fn fade_time(&self) -> Duration {
+            Duration::from_secs(10)
+        }
+
+        fn set_level(&mut self, level: f32) {
+            self.level = level;
+        }
+
+        fn update(&mut self, _dt: Duration, _ctx: &EffectContext, _rng: &mut dyn Rng) {}
+
+        fn render(&self, _renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registry_fades_effects_in_and_out() {
+        let mut registry = EffectRegistry::new();
+        registry.register(Drizzle { level: 0.0 });
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let entry = &mut registry.entries[0];
+
+        // The first frame starts where the conditions are
+        assert_eq!(entry.advance(true, at(0), 1.0), Some(Duration::ZERO));
+        assert_eq!(entry.level, Some(1.0));
+
+        // Fading out over ten seconds, at double speed in five
+        assert!(entry.advance(false, at(200), 1.0).is_some());
+        assert!((entry.level.unwrap() - 0.98).abs() < 1e-4);
+        let mut millis = 200;
+        while entry.advance(false, at(millis + 200), 2.0).is_some() {
+            millis += 200;
+        }
+        assert_eq!(entry.level, Some(0.0));
+        assert_eq!(millis, 5_000);
+        assert_eq!(entry.advance(false, at(millis + 400), 1.0), None);
+
+        // Coming back starts from nothing
+        assert_eq!(
+            entry.advance(true, at(millis + 600), 1.0),
+            Some(Duration::ZERO)
+        );
+        assert!(entry.advance(true, at(millis + 800), 1.0).is_some());
+        assert!((entry.level.unwrap() - 0.02).abs() < 1e-4);
+
+        // Switched off in the config, it goes at once
+        registry.set_enabled("rain", false);
+        let entry = &mut registry.entries[0];
+        assert_eq!(entry.advance(true, at(millis + 1_000), 1.0), None);
+        assert_eq!(registry.get::<Drizzle>().map(|d| d.level), Some(0.02));
+    }
+}
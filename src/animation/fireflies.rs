@@ -1,7 +1,10 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{DAY_NIGHT_FADE_TIME, chance_over, reference_frames};
 use crate::render::TerminalRenderer;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
 
 struct Firefly {
     x: f32,
@@ -37,11 +40,11 @@ impl Firefly {
         }
     }
 
-    fn update(&mut self, terminal_width: u16, horizon_y: u16, rng: &mut impl Rng) {
-        self.x += self.vx;
-        self.y += self.vy;
+    fn update(&mut self, terminal_width: u16, horizon_y: u16, frames: f32, rng: &mut impl Rng) {
+        self.x += self.vx * frames;
+        self.y += self.vy * frames;
 
-        if rng.random::<f32>() < 0.02 {
+        if rng.random::<f32>() < chance_over(0.02, frames) {
             self.vx = (rng.random::<f32>() - 0.5) * 0.3;
             self.vy = (rng.random::<f32>() - 0.5) * 0.2;
         }
@@ -63,7 +66,7 @@ impl Firefly {
             self.vy = -self.vy.abs(); // Bounce up
         }
 
-        self.glow_phase += self.glow_speed;
+        self.glow_phase += self.glow_speed * frames;
         if self.glow_phase > std::f32::consts::PI * 2.0 {
             self.glow_phase -= std::f32::consts::PI * 2.0;
         }
@@ -113,6 +116,8 @@ pub struct FireflySystem {
     fireflies: Vec<Firefly>,
     terminal_width: u16,
     terminal_height: u16,
+    /// How far into their night the fireflies are, from 0 to 1.
+    level: f32,
 }
 
 impl FireflySystem {
@@ -123,11 +128,13 @@ impl FireflySystem {
             fireflies: Vec::with_capacity(fireflies_capacity),
             terminal_width,
             terminal_height,
+            level: 1.0,
         }
     }
 
     pub fn update(
         &mut self,
+        dt: Duration,
         terminal_width: u16,
         terminal_height: u16,
         horizon_y: u16,
@@ -136,12 +143,21 @@ impl FireflySystem {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let frames = reference_frames(dt);
         for firefly in &mut self.fireflies {
-            firefly.update(terminal_width, horizon_y, rng);
+            firefly.update(terminal_width, horizon_y, frames, rng);
         }
 
-        let target_count = std::cmp::max(3, terminal_width / 15) as usize;
-        if self.fireflies.len() < target_count && rng.random::<f32>() < 0.01 {
+        let target_count = (std::cmp::max(3, terminal_width / 15) as f32 * self.level) as usize;
+
+        // Surplus fireflies go out while they are dark rather than mid-glow
+        if self.fireflies.len() > target_count
+            && let Some(dark) = self.fireflies.iter().position(|f| !f.is_visible())
+        {
+            self.fireflies.swap_remove(dark);
+        }
+
+        if self.fireflies.len() < target_count && rng.random::<f32>() < chance_over(0.01, frames) {
             self.fireflies
                 .push(Firefly::new(terminal_width, horizon_y, rng));
         }
@@ -170,3 +186,37 @@ impl FireflySystem {
         Ok(())
     }
 }
+
+impl Effect for FireflySystem {
+    fn name(&self) -> &'static str {
+        "fireflies"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.conditions.is_day && ctx.show_fireflies
+    }
+
+    fn fade_time(&self) -> Duration {
+        DAY_NIGHT_FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.fireflies.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, ctx.geometry.horizon_y, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
@@ -0,0 +1,186 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
+use crate::render::TerminalRenderer;
+use crossterm::style::Color;
+use rand::prelude::*;
+use std::io;
+use std::time::Duration;
+
+const GRAVITY: f32 = 0.02;
+const MAX_ROCKETS: usize = 4;
+const SPARK_LIFE: f32 = 24.0;
+
+const BURST_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Yellow,
+    Color::Green,
+    Color::Cyan,
+    Color::Magenta,
+    Color::White,
+];
+
+struct Rocket {
+    x: f32,
+    y: f32,
+    speed: f32,
+    apex: f32,
+    color: Color,
+}
+
+struct Spark {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: f32,
+    color: Color,
+}
+
+pub struct FireworksSystem {
+    rockets: Vec<Rocket>,
+    sparks: Vec<Spark>,
+    active: bool,
+    terminal_width: u16,
+    terminal_height: u16,
+}
+
+impl FireworksSystem {
+    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
+        Self {
+            rockets: Vec::with_capacity(MAX_ROCKETS),
+            sparks: Vec::new(),
+            active: false,
+            terminal_width,
+            terminal_height,
+        }
+    }
+
+    /// Stops new launches when off; bursts already in the air finish.
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.active || !self.rockets.is_empty() || !self.sparks.is_empty()
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        horizon_y: u16,
+        rng: &mut impl Rng,
+    ) {
+        self.terminal_width = terminal_width;
+        self.terminal_height = terminal_height;
+
+        let frames = reference_frames(dt);
+        if self.active
+            && self.rockets.len() < MAX_ROCKETS
+            && horizon_y > 8
+            && rng.random::<f32>() < chance_over(0.04, frames)
+        {
+            let top = (horizon_y / 5).max(2) as f32;
+            self.rockets.push(Rocket {
+                x: rng.random_range(0.1..0.9) * terminal_width as f32,
+                y: horizon_y as f32,
+                speed: rng.random_range(0.5..0.8),
+                apex: rng.random_range(top..horizon_y as f32 * 0.55),
+                color: BURST_COLORS[rng.random_range(0..BURST_COLORS.len())],
+            });
+        }
+
+        let mut bursts = Vec::new();
+        self.rockets.retain_mut(|rocket| {
+            rocket.y -= rocket.speed * frames;
+            if rocket.y <= rocket.apex {
+                bursts.push((rocket.x, rocket.y, rocket.color));
+                false
+            } else {
+                true
+            }
+        });
+
+        for (x, y, color) in bursts {
+            let count = rng.random_range(12..20);
+            for i in 0..count {
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                let speed = rng.random_range(0.5..0.9);
+                self.sparks.push(Spark {
+                    x,
+                    y,
+                    // Cells are twice as tall as wide, so spread wider than high
+                    vx: angle.cos() * speed,
+                    vy: angle.sin() * speed * 0.5,
+                    age: 0.0,
+                    color,
+                });
+            }
+        }
+
+        let drag = 0.92_f32.powf(frames);
+        self.sparks.retain_mut(|spark| {
+            spark.x += spark.vx * frames;
+            spark.y += spark.vy * frames;
+            spark.vx *= drag;
+            spark.vy = spark.vy * drag + GRAVITY * frames;
+            spark.age += frames;
+            spark.age < SPARK_LIFE && spark.y < horizon_y as f32
+        });
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for rocket in &self.rockets {
+            self.plot(renderer, rocket.x, rocket.y, '|', Color::DarkYellow)?;
+            self.plot(renderer, rocket.x, rocket.y + 1.0, '.', Color::DarkGrey)?;
+        }
+
+        for spark in &self.sparks {
+            let (ch, color) = match spark.age as u8 {
+                0..=7 => ('*', spark.color),
+                8..=15 => ('+', spark.color),
+                _ => ('.', Color::DarkGrey),
+            };
+            self.plot(renderer, spark.x, spark.y, ch, color)?;
+        }
+        Ok(())
+    }
+
+    fn plot(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: f32,
+        y: f32,
+        ch: char,
+        color: Color,
+    ) -> io::Result<()> {
+        if x >= 0.0 && y >= 0.0 && x < self.terminal_width as f32 && y < self.terminal_height as f32
+        {
+            renderer.render_char(x as u16, y as u16, ch, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl Effect for FireworksSystem {
+    fn name(&self) -> &'static str {
+        "fireworks"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, _ctx: &EffectContext) -> bool {
+        self.is_running()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, ctx.geometry.horizon_y, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
@@ -1,9 +1,17 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::reference_frames;
 use crate::render::TerminalRenderer;
 use crate::weather::types::FogIntensity;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
+use std::time::Duration;
+
+/// Reach of a light's halo in the fog, in columns.
+const GLOW_RADIUS: f32 = 8.0;
+/// How long fog takes to thicken or lift.
+const FADE_TIME: Duration = Duration::from_secs(30);
 
 struct FogWisp {
     x: f32,
@@ -11,8 +19,8 @@ struct FogWisp {
     speed_x: f32,
     character: char,
     color: Color,
-    lifetime: u32,
-    max_lifetime: u32,
+    lifetime: f32,
+    max_lifetime: f32,
 }
 
 impl FogWisp {
@@ -43,14 +51,14 @@ impl FogWisp {
             speed_x: (rng.random::<f32>() - 0.5) * 0.15,
             character: chars[char_idx],
             color: colors[color_idx],
-            lifetime: 0,
-            max_lifetime: 100 + (rng.random::<u32>() % 200),
+            lifetime: 0.0,
+            max_lifetime: 100.0 + (rng.random::<u32>() % 200) as f32,
         }
     }
 
-    fn update(&mut self) {
-        self.x += self.speed_x;
-        self.lifetime += 1;
+    fn update(&mut self, frames: f32) {
+        self.x += self.speed_x * frames;
+        self.lifetime += frames;
     }
 
     fn is_alive(&self, terminal_width: u16) -> bool {
@@ -65,7 +73,9 @@ pub struct FogSystem {
     terminal_width: u16,
     terminal_height: u16,
     intensity: FogIntensity,
-    spawn_timer: u32,
+    spawn_timer: f32,
+    /// How thick the fog has grown, from 0 to 1.
+    level: f32,
 }
 
 impl FogSystem {
@@ -81,7 +91,8 @@ impl FogSystem {
             terminal_width,
             terminal_height,
             intensity,
-            spawn_timer: 0,
+            spawn_timer: 0.0,
+            level: 1.0,
         }
     }
 
@@ -89,45 +100,119 @@ impl FogSystem {
         self.intensity = intensity;
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let frames = reference_frames(dt);
         for wisp in &mut self.wisps {
-            wisp.update();
+            wisp.update(frames);
         }
 
         self.wisps.retain(|w| w.is_alive(terminal_width));
 
         let (target_multiplier, spawn_delay) = match self.intensity {
-            FogIntensity::Light => (0.3, 4),
-            FogIntensity::Medium => (0.6, 2),
-            FogIntensity::Heavy => (1.0, 1),
+            FogIntensity::Light => (0.3, 4.0),
+            FogIntensity::Medium => (0.6, 2.0),
+            FogIntensity::Heavy => (1.0, 1.0),
         };
-        let target_count = (terminal_width as f32 * target_multiplier) as usize;
+        let target_count = (terminal_width as f32 * target_multiplier * self.level) as usize;
 
-        self.spawn_timer += 1;
+        // Two wisps at a time, every `spawn_delay` frames
+        self.spawn_timer += frames;
         if self.spawn_timer >= spawn_delay && self.wisps.len() < target_count {
-            self.spawn_timer = 0;
-            for _ in 0..2 {
-                if self.wisps.len() < target_count {
-                    self.wisps
-                        .push_back(FogWisp::new(terminal_width, terminal_height, rng));
+            let bursts = (self.spawn_timer / spawn_delay) as usize;
+            self.spawn_timer %= spawn_delay;
+            for _ in 0..bursts * 2 {
+                if self.wisps.len() >= target_count {
+                    break;
                 }
+                self.wisps
+                    .push_back(FogWisp::new(terminal_width, terminal_height, rng));
             }
         }
     }
 
-    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+    /// Wisps near any of `lights` are lit up by them.
+    pub fn render(&self, renderer: &mut TerminalRenderer, lights: &[(u16, u16)]) -> io::Result<()> {
         for wisp in &self.wisps {
             let x = wisp.x as i16;
             let y = wisp.y as i16;
 
             if x >= 0 && x < self.terminal_width as i16 && y >= 0 && y < self.terminal_height as i16
             {
-                renderer.render_char(x as u16, y as u16, wisp.character, wisp.color)?;
+                let color = match glow(wisp.x, wisp.y, lights) {
+                    Some(strength) => Color::Rgb {
+                        r: (140.0 + strength * 100.0) as u8,
+                        g: (130.0 + strength * 70.0) as u8,
+                        b: (100.0 - strength * 40.0) as u8,
+                    },
+                    None => wisp.color,
+                };
+                renderer.render_char(x as u16, y as u16, wisp.character, color)?;
             }
         }
         Ok(())
     }
 }
+
+/// How strongly the nearest light shines on a point, from 0 at the edge of
+/// its halo to 1 right next to it. Cells are twice as tall as wide.
+fn glow(x: f32, y: f32, lights: &[(u16, u16)]) -> Option<f32> {
+    lights
+        .iter()
+        .map(|&(lx, ly)| {
+            let dx = x - lx as f32;
+            let dy = (y - ly as f32) * 2.0;
+            (dx * dx + dy * dy).sqrt()
+        })
+        .filter(|&distance| distance < GLOW_RADIUS)
+        .map(|distance| 1.0 - distance / GLOW_RADIUS)
+        .reduce(f32::max)
+}
+
+impl Effect for FogSystem {
+    fn name(&self) -> &'static str {
+        "fog"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.conditions.is_foggy
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.wisps.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        // Lamps and windows glow through the fog after dark
+        let lights = if ctx.conditions.is_day {
+            &[][..]
+        } else {
+            &ctx.geometry.lights
+        };
+        self.render(renderer, lights)
+    }
+}
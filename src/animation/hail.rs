@@ -0,0 +1,271 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{Wind, reference_frames};
+use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
+use crossterm::style::Color;
+use rand::prelude::*;
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+
+const GRAVITY: f32 = 0.08;
+const MAX_FALL_SPEED: f32 = 1.6;
+const MAX_BOUNCES: u8 = 2;
+const MAX_SETTLED: usize = 150;
+/// How long a hail shower takes to start or pass.
+const FADE_TIME: Duration = Duration::from_secs(5);
+
+struct Hailstone {
+    x: f32,
+    y: f32,
+    speed_x: f32,
+    speed_y: f32,
+    bounces: u8,
+    large: bool,
+    /// Rows into the ground band this stone lands at on open ground.
+    depth: u16,
+}
+
+/// A stone lying where it came to rest until it melts.
+struct SettledStone {
+    x: u16,
+    y: u16,
+    /// Frames until it melts.
+    timer: f32,
+}
+
+pub struct HailSystem {
+    stones: Vec<Hailstone>,
+    settled: VecDeque<SettledStone>,
+    terminal_width: u16,
+    terminal_height: u16,
+    wind_x: f32,
+    /// Stones owed from earlier updates too short to spawn a whole one.
+    spawn_budget: f32,
+    /// How hard the hail is coming down, from 0 to 1.
+    level: f32,
+    geometry: SceneGeometry,
+}
+
+impl HailSystem {
+    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
+        Self {
+            stones: Vec::with_capacity((terminal_width / 3) as usize),
+            settled: VecDeque::with_capacity(MAX_SETTLED),
+            terminal_width,
+            terminal_height,
+            wind_x: 0.0,
+            spawn_budget: 0.0,
+            level: 1.0,
+            geometry: SceneGeometry::flat(terminal_width, terminal_height),
+        }
+    }
+
+    pub fn set_wind(&mut self, wind: Wind) {
+        // Heavy stones drift far less than raindrops
+        self.wind_x = wind.horizontal() * 0.02;
+    }
+
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        if self.geometry != *geometry {
+            self.geometry = geometry.clone();
+        }
+    }
+
+    fn spawn_stone(&mut self, rng: &mut impl Rng) {
+        let width = self.terminal_width as f32;
+        let large = rng.random::<f32>() < 0.6;
+        let depth = if large {
+            rng.random_range(0..self.geometry.ground_height.max(1))
+        } else {
+            0
+        };
+
+        self.stones.push(Hailstone {
+            x: rng.random::<f32>() * width * 1.4 - width * 0.2,
+            y: 0.0,
+            speed_x: self.wind_x + rng.random::<f32>() * 0.1 - 0.05,
+            speed_y: 0.8 + rng.random::<f32>() * 0.4,
+            bounces: 0,
+            large,
+            depth,
+        });
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
+        self.terminal_width = terminal_width;
+        self.terminal_height = terminal_height;
+
+        let frames = reference_frames(dt);
+
+        let target_count = ((terminal_width / 3) as f32 * self.level) as usize;
+        self.spawn_budget += 3.0 * self.level * frames;
+        while self.spawn_budget >= 1.0 {
+            self.spawn_budget -= 1.0;
+            if self.stones.len() < target_count {
+                self.spawn_stone(rng);
+            }
+        }
+
+        let geometry = &self.geometry;
+        let settled = &mut self.settled;
+
+        self.stones.retain_mut(|stone| {
+            stone.speed_y = (stone.speed_y + GRAVITY * frames).min(MAX_FALL_SPEED);
+            stone.x += stone.speed_x * frames;
+            stone.y += stone.speed_y * frames;
+
+            let x = stone.x as i32;
+            let landing = geometry.landing_row(x, stone.depth);
+            if stone.y < landing as f32 {
+                return stone.x > -10.0 && stone.x < terminal_width as f32 + 10.0;
+            }
+
+            // Large stones bounce off whatever they hit before settling
+            if stone.large && stone.bounces < MAX_BOUNCES && stone.speed_y > 0.4 {
+                stone.y = landing as f32 - 1.0;
+                stone.speed_y = -stone.speed_y * 0.35;
+                stone.speed_x += rng.random::<f32>() * 0.6 - 0.3;
+                stone.bounces += 1;
+                return true;
+            }
+
+            if stone.large && x >= 0 && x < terminal_width as i32 {
+                let y = if geometry.is_open_ground(x) {
+                    landing
+                } else {
+                    landing.saturating_sub(1)
+                };
+                settled.push_back(SettledStone {
+                    x: x as u16,
+                    y,
+                    timer: rng.random_range(80.0..200.0),
+                });
+            }
+            false
+        });
+
+        while self.settled.len() > MAX_SETTLED {
+            self.settled.pop_front();
+        }
+
+        self.settled.retain_mut(|stone| {
+            stone.timer -= frames;
+            stone.timer > 0.0
+        });
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for stone in &self.settled {
+            if stone.x < self.terminal_width && stone.y < self.terminal_height {
+                // Melting stones shrink before they vanish
+                let (ch, color) = if stone.timer > 30.0 {
+                    ('o', Color::White)
+                } else {
+                    ('.', Color::Grey)
+                };
+                renderer.render_char(stone.x, stone.y, ch, color)?;
+            }
+        }
+
+        for stone in &self.stones {
+            let x = stone.x as i16;
+            let y = stone.y as i16;
+
+            if x >= 0 && x < self.terminal_width as i16 && y >= 0 && y < self.terminal_height as i16
+            {
+                let (ch, color) = if stone.large {
+                    ('o', Color::White)
+                } else {
+                    ('.', Color::Grey)
+                };
+                renderer.render_char(x as u16, y as u16, ch, color)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Effect for HailSystem {
+    fn name(&self) -> &'static str {
+        "hail"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.conditions.is_thunderstorm && ctx.conditions.is_hail
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.stones.is_empty() && self.settled.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_stone_bounces_then_settles_on_the_surface() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut hail = HailSystem::new(80, 24);
+        hail.level = 0.0;
+        hail.stones.push(Hailstone {
+            x: 40.0,
+            y: 0.0,
+            speed_x: 0.0,
+            speed_y: 1.0,
+            bounces: 0,
+            large: true,
+            depth: 0,
+        });
+
+        let mut rose = false;
+        for _ in 0..200 {
+            hail.update(Duration::from_millis(16), 80, 24, &mut rng);
+            rose |= hail.stones.iter().any(|stone| stone.speed_y < 0.0);
+            if hail.stones.is_empty() {
+                break;
+            }
+        }
+
+        assert!(rose);
+        assert!(hail.stones.is_empty());
+        assert_eq!(hail.settled.len(), 1);
+        assert_eq!(hail.settled[0].y, hail.geometry.horizon_y);
+    }
+}
@@ -1,7 +1,12 @@
+use super::Wind;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::reference_frames;
 use crate::render::TerminalRenderer;
+use crate::scene::Season;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
 
 struct Leaf {
     x: f32,
@@ -79,22 +84,26 @@ impl Leaf {
         }
     }
 
-    fn update(&mut self) {
-        self.y += self.fall_speed;
+    fn update(&mut self, flow: f32, frames: f32) {
+        self.y += self.fall_speed * frames;
+        self.x += flow * 0.05 * frames;
 
-        self.sway_phase += self.sway_speed;
+        self.sway_phase += self.sway_speed * frames;
         if self.sway_phase > std::f32::consts::PI * 2.0 {
             self.sway_phase -= std::f32::consts::PI * 2.0;
         }
 
         let sway_offset = self.sway_phase.sin() * self.sway_amplitude;
-        self.x += sway_offset * 0.1;
+        self.x += sway_offset * 0.1 * frames;
 
         self.rotation = ((self.sway_phase * 2.0).sin() * 4.0) as u8;
     }
 
-    fn is_offscreen(&self, terminal_height: u16) -> bool {
+    fn is_offscreen(&self, terminal_width: u16, terminal_height: u16) -> bool {
+        let margin = terminal_width as f32;
         self.y > terminal_height as f32
+            || self.x < -margin
+            || self.x > terminal_width as f32 + margin
     }
 
     fn get_character(&self) -> char {
@@ -121,48 +130,72 @@ impl Leaf {
 
 pub struct FallingLeaves {
     leaves: Vec<Leaf>,
-    spawn_counter: u32,
-    spawn_rate: u32,
+    spawn_counter: f32,
+    spawn_rate: f32,
+    wind: Wind,
+    /// Falls in any season, or never, instead of in autumn.
+    forced: Option<bool>,
     terminal_width: u16,
     terminal_height: u16,
 }
 
 impl FallingLeaves {
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let mut rng = rand::rng();
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
         let initial_count = std::cmp::max(5, terminal_width / 10);
 
         let max_capacity = std::cmp::max(10, terminal_width / 8) as usize;
         let mut leaves = Vec::with_capacity(max_capacity);
 
         for _ in 0..initial_count {
-            leaves.push(Leaf::new(terminal_width, false, &mut rng));
+            leaves.push(Leaf::new(terminal_width, false, rng));
         }
 
         Self {
             leaves,
-            spawn_counter: 0,
-            spawn_rate: 15,
+            spawn_counter: 0.0,
+            spawn_rate: 15.0,
+            wind: Wind::default(),
+            forced: None,
             terminal_width,
             terminal_height,
         }
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn set_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
+    pub fn set_forced(&mut self, forced: Option<bool>) {
+        self.forced = forced;
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let frames = reference_frames(dt);
+        let flow = self.wind.horizontal();
         for leaf in &mut self.leaves {
-            leaf.update();
+            leaf.update(flow, frames);
         }
 
-        self.leaves.retain(|l| !l.is_offscreen(terminal_height));
+        self.leaves
+            .retain(|l| !l.is_offscreen(terminal_width, terminal_height));
 
-        self.spawn_counter += 1;
-        if self.spawn_counter >= self.spawn_rate {
-            self.spawn_counter = 0;
+        self.spawn_counter += frames;
+        while self.spawn_counter >= self.spawn_rate {
+            self.spawn_counter -= self.spawn_rate;
             if rng.random::<f32>() < 0.7 {
-                self.leaves.push(Leaf::new(terminal_width, true, rng));
+                let mut leaf = Leaf::new(terminal_width, true, rng);
+                // Start upwind so gusts carry leaves across the scene, not off it
+                leaf.x -= flow * 0.05 * (terminal_height as f32 / leaf.fall_speed) * 0.5;
+                self.leaves.push(leaf);
             }
         }
 
@@ -185,3 +218,29 @@ impl FallingLeaves {
         Ok(())
     }
 }
+
+impl Effect for FallingLeaves {
+    fn name(&self) -> &'static str {
+        "leaves"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        self.forced.unwrap_or(ctx.season == Season::Autumn) && !ctx.precipitating()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+}
@@ -1,22 +1,167 @@
 pub mod airplanes;
+pub mod aurora;
 pub mod birds;
 pub mod chimney;
 pub mod clouds;
+pub mod effect;
 pub mod fireflies;
+pub mod fireworks;
 pub mod fog;
+pub mod hail;
 pub mod leaves;
 pub mod moon;
+pub mod rainbow;
 pub mod raindrops;
+#[cfg(feature = "scripting")]
+pub mod scripted;
 pub mod smog;
 pub mod snow;
+pub mod sprites;
+pub mod star_catalog;
 pub mod stars;
+pub mod street;
 pub mod sunny;
 pub mod thunderstorm;
 pub mod ufo;
 
-use crate::render::TerminalRenderer;
+use crate::render::ColorSupport;
+use crate::render::blend::mix;
+use crate::weather::{SkyPosition, beaufort_scale};
 use crossterm::style::Color;
-use std::io;
+use std::time::Duration;
+
+/// The main loop used to draw at this rate, and the systems' speeds, chances
+/// and timers are still given per frame at this rate.
+pub const REFERENCE_FPS: f32 = 60.0;
+
+/// Longest real time anything is advanced by in one go, so a stalled
+/// terminal does not send everything flying when it resumes.
+pub const MAX_STEP: Duration = Duration::from_millis(250);
+
+/// Animation time for `elapsed` real time, capped at [`MAX_STEP`] and run at
+/// `speed_multiplier`.
+pub fn animation_step(elapsed: Duration, speed_multiplier: f32) -> Duration {
+    elapsed.min(MAX_STEP).mul_f32(speed_multiplier)
+}
+
+/// How many reference frames `dt` covers.
+pub fn reference_frames(dt: Duration) -> f32 {
+    dt.as_secs_f32() * REFERENCE_FPS
+}
+
+/// The chance that something with probability `chance` per reference frame
+/// happens at least once over `frames` reference frames.
+pub fn chance_over(chance: f32, frames: f32) -> f32 {
+    1.0 - (1.0 - chance.clamp(0.0, 1.0)).powf(frames)
+}
+
+/// How long the sky, the scene and the things out by day or by night take
+/// to turn over at dawn and dusk.
+pub const DAY_NIGHT_FADE_TIME: Duration = Duration::from_secs(20);
+
+/// Moves `level` toward `target` by the share of `fade_time` that `dt`
+/// covers.
+pub fn fade_toward(level: f32, target: f32, dt: Duration, fade_time: Duration) -> f32 {
+    if fade_time.is_zero() {
+        return target;
+    }
+    let step = dt.as_secs_f32() / fade_time.as_secs_f32();
+    if level < target {
+        (level + step).min(target)
+    } else {
+        (level - step).max(target)
+    }
+}
+
+/// `color` darkened toward the black sky while an effect fades, from gone at
+/// 0 to unchanged at 1. Terminals limited to the named colours keep them
+/// rather than turning every shade white.
+pub fn faded(color: Color, level: f32, support: ColorSupport) -> Color {
+    if level >= 1.0 || matches!(support, ColorSupport::None | ColorSupport::Basic) {
+        return color;
+    }
+    mix(Color::Black, color, level).unwrap_or(color)
+}
+
+/// Surface wind as the scene sees it: speed in m/s and the meteorological
+/// direction the wind blows from.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Wind {
+    pub speed_ms: f32,
+    pub direction_deg: f32,
+}
+
+impl Wind {
+    pub fn new(speed_ms: f32, direction_deg: f32) -> Self {
+        Self {
+            speed_ms: speed_ms.max(0.0),
+            direction_deg,
+        }
+    }
+
+    pub fn speed_kmh(&self) -> f32 {
+        self.speed_ms * 3.6
+    }
+
+    /// Signed horizontal component in m/s; positive values blow to the right.
+    pub fn horizontal(&self) -> f32 {
+        -self.direction_deg.to_radians().sin() * self.speed_ms
+    }
+
+    pub fn beaufort(&self) -> u8 {
+        beaufort_scale(self.speed_ms as f64)
+    }
+}
+
+/// Sky views span half the horizon so things can be followed as they rise
+/// and set. At the usual 2:1 cell shape this reaches the zenith on most
+/// terminals.
+const SKY_FIELD_OF_VIEW_DEG: f64 = 180.0;
+
+/// Azimuth the sky view faces from `latitude`: toward the equator, where
+/// the sun, moon and planets cross the sky.
+pub fn sky_facing(latitude: f64) -> f64 {
+    if latitude < 0.0 { 0.0 } else { 180.0 }
+}
+
+/// Degrees of sky per column and per row across `width` columns. Terminal
+/// cells are about twice as tall as they are wide.
+pub fn sky_scale(width: u16) -> (f64, f64) {
+    let deg_per_col = SKY_FIELD_OF_VIEW_DEG / width.max(1) as f64;
+    (deg_per_col, deg_per_col * 2.0)
+}
+
+/// Where a point in the sky seen from `latitude` falls on screen, in
+/// fractional cells, whether or not it is in view. The view faces the
+/// equator, so it is centred on the south from the northern hemisphere and
+/// on the north from the southern, with the zenith toward the top.
+pub fn sky_offset(position: SkyPosition, latitude: f64, width: u16, horizon_y: u16) -> (f64, f64) {
+    let (deg_per_col, deg_per_row) = sky_scale(width);
+    let bearing = (position.azimuth - sky_facing(latitude) + 180.0).rem_euclid(360.0) - 180.0;
+    (
+        width as f64 / 2.0 + bearing / deg_per_col,
+        horizon_y as f64 - position.elevation / deg_per_row,
+    )
+}
+
+/// Screen cell for a point in the sky seen from `latitude`, if it is above
+/// the horizon and in view.
+pub fn project_sky(
+    position: SkyPosition,
+    latitude: f64,
+    width: u16,
+    horizon_y: u16,
+) -> Option<(u16, u16)> {
+    if position.elevation <= 0.0 || width == 0 {
+        return None;
+    }
+
+    let (x, y) = sky_offset(position, latitude, width, horizon_y);
+    if x < 0.0 || x >= width as f64 || y < 0.0 {
+        return None;
+    }
+    Some((x as u16, y as u16))
+}
 
 pub trait Animation {
     fn get_frame(&self, frame_number: usize) -> &[String];
@@ -41,15 +186,8 @@ impl AnimationController {
         self.current_frame
     }
 
-    pub fn render_frame<A: Animation>(
-        &self,
-        renderer: &mut TerminalRenderer,
-        animation: &A,
-        y_offset: u16,
-    ) -> io::Result<()> {
-        let frame = animation.get_frame(self.current_frame);
-        let color = animation.get_color();
-        renderer.render_centered_colored(frame, y_offset, color)
+    pub fn current_frame(&self) -> usize {
+        self.current_frame
     }
 
     #[allow(dead_code)]
@@ -63,3 +201,77 @@ impl Default for AnimationController {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_sky_faces_the_equator() {
+        let north = SkyPosition {
+            elevation: 45.0,
+            azimuth: 0.0,
+        };
+        let east = SkyPosition {
+            elevation: 10.0,
+            azimuth: 80.0,
+        };
+        let south = SkyPosition {
+            elevation: 45.0,
+            azimuth: 180.0,
+        };
+        let south_east = SkyPosition {
+            elevation: 10.0,
+            azimuth: 100.0,
+        };
+
+        // From the southern hemisphere north is in the middle, east on the right
+        assert_eq!(project_sky(north, -34.0, 180, 40), Some((90, 17)));
+        assert!(project_sky(east, -34.0, 180, 40).is_some_and(|(x, _)| x > 160));
+        assert_eq!(project_sky(south, -34.0, 180, 40), None);
+
+        // From the northern hemisphere it is the south, with east on the left
+        assert_eq!(project_sky(south, 50.0, 180, 40), Some((90, 17)));
+        assert!(project_sky(south_east, 50.0, 180, 40).is_some_and(|(x, _)| x < 20));
+        assert_eq!(project_sky(north, 50.0, 180, 40), None);
+    }
+
+    #[test]
+    fn test_chance_over_frames() {
+        assert!((chance_over(0.1, 1.0) - 0.1).abs() < 1e-6);
+        assert!((chance_over(0.1, 2.0) - 0.19).abs() < 1e-6);
+        assert_eq!(chance_over(0.3, 0.0), 0.0);
+        assert_eq!(chance_over(1.0, 0.5), 1.0);
+        assert_eq!(
+            reference_frames(Duration::from_millis(500)),
+            REFERENCE_FPS / 2.0
+        );
+    }
+
+    #[test]
+    fn test_animation_step_is_scaled_and_capped() {
+        let frame = Duration::from_micros(62_500);
+        assert_eq!(animation_step(frame, 2.0), Duration::from_millis(125));
+        assert_eq!(animation_step(Duration::from_secs(5), 1.0), MAX_STEP);
+        assert_eq!(animation_step(Duration::from_secs(5), 0.5), MAX_STEP / 2);
+    }
+
+    #[test]
+    fn test_faded_colors() {
+        let full = ColorSupport::TrueColor;
+        assert_eq!(faded(Color::White, 1.0, full), Color::White);
+        let black = Color::Rgb { r: 0, g: 0, b: 0 };
+        let half_yellow = Color::Rgb {
+            r: 127,
+            g: 127,
+            b: 0,
+        };
+        assert_eq!(faded(Color::Yellow, 0.5, full), half_yellow);
+        assert_eq!(faded(half_yellow, 0.0, full), black);
+        assert_eq!(faded(Color::Reset, 0.5, full), Color::Reset);
+        assert_eq!(
+            faded(Color::Yellow, 0.5, ColorSupport::Basic),
+            Color::Yellow
+        );
+    }
+}
@@ -1,11 +1,17 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{DAY_NIGHT_FADE_TIME, faded};
 use crate::render::TerminalRenderer;
 use crossterm::style::Color;
+use rand::Rng;
 use std::io;
+use std::time::Duration;
 
 pub struct MoonSystem {
     phase: f64, // 0.0 = New, 0.25 = First Quarter, 0.5 = Full, 0.75 = Last Quarter
     x: u16,
     y: u16,
+    /// How bright the moon shows, from 0 to 1.
+    level: f32,
 }
 
 impl MoonSystem {
@@ -14,10 +20,10 @@ impl MoonSystem {
             phase: 0.5,
             x: (terminal_width / 4) + 10,
             y: (terminal_height / 4) + 2,
+            level: 1.0,
         }
     }
 
-    #[allow(dead_code)]
     pub fn set_phase(&mut self, phase: f64) {
         self.phase = phase;
     }
@@ -29,6 +35,7 @@ impl MoonSystem {
 
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         let step = (self.phase * 8.0).round() as usize % 8;
+        let color = faded(Color::White, self.level, renderer.color_support());
 
         let art = match step {
             0 => vec![
@@ -117,13 +124,46 @@ impl MoonSystem {
 
                 if ch == '~' {
                     // Opaque Moon Body (hides stars) - Render as space but overwrite what's there
-                    renderer.render_char(x, y, ' ', Color::White)?;
+                    renderer.render_char(x, y, ' ', color)?;
                 } else {
                     // Texture/Outline
-                    renderer.render_char(x, y, ch, Color::White)?;
+                    renderer.render_char(x, y, ch, color)?;
                 }
             }
         }
         Ok(())
     }
 }
+
+impl Effect for MoonSystem {
+    fn name(&self) -> &'static str {
+        "moon"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.conditions.is_day
+    }
+
+    fn fade_time(&self) -> Duration {
+        DAY_NIGHT_FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn update(&mut self, _dt: Duration, ctx: &EffectContext, _rng: &mut dyn Rng) {
+        if let Some(phase) = ctx.weather.and_then(|weather| weather.moon_phase) {
+            self.set_phase(phase);
+        }
+        self.update(ctx.width, ctx.height);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
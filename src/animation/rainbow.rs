@@ -0,0 +1,265 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{sky_facing, sky_offset, sky_scale};
+use crate::render::{ColorSupport, TerminalRenderer};
+use crate::scene::SceneGeometry;
+use crate::weather::SkyPosition;
+use crossterm::style::Color;
+use rand::Rng;
+use std::io;
+use std::time::Duration;
+
+/// Rainbows appear at 42° from the antisolar point, so the sun must be
+/// lower than that for any of the arc to clear the horizon.
+const RAINBOW_RADIUS_DEG: f64 = 42.0;
+/// How long a rainbow takes to form or fade.
+const FADE_TIME: Duration = Duration::from_secs(120);
+/// How long after rain stops a rainbow can still form.
+const RECENT_RAIN: Duration = Duration::from_secs(30 * 60);
+/// Height of the sun behind the viewer for a simulated rainbow.
+const SIMULATED_SUN_ELEVATION: f64 = 10.0;
+
+/// Bands from the inner violet edge to the outer red edge.
+const TRUECOLOR_BANDS: [(u8, u8, u8); 7] = [
+    (148, 0, 211),
+    (75, 0, 130),
+    (0, 0, 255),
+    (0, 255, 0),
+    (255, 255, 0),
+    (255, 127, 0),
+    (255, 0, 0),
+];
+const ANSI256_BANDS: [u8; 7] = [93, 54, 21, 46, 226, 208, 196];
+const BASIC_BANDS: [Color; 7] = [
+    Color::Magenta,
+    Color::DarkMagenta,
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::DarkYellow,
+    Color::Red,
+];
+
+pub struct RainbowSystem {
+    /// How far the rainbow has formed, from 0 to 1.
+    level: f32,
+    sun: SkyPosition,
+    latitude: f64,
+    simulated: bool,
+    geometry: SceneGeometry,
+}
+
+impl RainbowSystem {
+    pub fn new() -> Self {
+        Self {
+            level: 0.0,
+            sun: Self::simulated_sun(0.0),
+            latitude: 0.0,
+            simulated: false,
+            geometry: SceneGeometry::flat(0, 0),
+        }
+    }
+
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        if self.geometry != *geometry {
+            self.geometry = geometry.clone();
+        }
+    }
+
+    /// Shows a fully formed rainbow regardless of the real sun position.
+    pub fn simulate(&mut self) {
+        self.simulated = true;
+    }
+
+    /// A low sun straight behind a viewer at `latitude`.
+    fn simulated_sun(latitude: f64) -> SkyPosition {
+        SkyPosition {
+            elevation: SIMULATED_SUN_ELEVATION,
+            azimuth: (sky_facing(latitude) + 180.0).rem_euclid(360.0),
+        }
+    }
+
+    fn sun_for(&self, ctx: &EffectContext) -> SkyPosition {
+        if self.simulated {
+            Self::simulated_sun(ctx.location.latitude)
+        } else {
+            ctx.sun
+        }
+    }
+
+    /// The sun is out after a shower, with rain still in the air or fallen
+    /// recently, and low behind the viewer.
+    fn visible(&self, ctx: &EffectContext) -> bool {
+        let raining = ctx.conditions.is_raining || ctx.conditions.is_thunderstorm;
+        let rain_nearby = self.simulated
+            || ctx
+                .weather
+                .is_some_and(|weather| weather.precipitation > 0.0)
+            || ctx
+                .last_rain
+                .is_some_and(|t| ctx.now.saturating_duration_since(t) < RECENT_RAIN);
+        ctx.show_sun
+            && !raining
+            && rain_nearby
+            && Self::sun_allows(self.sun_for(ctx), ctx.location.latitude)
+    }
+
+    /// The sun has to be up, low, and behind a viewer at `latitude`.
+    fn sun_allows(sun: SkyPosition, latitude: f64) -> bool {
+        let bearing = (sun.azimuth - sky_facing(latitude)).rem_euclid(360.0);
+        let behind = (90.0..=270.0).contains(&bearing);
+        sun.elevation > 0.0 && sun.elevation < RAINBOW_RADIUS_DEG && behind
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer, width: u16) -> io::Result<()> {
+        let horizon_y = self.geometry.horizon_y;
+        let support = renderer.color_support();
+        if self.level <= 0.0 || width == 0 || support == ColorSupport::None {
+            return Ok(());
+        }
+
+        let (deg_per_col, deg_per_row) = sky_scale(width);
+        let thickness = deg_per_col * TRUECOLOR_BANDS.len() as f64;
+        let inner = RAINBOW_RADIUS_DEG - thickness;
+
+        // The arc is centred on the antisolar point, below the horizon
+        let antisolar = SkyPosition {
+            elevation: -self.sun.elevation,
+            azimuth: self.sun.azimuth + 180.0,
+        };
+        let (center_x, center_y) = sky_offset(antisolar, self.latitude, width, horizon_y);
+
+        for y in 1..horizon_y {
+            for x in 0..width {
+                // The house and trees stand in front of the arc
+                if y >= self.geometry.surface_at(x as i32) {
+                    continue;
+                }
+
+                let dx = (x as f64 - center_x) * deg_per_col;
+                let dy = (center_y - y as f64) * deg_per_row;
+                let distance = dx.hypot(dy);
+                if distance < inner || distance >= RAINBOW_RADIUS_DEG {
+                    continue;
+                }
+
+                // A faint rainbow only shows in patches
+                let speckle = ((x as u32 * 31 + y as u32 * 17) % 100) as f32 / 100.0;
+                if speckle >= self.level {
+                    continue;
+                }
+
+                let band =
+                    (((distance - inner) / thickness) * TRUECOLOR_BANDS.len() as f64) as usize;
+                let band = band.min(TRUECOLOR_BANDS.len() - 1);
+                let ch = if self.level < 0.5 { '.' } else { ':' };
+                renderer.render_char(x, y, ch, self.band_color(band, support))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn band_color(&self, band: usize, support: ColorSupport) -> Color {
+        match support {
+            ColorSupport::TrueColor => {
+                let (r, g, b) = TRUECOLOR_BANDS[band];
+                let scale = 0.4 + 0.6 * self.level;
+                Color::Rgb {
+                    r: (r as f32 * scale) as u8,
+                    g: (g as f32 * scale) as u8,
+                    b: (b as f32 * scale) as u8,
+                }
+            }
+            ColorSupport::Ansi256 => Color::AnsiValue(ANSI256_BANDS[band]),
+            ColorSupport::Basic | ColorSupport::None => BASIC_BANDS[band],
+        }
+    }
+}
+
+impl Default for RainbowSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for RainbowSystem {
+    fn name(&self) -> &'static str {
+        "rainbow"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        self.visible(ctx)
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn update(&mut self, _dt: Duration, ctx: &EffectContext, _rng: &mut dyn Rng) {
+        self.sun = self.sun_for(ctx);
+        self.latitude = ctx.location.latitude;
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer, ctx.width)
+    }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_allows_rainbow() {
+        let low_south = SkyPosition {
+            elevation: 15.0,
+            azimuth: 200.0,
+        };
+        let high = SkyPosition {
+            elevation: 50.0,
+            azimuth: 180.0,
+        };
+        let in_front = SkyPosition {
+            elevation: 15.0,
+            azimuth: 20.0,
+        };
+        assert!(RainbowSystem::sun_allows(low_south, -34.0));
+        assert!(!RainbowSystem::sun_allows(high, -34.0));
+        assert!(!RainbowSystem::sun_allows(in_front, -34.0));
+
+        // From the northern hemisphere the view faces south
+        assert!(RainbowSystem::sun_allows(in_front, 50.0));
+        assert!(!RainbowSystem::sun_allows(low_south, 50.0));
+    }
+
+    #[test]
+    fn test_rainbow_is_centred_opposite_the_sun() {
+        let mut rainbow = RainbowSystem::new();
+        rainbow.set_geometry(&SceneGeometry::flat(90, 30));
+        rainbow.latitude = 50.0;
+        rainbow.sun = RainbowSystem::simulated_sun(50.0);
+        rainbow.set_level(1.0);
+
+        let mut renderer = TerminalRenderer::headless(90, 30);
+        rainbow.render(&mut renderer, 90).unwrap();
+        let contents = renderer.contents();
+        let columns: Vec<usize> = contents
+            .lines()
+            .flat_map(|row| row.match_indices(':').map(|(x, _)| x))
+            .collect();
+        let (left, right) = (columns.iter().min().unwrap(), columns.iter().max().unwrap());
+        assert!((left + right).abs_diff(89) <= 1);
+    }
+}
@@ -1,11 +1,25 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{Wind, chance_over, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
 use crate::weather::types::RainIntensity;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
+use std::time::Duration;
 
 const MAX_SPLASHES: usize = 100;
+const MAX_GLAZE: usize = 200;
+/// A splash shows for this many frames.
+const SPLASH_FRAMES: f32 = 3.0;
+/// How long showers take to build up or ease off.
+const FADE_TIME: Duration = Duration::from_secs(10);
+const ICE_COLOR: Color = Color::Rgb {
+    r: 190,
+    g: 230,
+    b: 255,
+};
 
 struct Raindrop {
     x: f32,
@@ -15,28 +29,50 @@ struct Raindrop {
     character: char,
     color: Color,
     z_index: u8,
+    /// Rows into the ground band this drop lands at on open ground.
+    depth: u16,
 }
 
 #[derive(Clone, Copy)]
 struct Splash {
     x: u16,
     y: u16,
-    timer: u8,
-    max_timer: u8,
+    /// Frames since the drop hit.
+    timer: f32,
+}
+
+/// Ice left where a supercooled drop froze on impact.
+struct Glaze {
+    x: u16,
+    y: u16,
+    /// Frames until it melts.
+    timer: f32,
 }
 
 pub struct RaindropSystem {
     drops: Vec<Raindrop>,
     splashes: VecDeque<Splash>,
     new_splashes: VecDeque<Splash>,
+    glaze: VecDeque<Glaze>,
+    freezing: bool,
+    /// Drops owed from earlier updates too short to spawn a whole one.
+    spawn_budget: f32,
+    /// How far the rain has set in, from 0 to 1.
+    level: f32,
     terminal_width: u16,
     terminal_height: u16,
     intensity: RainIntensity,
     wind_x: f32,
+    geometry: SceneGeometry,
 }
 
 impl RaindropSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16, intensity: RainIntensity) -> Self {
+    pub fn new(
+        terminal_width: u16,
+        terminal_height: u16,
+        intensity: RainIntensity,
+        rng: &mut impl Rng,
+    ) -> Self {
         let drops_capacity = match intensity {
             RainIntensity::Drizzle => (terminal_width / 4) as usize,
             RainIntensity::Light => (terminal_width / 2) as usize,
@@ -48,12 +84,17 @@ impl RaindropSystem {
             drops: Vec::with_capacity(drops_capacity),
             splashes: VecDeque::with_capacity(MAX_SPLASHES),
             new_splashes: VecDeque::with_capacity(20),
+            glaze: VecDeque::with_capacity(MAX_GLAZE),
+            freezing: false,
+            spawn_budget: 0.0,
+            level: 1.0,
             terminal_width,
             terminal_height,
             intensity,
             wind_x: 0.0,
+            geometry: SceneGeometry::flat(terminal_width, terminal_height),
         };
-        let wind_dir = if rand::random::<bool>() { 1.0 } else { -1.0 };
+        let wind_dir = if rng.random::<bool>() { 1.0 } else { -1.0 };
         system.set_intensity_with_dir(intensity, wind_dir);
         system
     }
@@ -74,13 +115,59 @@ impl RaindropSystem {
         self.wind_x = base_wind * direction_multiplier;
     }
 
-    pub fn set_wind(&mut self, speed_kmh: f32, direction_deg: f32) {
-        let speed_factor = speed_kmh / 40.0;
-        let direction_rad = direction_deg.to_radians();
+    pub fn set_wind(&mut self, wind: Wind) {
+        let speed_factor = wind.speed_kmh() / 40.0;
+        let direction_rad = wind.direction_deg.to_radians();
         let x_component = -direction_rad.sin();
         self.wind_x = speed_factor * x_component;
     }
 
+    /// Freezing rain: drops glaze whatever they hit instead of splashing.
+    /// When it thaws, the glaze melts away bit by bit, oldest first, over
+    /// the fade time.
+    pub fn set_freezing(&mut self, freezing: bool) {
+        if self.freezing && !freezing {
+            let melt = reference_frames(FADE_TIME);
+            let count = self.glaze.len() as f32;
+            for (i, ice) in self.glaze.iter_mut().enumerate() {
+                ice.timer = melt * (i + 1) as f32 / count;
+            }
+        }
+        self.freezing = freezing;
+    }
+
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        if self.geometry != *geometry {
+            self.geometry = geometry.clone();
+        }
+    }
+
+    /// Water running off the roof falls straight down from the eaves.
+    fn spawn_drips(&mut self, frames: f32, rng: &mut impl Rng) {
+        let drip_chance = match self.intensity {
+            RainIntensity::Drizzle => 0.03,
+            RainIntensity::Light => 0.08,
+            RainIntensity::Heavy => 0.2,
+            RainIntensity::Storm => 0.3,
+        };
+
+        let drip_chance = chance_over(drip_chance * self.level, frames);
+        for &(x, y) in &self.geometry.eaves {
+            if rng.random::<f32>() < drip_chance {
+                self.drops.push(Raindrop {
+                    x: x as f32,
+                    y: y as f32 + 1.0,
+                    speed_y: 0.3 + rng.random::<f32>() * 0.2,
+                    speed_x: 0.0,
+                    character: '\'',
+                    color: Color::Cyan,
+                    z_index: 1,
+                    depth: 0,
+                });
+            }
+        }
+    }
+
     fn spawn_drop(&mut self, rng: &mut impl Rng) {
         let x = (rng.random::<u32>() % (self.terminal_width as u32 * 2)) as f32
             - (self.terminal_width as f32 * 0.5);
@@ -130,7 +217,18 @@ impl RaindropSystem {
             ),
         };
 
+        let color = if self.freezing && z_index == 1 {
+            ICE_COLOR
+        } else {
+            color
+        };
+
         let char_idx = (rng.random::<u32>() as usize) % chars.len();
+        let depth = if z_index == 1 {
+            rng.random_range(0..self.geometry.ground_height.max(1))
+        } else {
+            0
+        };
 
         self.drops.push(Raindrop {
             x,
@@ -140,12 +238,20 @@ impl RaindropSystem {
             character: chars[char_idx],
             color,
             z_index,
+            depth,
         });
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
+        let frames = reference_frames(dt);
 
         let target_count = match self.intensity {
             RainIntensity::Drizzle => (terminal_width / 4) as usize,
@@ -153,19 +259,27 @@ impl RaindropSystem {
             RainIntensity::Heavy => terminal_width as usize,
             RainIntensity::Storm => (terminal_width as f32 * 1.5) as usize,
         };
+        let target_count = (target_count as f32 * self.level) as usize;
 
-        if self.drops.len() < target_count {
-            let spawn_rate = match self.intensity {
-                RainIntensity::Drizzle => 1,
-                RainIntensity::Light => 2,
-                _ => 5,
-            };
-            for _ in 0..spawn_rate {
+        let spawn_rate = match self.intensity {
+            RainIntensity::Drizzle => 1.0,
+            RainIntensity::Light => 2.0,
+            _ => 5.0,
+        } * self.level;
+        self.spawn_budget += spawn_rate * frames;
+        while self.spawn_budget >= 1.0 {
+            self.spawn_budget -= 1.0;
+            if self.drops.len() < target_count {
                 self.spawn_drop(rng);
             }
         }
 
+        self.spawn_drips(frames, rng);
+
         // Update drops
+        let geometry = &self.geometry;
+        let freezing = self.freezing;
+        let glaze = &mut self.glaze;
         let new_splashes = &mut self.new_splashes;
         let splash_chance = match self.intensity {
             RainIntensity::Drizzle => 0.1,
@@ -174,18 +288,33 @@ impl RaindropSystem {
         };
 
         self.drops.retain_mut(|drop| {
-            drop.y += drop.speed_y;
-            drop.x += drop.speed_x;
-
-            // Hit ground?
-            if drop.y >= (terminal_height - 1) as f32 {
-                if drop.z_index == 1 && rng.random::<f32>() < splash_chance {
-                    new_splashes.push_back(Splash {
-                        x: drop.x as u16,
-                        y: terminal_height - 1,
-                        timer: 0,
-                        max_timer: 3,
-                    });
+            drop.y += drop.speed_y * frames;
+            drop.x += drop.speed_x * frames;
+
+            // Hit the roof, a tree or the ground?
+            let x = drop.x as i32;
+            let landing = geometry.landing_row(x, drop.depth);
+            if drop.y >= landing as f32 {
+                if drop.z_index == 1 && x >= 0 && rng.random::<f32>() < splash_chance {
+                    // Splash on top of solid objects, on the ground itself
+                    let y = if geometry.is_open_ground(x) {
+                        landing
+                    } else {
+                        landing.saturating_sub(1)
+                    };
+                    if freezing {
+                        glaze.push_back(Glaze {
+                            x: x as u16,
+                            y,
+                            timer: rng.random_range(60.0..160.0),
+                        });
+                    } else {
+                        new_splashes.push_back(Splash {
+                            x: x as u16,
+                            y,
+                            timer: 0.0,
+                        });
+                    }
                 }
                 return false; // Remove drop
             }
@@ -205,8 +334,17 @@ impl RaindropSystem {
         }
 
         self.splashes.retain_mut(|splash| {
-            splash.timer += 1;
-            splash.timer < splash.max_timer
+            splash.timer += frames;
+            splash.timer < SPLASH_FRAMES
+        });
+
+        while self.glaze.len() > MAX_GLAZE {
+            self.glaze.pop_front();
+        }
+
+        self.glaze.retain_mut(|ice| {
+            ice.timer -= frames;
+            ice.timer > 0.0
         });
     }
 
@@ -235,10 +373,22 @@ impl RaindropSystem {
             }
         }
 
+        // Render glaze, glinting now and then
+        for ice in &self.glaze {
+            if ice.x < self.terminal_width && ice.y < self.terminal_height {
+                let (ch, color) = if ice.timer as u16 % 25 == 0 {
+                    ('*', Color::White)
+                } else {
+                    ('_', ICE_COLOR)
+                };
+                renderer.render_char(ice.x, ice.y, ch, color)?;
+            }
+        }
+
         // Render splashes
         for splash in &self.splashes {
             if splash.x < self.terminal_width && splash.y < self.terminal_height {
-                let ch = match splash.timer {
+                let ch = match splash.timer as u8 {
                     0 => '.',
                     1 => 'o',
                     2 => 'O',
@@ -251,3 +401,89 @@ impl RaindropSystem {
         Ok(())
     }
 }
+
+impl Effect for RaindropSystem {
+    fn name(&self) -> &'static str {
+        "rain"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.conditions.is_raining || ctx.conditions.is_thunderstorm
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.drops.is_empty() && self.splashes.is_empty() && self.glaze.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.set_freezing(ctx.conditions.is_freezing_rain && !ctx.conditions.is_thunderstorm);
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glaze_builds_only_while_freezing() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut rain = RaindropSystem::new(80, 24, RainIntensity::Heavy, &mut rng);
+        let mut splashed = false;
+        for _ in 0..60 {
+            rain.update(Duration::from_millis(16), 80, 24, &mut rng);
+            splashed |= !rain.splashes.is_empty();
+        }
+        assert!(splashed);
+        assert!(rain.glaze.is_empty());
+
+        rain.set_freezing(true);
+        for _ in 0..60 {
+            rain.update(Duration::from_millis(16), 80, 24, &mut rng);
+        }
+        assert!(!rain.glaze.is_empty());
+    }
+
+    #[test]
+    fn test_glaze_melts_over_the_fade_time_when_it_thaws() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut rain = RaindropSystem::new(80, 24, RainIntensity::Heavy, &mut rng);
+        rain.set_freezing(true);
+        for _ in 0..60 {
+            rain.update(Duration::from_millis(16), 80, 24, &mut rng);
+        }
+        let glazed = rain.glaze.len();
+        assert!(glazed > 0);
+
+        rain.set_freezing(false);
+        assert_eq!(rain.glaze.len(), glazed);
+        rain.update(FADE_TIME / 2, 80, 24, &mut rng);
+        assert!(!rain.glaze.is_empty() && rain.glaze.len() < glazed);
+        rain.update(FADE_TIME / 2, 80, 24, &mut rng);
+        assert!(rain.glaze.is_empty());
+    }
+}
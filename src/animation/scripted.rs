@@ -0,0 +1,563 @@
+//! Particle effects written as Rhai scripts in the `scripts` folder of the
+//! config directory. Each script keeps its state on `this` and may define:
+//!
+//! ```rhai
+//! fn init(ctx) { this.sparks = []; }
+//! fn update(ctx) {
+//!     if ctx.conditions.raining { return; }
+//!     this.sparks.push(#{ x: random(0.0, ctx.width.to_float()), y: ctx.horizon.to_float() });
+//!     for i in 0..this.sparks.len() { this.sparks[i].y -= ctx.dt * 4.0; }
+//!     this.sparks.retain(|s| s.y > 0.0);
+//! }
+//! fn render(canvas) {
+//!     for s in this.sparks { canvas.draw(s.x, s.y, "*", "yellow"); }
+//! }
+//! ```
+//!
+//! `ctx` holds `dt` and `time` in seconds, the terminal `width`, `height`
+//! and `horizon` row, `is_day`, the `conditions` flags and, once fetched,
+//! the `weather` readings. Scripts draw only through the canvas, cannot
+//! load modules or files, and are stopped when they overrun their share
+//! of the frame.
+
+use super::effect::{Effect, EffectContext, EffectLayer};
+use crate::error::ConfigError;
+use crate::render::TerminalRenderer;
+use crate::render::art::parse_color;
+use crate::weather::{WeatherConditions, WeatherData};
+use crossterm::style::Color;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope};
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Characters one script may draw per frame.
+const MAX_DRAWS: usize = 4000;
+/// Consecutive frames over budget before a script is stopped.
+const MAX_OVERRUNS: u32 = 10;
+/// Operations between clock checks against the budget.
+const OPS_PER_CHECK: u64 = 256;
+
+/// Characters drawn, as (column, row, character, color).
+type Cells = Vec<(u16, u16, char, Color)>;
+
+/// What a script drew this frame, clipped to the terminal.
+#[derive(Clone)]
+struct Canvas {
+    cells: Rc<RefCell<Cells>>,
+    width: u16,
+    height: u16,
+    horizon: u16,
+}
+
+impl Canvas {
+    fn draw(&mut self, x: f64, y: f64, text: &str, color: &str) {
+        let (x, y) = (x.round(), y.round());
+        if x < 0.0 || y < 0.0 || x >= self.width as f64 || y >= self.height as f64 {
+            return;
+        }
+        let Some(ch) = text.chars().next() else {
+            return;
+        };
+        let mut cells = self.cells.borrow_mut();
+        if cells.len() < MAX_DRAWS {
+            cells.push((
+                x as u16,
+                y as u16,
+                ch,
+                parse_color(color).unwrap_or(Color::White),
+            ));
+        }
+    }
+}
+
+/// Why a call into a script did not finish.
+enum Failure {
+    Overrun,
+    Error(String),
+}
+
+/// One loaded script and the sandboxed engine it runs in.
+pub struct ScriptedEffect {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    state: Dynamic,
+    canvas: Canvas,
+    rng: Rc<RefCell<StdRng>>,
+    deadline: Rc<Cell<Instant>>,
+    budget: Duration,
+    started: bool,
+    overruns: u32,
+    /// Set once the script has failed or kept overrunning; it no longer runs.
+    error: Option<String>,
+}
+
+impl ScriptedEffect {
+    pub fn load(path: &Path, budget: Duration) -> Result<Self, ConfigError> {
+        let source = fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        Self::compile(&name, &source, budget).map_err(|reason| ConfigError::InvalidScript {
+            path: path.display().to_string(),
+            reason,
+        })
+    }
+
+    pub fn compile(name: &str, source: &str, budget: Duration) -> Result<Self, String> {
+        let canvas = Canvas {
+            cells: Rc::new(RefCell::new(Vec::new())),
+            width: 0,
+            height: 0,
+            horizon: 0,
+        };
+        let rng = Rc::new(RefCell::new(StdRng::seed_from_u64(0)));
+        let deadline = Rc::new(Cell::new(Instant::now()));
+        let engine = sandbox(&rng, &deadline);
+
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        let defines = |name: &str| ast.iter_functions().any(|f| f.name == name);
+        if !defines("update") && !defines("render") {
+            return Err("defines neither fn update(ctx) nor fn render(canvas)".to_string());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            ast,
+            state: Dynamic::from_map(Map::new()),
+            canvas,
+            rng,
+            deadline,
+            budget,
+            started: false,
+            overruns: 0,
+            error: None,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Why the script was stopped, if it was.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Runs `init` the first time, then `update` and `render`, and keeps
+    /// what the script drew for [`ScriptedEffect::render`].
+    pub fn update(&mut self, ctx: Map, width: u16, height: u16, horizon: u16, seed: u64) {
+        self.canvas.cells.borrow_mut().clear();
+        if self.error.is_some() {
+            return;
+        }
+        self.canvas.width = width;
+        self.canvas.height = height;
+        self.canvas.horizon = horizon;
+        *self.rng.borrow_mut() = StdRng::seed_from_u64(seed);
+        self.deadline.set(Instant::now() + self.budget);
+
+        let ctx = Dynamic::from_map(ctx);
+        let mut result = Ok(());
+        if !self.started {
+            self.started = true;
+            result = self.call("init", ctx.clone());
+        }
+        if result.is_ok() {
+            result = self.call("update", ctx);
+        }
+        if result.is_ok() {
+            result = self.call("render", Dynamic::from(self.canvas.clone()));
+        }
+
+        match result {
+            Ok(()) => self.overruns = 0,
+            Err(Failure::Overrun) => {
+                self.canvas.cells.borrow_mut().clear();
+                self.overruns += 1;
+                if self.overruns >= MAX_OVERRUNS {
+                    self.error = Some(format!(
+                        "ran over its {:?} budget {MAX_OVERRUNS} frames in a row",
+                        self.budget
+                    ));
+                }
+            }
+            Err(Failure::Error(message)) => self.error = Some(message),
+        }
+    }
+
+    /// Calls a script function if it is defined, with `this` bound to the
+    /// script's state.
+    fn call(&mut self, function: &str, arg: Dynamic) -> Result<(), Failure> {
+        if !self.ast.iter_functions().any(|f| f.name == function) {
+            return Ok(());
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &self.ast,
+                function,
+                (arg,),
+            )
+            .map(|_| ())
+            .map_err(|e| match *e {
+                EvalAltResult::ErrorTerminated(..) => Failure::Overrun,
+                e => Failure::Error(format!("{function}: {e}")),
+            })
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for &(x, y, ch, color) in self.canvas.cells.borrow().iter() {
+            renderer.render_char(x, y, ch, color)?;
+        }
+        Ok(())
+    }
+}
+
+/// An engine that can only compute and draw: no modules, no `eval`, no
+/// output, bounded sizes, and a clock that stops a call at the deadline.
+fn sandbox(rng: &Rc<RefCell<StdRng>>, deadline: &Rc<Cell<Instant>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {})
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(10_000)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000);
+
+    let deadline = Rc::clone(deadline);
+    engine.on_progress(move |ops| {
+        (ops % OPS_PER_CHECK == 0 && Instant::now() > deadline.get()).then(Dynamic::default)
+    });
+
+    let unit = Rc::clone(rng);
+    engine.register_fn("random", move || unit.borrow_mut().random::<f64>());
+    let range = Rc::clone(rng);
+    engine.register_fn("random", move |low: f64, high: f64| {
+        random_between(&range, low, high)
+    });
+
+    engine
+        .register_type_with_name::<Canvas>("Canvas")
+        .register_get("width", |c: &mut Canvas| c.width as i64)
+        .register_get("height", |c: &mut Canvas| c.height as i64)
+        .register_get("horizon", |c: &mut Canvas| c.horizon as i64)
+        .register_fn("draw", draw);
+    engine
+}
+
+/// `random(low, high)`; an empty range gives `low`, and bounds too far
+/// apart to measure are refused rather than left to panic.
+fn random_between(rng: &RefCell<StdRng>, low: f64, high: f64) -> Result<f64, Box<EvalAltResult>> {
+    if !low.is_finite() || !high.is_finite() || !(high - low).is_finite() {
+        return Err(format!("random needs a finite range, got {low} to {high}").into());
+    }
+    if high > low {
+        Ok(rng.borrow_mut().random_range(low..high))
+    } else {
+        Ok(low)
+    }
+}
+
+/// `canvas.draw(x, y, char, color)`, taking integer or float positions.
+fn draw(
+    canvas: &mut Canvas,
+    x: Dynamic,
+    y: Dynamic,
+    ch: &str,
+    color: &str,
+) -> Result<(), Box<EvalAltResult>> {
+    match (number(&x), number(&y)) {
+        (Some(x), Some(y)) => {
+            canvas.draw(x, y, ch, color);
+            Ok(())
+        }
+        _ => Err(format!(
+            "draw needs numbers for x and y, got {} and {}",
+            x.type_name(),
+            y.type_name()
+        )
+        .into()),
+    }
+}
+
+fn number(value: &Dynamic) -> Option<f64> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|i| i as f64))
+}
+
+/// The flags scripts can branch on.
+fn conditions_map(conditions: &WeatherConditions) -> Map {
+    let mut map = Map::new();
+    for (key, value) in [
+        ("raining", conditions.is_raining),
+        ("snowing", conditions.is_snowing),
+        ("thunderstorm", conditions.is_thunderstorm),
+        ("hail", conditions.is_hail),
+        ("freezing_rain", conditions.is_freezing_rain),
+        ("cloudy", conditions.is_cloudy),
+        ("foggy", conditions.is_foggy),
+        ("day", conditions.is_day),
+    ] {
+        map.insert(key.into(), value.into());
+    }
+    map
+}
+
+/// Readings scripts can see, normalised to °C, m/s and mm whatever units
+/// the config displays.
+fn weather_map(weather: &WeatherData) -> Map {
+    let mut map = Map::new();
+    for (key, value) in [
+        ("temperature", weather.temperature),
+        ("apparent_temperature", weather.apparent_temperature),
+        ("humidity", weather.humidity),
+        ("precipitation", weather.precipitation),
+        ("wind_speed", weather.wind_speed),
+        ("wind_direction", weather.wind_direction),
+        ("cloud_cover", weather.cloud_cover),
+        ("pressure", weather.pressure),
+    ] {
+        map.insert(key.into(), value.into());
+    }
+    map.insert(
+        "condition".into(),
+        format!("{:?}", weather.condition).into(),
+    );
+    map.insert("is_day".into(), weather.is_day.into());
+    map
+}
+
+/// Loads every `.rhai` file in `dir`, in name order. A missing folder
+/// means no scripts.
+pub fn load_dir(dir: &Path, budget: Duration) -> Result<Vec<ScriptedEffect>, ConfigError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ConfigError::ReadError {
+                path: dir.display().to_string(),
+                source: e,
+            });
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| ScriptedEffect::load(path, budget))
+        .collect()
+}
+
+/// Runs the user's scripts once per frame.
+pub struct ScriptSystem {
+    effects: Vec<ScriptedEffect>,
+    /// Animation time the scripts have run for, given to them as `ctx.time`.
+    clock: Duration,
+}
+
+impl ScriptSystem {
+    pub fn new() -> Self {
+        Self {
+            effects: Vec::new(),
+            clock: Duration::ZERO,
+        }
+    }
+
+    pub fn set_effects(&mut self, effects: Vec<ScriptedEffect>) {
+        self.effects = effects;
+    }
+
+    pub fn effects(&self) -> &[ScriptedEffect] {
+        &self.effects
+    }
+
+    pub fn update(&mut self, dt: Duration, frame: &EffectContext, rng: &mut impl Rng) {
+        if self.effects.is_empty() {
+            return;
+        }
+        self.clock += dt;
+        let (width, height) = (frame.width, frame.height);
+        let horizon = frame.geometry.horizon_y;
+        let conditions = frame.conditions;
+
+        let mut ctx = Map::new();
+        ctx.insert("dt".into(), dt.as_secs_f64().into());
+        ctx.insert("time".into(), self.clock.as_secs_f64().into());
+        ctx.insert("width".into(), (width as i64).into());
+        ctx.insert("height".into(), (height as i64).into());
+        ctx.insert("horizon".into(), (horizon as i64).into());
+        ctx.insert("is_day".into(), conditions.is_day.into());
+        ctx.insert(
+            "conditions".into(),
+            Dynamic::from_map(conditions_map(conditions)),
+        );
+        let weather = frame
+            .weather
+            .map_or(Dynamic::UNIT, |w| Dynamic::from_map(weather_map(w)));
+        ctx.insert("weather".into(), weather);
+
+        for effect in &mut self.effects {
+            effect.update(ctx.clone(), width, height, horizon, rng.random());
+        }
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for effect in &self.effects {
+            effect.render(renderer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ScriptSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for ScriptSystem {
+    fn name(&self) -> &'static str {
+        "scripts"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, _ctx: &EffectContext) -> bool {
+        !self.effects.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: Duration = Duration::from_millis(50);
+
+    fn ctx(raining: bool) -> Map {
+        let mut ctx = Map::new();
+        ctx.insert("dt".into(), 0.5_f64.into());
+        let conditions = WeatherConditions {
+            is_raining: raining,
+            ..WeatherConditions::default()
+        };
+        ctx.insert(
+            "conditions".into(),
+            Dynamic::from_map(conditions_map(&conditions)),
+        );
+        ctx
+    }
+
+    fn drawn(effect: &ScriptedEffect) -> Cells {
+        effect.canvas.cells.borrow().clone()
+    }
+
+    #[test]
+    fn test_script_keeps_state_and_draws() {
+        let source = r#"
+            fn init(ctx) { this.y = 10.0; }
+            fn update(ctx) {
+                if !ctx.conditions.raining { this.y -= ctx.dt * 4.0; }
+            }
+            fn render(canvas) {
+                canvas.draw(3, this.y, "*", "yellow");
+                canvas.draw(canvas.width, 0, "x", "red");
+            }
+        "#;
+        let mut effect = ScriptedEffect::compile("rise", source, BUDGET).unwrap();
+
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert_eq!(drawn(&effect), [(3, 8, '*', Color::Yellow)]);
+        effect.update(ctx(true), 20, 20, 15, 1);
+        assert_eq!(drawn(&effect), [(3, 8, '*', Color::Yellow)]);
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert_eq!(drawn(&effect), [(3, 6, '*', Color::Yellow)]);
+        assert_eq!(effect.error(), None);
+    }
+
+    #[test]
+    fn test_script_sandbox() {
+        let compile = |source: &str| ScriptedEffect::compile("bad", source, BUDGET);
+        assert!(compile("fn update(ctx) { import \"os\" as os; }").is_ok());
+        assert!(compile("fn update(ctx) { eval(\"1\") }").is_err());
+        assert!(compile("let x = 1;").is_err_and(|e| e.contains("neither")));
+        assert!(compile("fn update(ctx) {").is_err());
+
+        let mut effect = compile("fn update(ctx) { import \"os\" as os; }").unwrap();
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert!(effect.error().is_some());
+
+        let mut effect = compile("fn update(ctx) { let x = this.missing + 1; }").unwrap();
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert!(effect.error().is_some_and(|e| e.starts_with("update")));
+
+        let mut effect =
+            compile("fn render(canvas) { canvas.draw(\"1\", 2, \"*\", \"red\"); }").unwrap();
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert!(effect.error().is_some_and(|e| e.contains("numbers")));
+
+        for range in ["-1.0e308, 1.0e308", "0.0, 1.0 / 0.0"] {
+            let source = format!("fn update(ctx) {{ let x = random({range}); }}");
+            let mut effect = compile(&source).unwrap();
+            effect.update(ctx(false), 20, 20, 15, 1);
+            assert!(effect.error().is_some_and(|e| e.contains("finite range")));
+        }
+    }
+
+    #[test]
+    fn test_script_stopped_after_overrunning() {
+        let source = "fn update(ctx) { loop {} }";
+        let mut effect = ScriptedEffect::compile("spin", source, Duration::ZERO).unwrap();
+        for _ in 0..MAX_OVERRUNS - 1 {
+            effect.update(ctx(false), 20, 20, 15, 1);
+        }
+        assert_eq!(effect.error(), None);
+        effect.update(ctx(false), 20, 20, 15, 1);
+        assert!(effect.error().is_some_and(|e| e.contains("budget")));
+    }
+
+    #[test]
+    fn test_script_random_follows_seed() {
+        let source = "fn render(canvas) { canvas.draw(random(0.0, 10.0), random() * 10.0, \"o\", \"white\"); }";
+        let mut effect = ScriptedEffect::compile("dots", source, BUDGET).unwrap();
+        effect.update(ctx(false), 20, 20, 15, 7);
+        let first = drawn(&effect);
+        effect.update(ctx(false), 20, 20, 15, 7);
+        assert_eq!(drawn(&effect), first);
+    }
+}
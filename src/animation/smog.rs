@@ -1,8 +1,12 @@
+use super::Wind;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
 use crate::render::TerminalRenderer;
 use crate::weather::WeatherConditions;
 use crossterm::style::Color;
 use rand::{Rng, RngExt};
 use std::io;
+use std::time::Duration;
 
 struct SmogParticle {
     x: f32,
@@ -15,6 +19,7 @@ const SMOG_CHARS: [char; 3] = ['~', '-', '.'];
 
 pub struct Smog {
     particles: Vec<SmogParticle>,
+    wind: Wind,
     width: u16,
     height: u16,
 }
@@ -23,11 +28,16 @@ impl Smog {
     pub fn new(width: u16, height: u16) -> Self {
         Self {
             particles: Vec::new(),
+            wind: Wind::default(),
             width,
             height,
         }
     }
 
+    pub fn set_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
     pub fn resize(&mut self, width: u16, height: u16) {
         self.width = width;
         self.height = height;
@@ -36,8 +46,8 @@ impl Smog {
 
     pub fn update(
         &mut self,
+        dt: Duration,
         should_show: bool,
-        speed_multiplier: f32,
         _conditions: &WeatherConditions,
         rng: &mut impl Rng,
     ) {
@@ -46,28 +56,33 @@ impl Smog {
             return;
         }
 
+        let frames = reference_frames(dt);
         let target_particles = (self.width as usize * self.height as usize) / 80;
-        if self.particles.len() < target_particles {
-            if rng.random_bool(0.3) {
-                self.particles.push(SmogParticle {
-                    x: rng.random_range(0.0..self.width as f32),
-                    y: rng.random_range(0.0..self.height as f32),
-                    speed: rng.random_range(0.05..0.15) * speed_multiplier,
-                    char_idx: rng.random_range(0..SMOG_CHARS.len()),
-                });
-            }
+        if self.particles.len() < target_particles
+            && rng.random_bool(chance_over(0.3, frames).into())
+        {
+            self.particles.push(SmogParticle {
+                x: rng.random_range(0.0..self.width as f32),
+                y: rng.random_range(0.0..self.height as f32),
+                speed: rng.random_range(0.05..0.15),
+                char_idx: rng.random_range(0..SMOG_CHARS.len()),
+            });
         }
 
+        let drift = self.wind.horizontal() * 0.02;
         for p in &mut self.particles {
-            p.x += p.speed;
+            p.x += (p.speed + drift) * frames;
             
-            if rng.random_bool(0.1) {
+            if rng.random_bool(chance_over(0.1, frames).into()) {
                 p.y += rng.random_range(-0.1..0.1);
             }
 
             if p.x >= self.width as f32 {
                 p.x = 0.0;
                 p.y = rng.random_range(0.0..self.height as f32);
+            } else if p.x < 0.0 {
+                p.x = self.width as f32 - 1.0;
+                p.y = rng.random_range(0.0..self.height as f32);
             }
             if p.y < 0.0 {
                 p.y = self.height as f32 - 1.0;
@@ -107,3 +122,27 @@ impl Smog {
         Ok(())
     }
 }
+
+impl Effect for Smog {
+    fn name(&self) -> &'static str {
+        "smog"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    /// Keeps running when the air clears so the haze is dropped.
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.resize(ctx.width, ctx.height);
+        self.update(dt, ctx.show_smog, ctx.conditions, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer, ctx.show_smog, ctx.conditions.is_day)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+}
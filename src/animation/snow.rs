@@ -1,8 +1,15 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{Wind, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
 use crate::weather::types::SnowIntensity;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
+
+/// How long snowfall takes to build up or ease off.
+const FADE_TIME: Duration = Duration::from_secs(15);
 
 struct Snowflake {
     x: f32,
@@ -12,6 +19,8 @@ struct Snowflake {
     sway_offset: f32,
     character: char,
     color: Color,
+    /// Rows into the ground band this flake settles at on open ground.
+    depth: u16,
 }
 
 pub struct SnowSystem {
@@ -20,10 +29,20 @@ pub struct SnowSystem {
     terminal_height: u16,
     intensity: SnowIntensity,
     wind_x: f32,
+    /// Flakes owed from earlier updates too short to spawn a whole one.
+    spawn_budget: f32,
+    /// How far the snowfall has set in, from 0 to 1.
+    level: f32,
+    geometry: SceneGeometry,
 }
 
 impl SnowSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16, intensity: SnowIntensity) -> Self {
+    pub fn new(
+        terminal_width: u16,
+        terminal_height: u16,
+        intensity: SnowIntensity,
+        rng: &mut impl Rng,
+    ) -> Self {
         let flakes_capacity = match intensity {
             SnowIntensity::Light => (terminal_width / 4) as usize,
             SnowIntensity::Medium => (terminal_width / 2) as usize,
@@ -36,9 +55,12 @@ impl SnowSystem {
             terminal_height,
             intensity,
             wind_x: 0.0,
+            spawn_budget: 0.0,
+            level: 1.0,
+            geometry: SceneGeometry::flat(terminal_width, terminal_height),
         };
         // Initialize with some default wind
-        let wind_dir = if rand::random::<bool>() { 0.2 } else { -0.2 };
+        let wind_dir = if rng.random::<bool>() { 0.2 } else { -0.2 };
         system.set_intensity_with_dir(intensity, wind_dir);
         system
     }
@@ -59,13 +81,19 @@ impl SnowSystem {
         self.wind_x = base_wind * direction_multiplier;
     }
 
-    pub fn set_wind(&mut self, speed_kmh: f32, direction_deg: f32) {
-        let speed_factor = speed_kmh / 20.0;
-        let direction_rad = direction_deg.to_radians();
+    pub fn set_wind(&mut self, wind: Wind) {
+        let speed_factor = wind.speed_kmh() / 20.0;
+        let direction_rad = wind.direction_deg.to_radians();
         let x_component = -direction_rad.sin();
         self.wind_x = speed_factor * x_component;
     }
 
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        if self.geometry != *geometry {
+            self.geometry = geometry.clone();
+        }
+    }
+
     fn spawn_flake(&mut self, rng: &mut impl Rng) {
         // Spawn across a wider area to account for wind blowing them in
         let x = (rng.random::<u32>() % (self.terminal_width as u32 * 3)) as f32
@@ -80,6 +108,11 @@ impl SnowSystem {
         };
 
         let char_idx = (rng.random::<u32>() as usize) % chars.len();
+        let depth = if z_index == 1 {
+            rng.random_range(0..self.geometry.ground_height.max(1))
+        } else {
+            0
+        };
 
         self.flakes.push(Snowflake {
             x,
@@ -93,39 +126,51 @@ impl SnowSystem {
             } else {
                 Color::DarkGrey
             },
+            depth,
         });
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
+        let frames = reference_frames(dt);
 
         let target_count = match self.intensity {
             SnowIntensity::Light => (terminal_width / 4) as usize,
             SnowIntensity::Medium => (terminal_width / 2) as usize,
             SnowIntensity::Heavy => terminal_width as usize,
         };
-
-        if self.flakes.len() < target_count {
-            let spawn_rate = match self.intensity {
-                SnowIntensity::Light => 1,
-                SnowIntensity::Medium => 2,
-                SnowIntensity::Heavy => 4,
-            };
-            for _ in 0..spawn_rate {
+        let target_count = (target_count as f32 * self.level) as usize;
+
+        let spawn_rate = match self.intensity {
+            SnowIntensity::Light => 1.0,
+            SnowIntensity::Medium => 2.0,
+            SnowIntensity::Heavy => 4.0,
+        } * self.level;
+        self.spawn_budget += spawn_rate * frames;
+        while self.spawn_budget >= 1.0 {
+            self.spawn_budget -= 1.0;
+            if self.flakes.len() < target_count {
                 self.spawn_flake(rng);
             }
         }
 
+        let geometry = &self.geometry;
         self.flakes.retain_mut(|flake| {
-            flake.y += flake.speed_y;
+            flake.y += flake.speed_y * frames;
 
             // Add horizontal sway
             let sway = (flake.y * 0.2 + flake.sway_offset).sin() * 0.05;
-            flake.x += flake.speed_x + sway;
+            flake.x += (flake.speed_x + sway) * frames;
 
-            // Hit ground or out of bounds
-            if flake.y >= (terminal_height - 1) as f32 {
+            // Settled on the roof, a tree or the ground
+            if flake.y >= geometry.landing_row(flake.x as i32, flake.depth) as f32 {
                 return false;
             }
 
@@ -151,3 +196,46 @@ impl SnowSystem {
         Ok(())
     }
 }
+
+impl Effect for SnowSystem {
+    fn name(&self) -> &'static str {
+        "snow"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    /// Rain wins when a report carries both.
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.conditions.is_snowing && !ctx.conditions.is_raining && !ctx.conditions.is_thunderstorm
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.flakes.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+
+    fn set_wind(&mut self, wind: Wind) {
+        self.set_wind(wind);
+    }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
@@ -0,0 +1,682 @@
+//! Animated sprites defined in the `sprites` folder of the config directory,
+//! one TOML file each: frames of ASCII art with color masks, a path across
+//! the screen and the conditions they come out in.
+//!
+//! ```toml
+//! layer = "ground"           # "sky" behind the clouds, "ground" by the street
+//! speed = 6.0                # Cells per second along the path
+//! every = [60, 300]          # Seconds between appearances
+//! path = [[-0.1, 0], [1.1, 0]]
+//!
+//! [colors]
+//! o = { day = "dark_yellow" }
+//!
+//! [[frames]]
+//! art = '''
+//!  o
+//! /|\
+//! / \
+//! '''
+//! mask = " o"
+//! ms = 250
+//!
+//! [when]
+//! time = "day"
+//! weather = ["clear", "cloudy"]
+//! ```
+//!
+//! Path points are (fraction of the terminal width, rows above the horizon)
+//! for the bottom middle of the sprite, so `-0.1` and `1.1` are off screen.
+
+use super::Animation;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use crate::error::ConfigError;
+use crate::render::TerminalRenderer;
+use crate::render::art::{ColorEntry, MaskedArt, Palette, parse_color};
+use crate::scene::Season;
+use crate::weather::WeatherConditions;
+use crossterm::style::Color;
+use rand::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Where a sprite is drawn among the other animations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpriteLayer {
+    /// Behind the clouds, with the birds and aircraft.
+    #[default]
+    Sky,
+    /// In front of the scene with the street, behind the rain.
+    Ground,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TimeOfDay {
+    #[default]
+    Any,
+    Day,
+    Night,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Sky {
+    Clear,
+    Cloudy,
+    Fog,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl Sky {
+    fn of(conditions: &WeatherConditions) -> Self {
+        if conditions.is_thunderstorm {
+            Sky::Thunderstorm
+        } else if conditions.is_snowing {
+            Sky::Snow
+        } else if conditions.is_raining {
+            Sky::Rain
+        } else if conditions.is_foggy {
+            Sky::Fog
+        } else if conditions.is_cloudy {
+            Sky::Cloudy
+        } else {
+            Sky::Clear
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpriteFile {
+    #[serde(default)]
+    layer: SpriteLayer,
+    #[serde(default = "default_speed")]
+    speed: f32,
+    #[serde(default = "default_every")]
+    every: [u32; 2],
+    /// Seconds spent at the last point before leaving.
+    #[serde(default)]
+    linger: f32,
+    path: Vec<[f32; 2]>,
+    /// Color of art the mask leaves blank.
+    color: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, ColorEntry>,
+    #[serde(default)]
+    frames: Vec<FrameFile>,
+    #[serde(default)]
+    when: When,
+}
+
+fn default_speed() -> f32 {
+    8.0
+}
+
+fn default_every() -> [u32; 2] {
+    [60, 300]
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FrameFile {
+    art: String,
+    mask: Option<String>,
+    #[serde(default = "default_frame_ms")]
+    ms: u32,
+}
+
+fn default_frame_ms() -> u32 {
+    200
+}
+
+/// Spawn conditions; empty lists allow anything.
+#[derive(Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct When {
+    #[serde(default)]
+    time: TimeOfDay,
+    #[serde(default)]
+    weather: Vec<Sky>,
+    #[serde(default)]
+    seasons: Vec<Season>,
+}
+
+#[derive(Clone)]
+struct Frame {
+    art: MaskedArt,
+    /// Plain lines for the [`Animation`] trait.
+    text: Vec<String>,
+    duration: Duration,
+}
+
+/// A sprite definition loaded from a file.
+#[derive(Clone)]
+pub struct Sprite {
+    layer: SpriteLayer,
+    speed: f32,
+    every: [u32; 2],
+    linger: Duration,
+    path: Vec<(f32, f32)>,
+    color: Color,
+    palette: Palette,
+    frames: Vec<Frame>,
+    when: When,
+}
+
+impl Sprite {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let source = fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::parse(&source).map_err(|reason| ConfigError::InvalidSprite {
+            path: path.display().to_string(),
+            reason,
+        })
+    }
+
+    /// Parses and validates a sprite file, describing the first problem
+    /// found.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let file: SpriteFile = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        if file.frames.is_empty() {
+            return Err("no frames; add at least one [[frames]] table with art".to_string());
+        }
+        if file.path.is_empty() {
+            return Err("path needs at least one point".to_string());
+        }
+        if let Some([x, above]) = file
+            .path
+            .iter()
+            .find(|point| !point.iter().all(|v| v.is_finite()))
+        {
+            return Err(format!("path point [{x}, {above}] must be finite numbers"));
+        }
+        for (name, value) in [("speed", file.speed), ("linger", file.linger)] {
+            if !value.is_finite() {
+                return Err(format!("{name} must be a finite number, got {value}"));
+            }
+        }
+        if file.path.len() > 1 && file.speed <= 0.0 {
+            return Err(format!("speed must be above 0, got {}", file.speed));
+        }
+        if file.path.len() == 1 && file.linger <= 0.0 {
+            return Err("a sprite with a single path point needs linger seconds".to_string());
+        }
+        let [min, max] = file.every;
+        if min > max {
+            return Err(format!(
+                "every must be [shortest, longest], got [{min}, {max}]"
+            ));
+        }
+
+        let linger = Duration::try_from_secs_f32(file.linger.max(0.0))
+            .map_err(|_| format!("linger of {} seconds is too long", file.linger))?;
+
+        let color = match &file.color {
+            Some(color) => {
+                parse_color(color).ok_or_else(|| format!("unknown color \"{color}\""))?
+            }
+            None => Color::White,
+        };
+        let palette = Palette::parse(&file.colors)?;
+        let mut frames = Vec::new();
+        for (i, frame) in file.frames.iter().enumerate() {
+            if frame.ms == 0 {
+                return Err(format!("frame {}: ms must be above 0", i + 1));
+            }
+            let art = MaskedArt::parse(
+                &format!("frame {}", i + 1),
+                &frame.art,
+                frame.mask.as_deref(),
+                &palette,
+            )?;
+            let text = art.lines.iter().map(|line| line.iter().collect()).collect();
+            frames.push(Frame {
+                art,
+                text,
+                duration: Duration::from_millis(frame.ms as u64),
+            });
+        }
+
+        Ok(Self {
+            layer: file.layer,
+            speed: file.speed,
+            every: file.every,
+            linger,
+            path: file.path.iter().map(|&[x, y]| (x, y)).collect(),
+            color,
+            palette,
+            frames,
+            when: file.when,
+        })
+    }
+
+    /// Whether the sprite may come out in these conditions.
+    pub fn allowed(&self, conditions: &WeatherConditions, season: Season) -> bool {
+        let time = match self.when.time {
+            TimeOfDay::Any => true,
+            TimeOfDay::Day => conditions.is_day,
+            TimeOfDay::Night => !conditions.is_day,
+        };
+        let weather =
+            self.when.weather.is_empty() || self.when.weather.contains(&Sky::of(conditions));
+        let season = self.when.seasons.is_empty() || self.when.seasons.contains(&season);
+        time && weather && season
+    }
+
+    /// Frame shown `elapsed` into an appearance.
+    fn frame_at(&self, elapsed: Duration) -> usize {
+        let cycle: Duration = self.frames.iter().map(|f| f.duration).sum();
+        let mut t = Duration::from_nanos((elapsed.as_nanos() % cycle.as_nanos()) as u64);
+        for (i, frame) in self.frames.iter().enumerate() {
+            if t < frame.duration {
+                return i;
+            }
+            t -= frame.duration;
+        }
+        0
+    }
+
+    /// Screen points of the path as (column, row) for the bottom middle.
+    fn waypoints(&self, width: u16, horizon_y: u16) -> Vec<(f32, f32)> {
+        self.path
+            .iter()
+            .map(|&(x, above)| (x * width as f32, horizon_y as f32 - 1.0 - above))
+            .collect()
+    }
+
+    /// Travel time along the path, then the linger at its end.
+    fn duration(&self, width: u16, horizon_y: u16) -> Duration {
+        let length = path_length(&self.waypoints(width, horizon_y));
+        Duration::try_from_secs_f32(length / self.speed.max(f32::EPSILON))
+            .unwrap_or(Duration::MAX)
+            .saturating_add(self.linger)
+    }
+
+    /// Bottom middle of the sprite `elapsed` into an appearance.
+    fn position_at(&self, elapsed: Duration, width: u16, horizon_y: u16) -> (f32, f32) {
+        let points = self.waypoints(width, horizon_y);
+        let mut travelled = elapsed.as_secs_f32() * self.speed;
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let leg = distance(from, to);
+            if travelled < leg {
+                let t = travelled / leg;
+                return (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            }
+            travelled -= leg;
+        }
+        points[points.len() - 1]
+    }
+
+    fn next_delay(&self, rng: &mut impl Rng) -> Duration {
+        let [min, max] = self.every;
+        Duration::from_secs(rng.random_range(min..=max) as u64)
+    }
+}
+
+impl Animation for Sprite {
+    fn get_frame(&self, frame_number: usize) -> &[String] {
+        &self.frames[frame_number % self.frames.len()].text
+    }
+
+    fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    fn get_color(&self) -> Color {
+        self.color
+    }
+}
+
+fn distance(from: (f32, f32), to: (f32, f32)) -> f32 {
+    ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt()
+}
+
+fn path_length(points: &[(f32, f32)]) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum()
+}
+
+/// Loads every `.toml` file in `dir`, in name order. A missing folder
+/// means no sprites.
+pub fn load_dir(dir: &Path) -> Result<Vec<Sprite>, ConfigError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ConfigError::ReadError {
+                path: dir.display().to_string(),
+                source: e,
+            });
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.iter().map(|path| Sprite::load(path)).collect()
+}
+
+/// One sprite's timetable on the system's clock: out on screen since
+/// `started`, or next due at `due`.
+struct Schedule {
+    sprite: Sprite,
+    due: Option<Duration>,
+    started: Option<Duration>,
+}
+
+/// Brings user sprites out one appearance at a time, each at random
+/// intervals and only in the conditions it asks for.
+pub struct SpriteSystem {
+    layer: SpriteLayer,
+    schedules: Vec<Schedule>,
+    /// Animation time since the system started.
+    clock: Duration,
+}
+
+impl SpriteSystem {
+    pub fn new(layer: SpriteLayer) -> Self {
+        Self {
+            layer,
+            schedules: Vec::new(),
+            clock: Duration::ZERO,
+        }
+    }
+
+    /// Keeps the sprites that belong on this system's layer.
+    pub fn set_sprites(&mut self, sprites: Vec<Sprite>) {
+        self.schedules = sprites
+            .into_iter()
+            .filter(|sprite| sprite.layer == self.layer)
+            .map(|sprite| Schedule {
+                sprite,
+                due: None,
+                started: None,
+            })
+            .collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schedules.is_empty()
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        conditions: &WeatherConditions,
+        season: Season,
+        width: u16,
+        horizon_y: u16,
+        rng: &mut impl Rng,
+    ) {
+        self.clock += dt;
+        let now = self.clock;
+        for schedule in &mut self.schedules {
+            let sprite = &schedule.sprite;
+            if let Some(started) = schedule.started {
+                if now.saturating_sub(started) >= sprite.duration(width, horizon_y) {
+                    schedule.started = None;
+                    schedule.due = Some(now + sprite.next_delay(rng));
+                }
+                continue;
+            }
+
+            // The first appearance comes sooner than the usual interval
+            let due = *schedule.due.get_or_insert_with(|| {
+                now + Duration::from_secs(rng.random_range(0..=sprite.every[0]) as u64)
+            });
+            if now >= due && sprite.allowed(conditions, season) {
+                schedule.started = Some(now);
+            }
+        }
+    }
+
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        is_day: bool,
+        width: u16,
+        horizon_y: u16,
+    ) -> io::Result<()> {
+        for schedule in &self.schedules {
+            let sprite = &schedule.sprite;
+            let Some(started) = schedule.started else {
+                continue;
+            };
+
+            let elapsed = self.clock.saturating_sub(started);
+            let art = &sprite.frames[sprite.frame_at(elapsed)].art;
+            let (x, bottom) = sprite.position_at(elapsed, width, horizon_y);
+            let left = (x - art.width() as f32 / 2.0).round() as i32;
+            let top = bottom.round() as i32 - art.height() as i32 + 1;
+
+            for (row, line) in art.lines.iter().enumerate() {
+                for (col, &ch) in line.iter().enumerate() {
+                    let (cx, cy) = (left + col as i32, top + row as i32);
+                    if ch == ' ' || cx < 0 || cy < 0 || cx >= width as i32 {
+                        continue;
+                    }
+                    let color = art
+                        .color(row, col, &sprite.palette, is_day)
+                        .unwrap_or(sprite.color);
+                    renderer.render_char(cx as u16, cy as u16, ch, color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Effect for SpriteSystem {
+    fn name(&self) -> &'static str {
+        "sprites"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        match self.layer {
+            SpriteLayer::Sky => EffectLayer::Sky,
+            SpriteLayer::Ground => EffectLayer::Foreground,
+        }
+    }
+
+    fn is_active(&self, _ctx: &EffectContext) -> bool {
+        !self.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(
+            dt,
+            ctx.conditions,
+            ctx.season,
+            ctx.width,
+            ctx.geometry.horizon_y,
+            &mut rng,
+        );
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        self.render(
+            renderer,
+            ctx.conditions.is_day,
+            ctx.width,
+            ctx.geometry.horizon_y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALKER: &str = r#"
+layer = "ground"
+speed = 10.0
+every = [5, 5]
+path = [[0.0, 0], [1.0, 0]]
+
+[colors]
+h = { day = "yellow", night = "dark_yellow" }
+
+[[frames]]
+art = '''
+ o
+/|\
+'''
+mask = " h"
+ms = 100
+
+[[frames]]
+art = '''
+ o
+\|/
+'''
+ms = 300
+
+[when]
+time = "day"
+weather = ["clear"]
+seasons = ["summer", "autumn"]
+"#;
+
+    fn clear_day() -> WeatherConditions {
+        WeatherConditions {
+            is_day: true,
+            ..WeatherConditions::default()
+        }
+    }
+
+    #[test]
+    fn test_sprite_frames_and_path() {
+        let sprite = Sprite::parse(WALKER).unwrap();
+        assert_eq!(sprite.frame_count(), 2);
+        assert_eq!(sprite.get_frame(1), [" o", "\\|/"]);
+        assert_eq!(sprite.layer, SpriteLayer::Ground);
+
+        assert_eq!(sprite.frame_at(Duration::from_millis(50)), 0);
+        assert_eq!(sprite.frame_at(Duration::from_millis(150)), 1);
+        assert_eq!(sprite.frame_at(Duration::from_millis(450)), 0);
+
+        // 100 columns at 10 per second, bottom row just above the horizon
+        assert_eq!(sprite.duration(100, 30), Duration::from_secs(10));
+        assert_eq!(
+            sprite.position_at(Duration::from_secs(5), 100, 30),
+            (50.0, 29.0)
+        );
+        assert_eq!(
+            sprite.position_at(Duration::from_secs(20), 100, 30),
+            (100.0, 29.0)
+        );
+    }
+
+    #[test]
+    fn test_sprite_spawn_conditions() {
+        let sprite = Sprite::parse(WALKER).unwrap();
+        assert!(sprite.allowed(&clear_day(), Season::Summer));
+        assert!(!sprite.allowed(&clear_day(), Season::Winter));
+        let night = WeatherConditions {
+            is_day: false,
+            ..clear_day()
+        };
+        assert!(!sprite.allowed(&night, Season::Summer));
+
+        let rain = WeatherConditions {
+            is_raining: true,
+            ..clear_day()
+        };
+        assert!(!sprite.allowed(&rain, Season::Summer));
+    }
+
+    #[test]
+    fn test_sprite_system_schedules_appearances() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut system = SpriteSystem::new(SpriteLayer::Ground);
+        system.set_sprites(vec![Sprite::parse(WALKER).unwrap()]);
+        // Updates take the time since the last frame; the clock they run
+        // reaches 0, 5, 15 and then 20 seconds.
+        let secs = Duration::from_secs;
+
+        system.update(secs(0), &clear_day(), Season::Summer, 100, 30, &mut rng);
+        assert!(system.schedules[0].due.is_some_and(|due| due <= secs(5)));
+
+        system.update(secs(5), &clear_day(), Season::Summer, 100, 30, &mut rng);
+        assert_eq!(system.schedules[0].started, Some(secs(5)));
+
+        // Gone once it reaches the end of the path at 15 s, back an interval later
+        system.update(secs(10), &clear_day(), Season::Summer, 100, 30, &mut rng);
+        assert_eq!(system.schedules[0].started, None);
+        assert_eq!(system.schedules[0].due, Some(secs(15) + secs(5)));
+
+        // Due again at 20 s, but waits for the weather it asked for
+        let fog = WeatherConditions {
+            is_foggy: true,
+            ..clear_day()
+        };
+        system.update(secs(5), &fog, Season::Summer, 100, 30, &mut rng);
+        assert_eq!(system.schedules[0].started, None);
+        assert_eq!(system.schedules[0].due, Some(secs(20)));
+    }
+
+    #[test]
+    fn test_sprite_numbers_must_be_finite() {
+        let error = |fields: &str| {
+            Sprite::parse(&format!("{fields}\n[[frames]]\nart = 'x'"))
+                .err()
+                .unwrap()
+        };
+        assert_eq!(
+            error("path = [[0, 0], [1, 0]]\nspeed = inf"),
+            "speed must be a finite number, got inf"
+        );
+        assert_eq!(
+            error("path = [[0, 0]]\nlinger = nan"),
+            "linger must be a finite number, got NaN"
+        );
+        assert_eq!(
+            error("path = [[0, 0]]\nlinger = 1e30"),
+            "linger of 1000000000000000000000000000000 seconds is too long"
+        );
+        assert_eq!(
+            error("path = [[0, 0], [-inf, 2]]"),
+            "path point [-inf, 2] must be finite numbers"
+        );
+        // Whole seconds, so anything but a count is refused while reading
+        assert!(error("path = [[0, 0]]\nlinger = 1\nevery = [inf, 9]").contains("every"));
+    }
+
+    #[test]
+    fn test_sprite_errors_say_what_is_wrong() {
+        let error = |source: &str| Sprite::parse(source).err().unwrap();
+        assert!(error("path = [[0, 0]]").contains("no frames"));
+        assert!(error("path = []\n[[frames]]\nart = 'x'").contains("path"));
+        assert!(error("path = [[0, 0]]\n[[frames]]\nart = 'x'").contains("linger"));
+        assert!(
+            error("path = [[0, 0], [1, 0]]\nspeed = 0.0\n[[frames]]\nart = 'x'").contains("speed")
+        );
+        assert!(
+            error("path = [[0, 0], [1, 0]]\nevery = [9, 1]\n[[frames]]\nart = 'x'")
+                .contains("every")
+        );
+        assert!(
+            error("path = [[0, 0], [1, 0]]\n[[frames]]\nart = 'x'\nmask = 'q'").contains("frame 1")
+        );
+        assert!(
+            error("path = [[0, 0], [1, 0]]\n[when]\nweather = ['hail']\n[[frames]]\nart = 'x'")
+                .contains("hail")
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Stars down to about third magnitude that outline the well-known
+//! constellations, as (right ascension in hours, declination in degrees,
+//! visual magnitude) at J2000.
+
+pub const BRIGHT_STARS: &[(f64, f64, f32)] = &[
+    // Orion
+    (5.919, 7.407, 0.50),  // Betelgeuse
+    (5.242, -8.202, 0.13), // Rigel
+    (5.419, 6.350, 1.64),  // Bellatrix
+    (5.796, -9.670, 2.06), // Saiph
+    (5.679, -1.943, 1.77), // Alnitak
+    (5.604, -1.202, 1.69), // Alnilam
+    (5.533, -0.299, 2.23), // Mintaka
+    (5.585, 9.934, 3.33),  // Meissa
+    // Canis Major and Minor
+    (6.752, -16.716, -1.46), // Sirius
+    (6.977, -28.972, 1.50),  // Adhara
+    (7.140, -26.393, 1.83),  // Wezen
+    (6.378, -17.956, 1.98),  // Mirzam
+    (7.401, -29.303, 2.45),  // Aludra
+    (7.655, 5.225, 0.34),    // Procyon
+    (7.453, 8.289, 2.89),    // Gomeisa
+    // Gemini
+    (7.577, 31.888, 1.58), // Castor
+    (7.755, 28.026, 1.14), // Pollux
+    (6.629, 16.399, 1.93), // Alhena
+    // Taurus
+    (4.599, 16.509, 0.85), // Aldebaran
+    (5.438, 28.608, 1.65), // Elnath
+    (3.791, 24.105, 2.87), // Alcyone, in the Pleiades
+    // Auriga
+    (5.278, 45.998, 0.08), // Capella
+    (5.992, 44.948, 1.90), // Menkalinan
+    (5.995, 37.213, 2.62), // Mahasim
+    (4.950, 33.166, 2.69), // Hassaleh
+    // Ursa Major
+    (11.062, 61.751, 1.79), // Dubhe
+    (11.031, 56.382, 2.37), // Merak
+    (11.897, 53.695, 2.44), // Phecda
+    (12.257, 57.033, 3.31), // Megrez
+    (12.900, 55.960, 1.77), // Alioth
+    (13.399, 54.925, 2.27), // Mizar
+    (13.792, 49.313, 1.86), // Alkaid
+    // Ursa Minor
+    (2.530, 89.264, 1.98),  // Polaris
+    (14.845, 74.156, 2.08), // Kochab
+    (15.345, 71.834, 3.05), // Pherkad
+    // Cassiopeia
+    (0.675, 56.537, 2.24), // Schedar
+    (0.153, 59.150, 2.28), // Caph
+    (0.945, 60.717, 2.47), // Gamma Cassiopeiae
+    (1.430, 60.235, 2.68), // Ruchbah
+    (1.907, 63.670, 3.37), // Segin
+    // Cepheus
+    (21.310, 62.585, 2.45), // Alderamin
+    (21.478, 70.561, 3.23), // Alfirk
+    (23.655, 77.632, 3.21), // Errai
+    // Draco
+    (17.943, 51.489, 2.23), // Eltanin
+    (17.507, 52.301, 2.79), // Rastaban
+    (16.400, 61.514, 2.73), // Athebyne
+    (17.146, 65.715, 3.17), // Aldhibah
+    (14.073, 64.376, 3.65), // Thuban
+    // Cygnus
+    (20.690, 45.280, 1.25), // Deneb
+    (20.370, 40.257, 2.23), // Sadr
+    (20.770, 33.970, 2.48), // Aljanah
+    (19.750, 45.131, 2.87), // Fawaris
+    (19.512, 27.960, 3.05), // Albireo
+    // Lyra
+    (18.616, 38.784, 0.03), // Vega
+    (18.835, 33.363, 3.52), // Sheliak
+    (18.982, 32.690, 3.25), // Sulafat
+    // Aquila
+    (19.846, 8.868, 0.77),  // Altair
+    (19.771, 10.613, 2.72), // Tarazed
+    (19.922, 6.407, 3.71),  // Alshain
+    // Hercules and Ophiuchus
+    (16.504, 21.490, 2.77), // Kornephoros
+    (16.688, 31.603, 2.81), // Zeta Herculis
+    (17.582, 12.560, 2.07), // Rasalhague
+    // Bootes and Corona Borealis
+    (14.261, 19.182, -0.05), // Arcturus
+    (14.750, 27.074, 2.37),  // Izar
+    (13.911, 18.398, 2.68),  // Muphrid
+    (15.578, 26.715, 2.22),  // Alphecca
+    // Leo
+    (10.140, 11.967, 1.35), // Regulus
+    (11.818, 14.572, 2.14), // Denebola
+    (10.333, 19.842, 2.08), // Algieba
+    (11.235, 20.524, 2.56), // Zosma
+    (9.764, 23.774, 2.98),  // Ras Elased Australis
+    // Virgo, Corvus and Hydra
+    (13.420, -11.161, 0.97), // Spica
+    (12.263, -17.542, 2.59), // Gienah
+    (12.573, -23.397, 2.65), // Kraz
+    (12.498, -16.516, 2.95), // Algorab
+    (9.460, -8.659, 1.98),   // Alphard
+    // Scorpius
+    (16.490, -26.432, 1.06), // Antares
+    (17.560, -37.104, 1.62), // Shaula
+    (17.622, -42.998, 1.86), // Sargas
+    (16.006, -22.622, 2.29), // Dschubba
+    (16.091, -19.806, 2.62), // Acrab
+    (16.836, -34.293, 2.29), // Larawag
+    (17.708, -39.030, 2.39), // Girtab
+    // Sagittarius
+    (18.403, -34.385, 1.85), // Kaus Australis
+    (18.921, -26.297, 2.05), // Nunki
+    (19.043, -29.880, 2.60), // Ascella
+    (18.350, -29.828, 2.70), // Kaus Media
+    (18.466, -25.422, 2.81), // Kaus Borealis
+    (18.097, -30.424, 2.99), // Alnasl
+    // Perseus
+    (3.405, 49.861, 1.79), // Mirfak
+    (3.136, 40.956, 2.12), // Algol
+    (3.964, 40.010, 2.89), // Epsilon Persei
+    (3.715, 47.788, 3.01), // Delta Persei
+    (3.080, 53.506, 2.93), // Gamma Persei
+    // Andromeda, Pegasus and Aries
+    (0.140, 29.091, 2.06),  // Alpheratz
+    (1.162, 35.621, 2.05),  // Mirach
+    (2.065, 42.330, 2.10),  // Almach
+    (23.079, 15.205, 2.48), // Markab
+    (23.063, 28.083, 2.42), // Scheat
+    (0.221, 15.184, 2.83),  // Algenib
+    (21.736, 9.875, 2.38),  // Enif
+    (2.120, 23.463, 2.00),  // Hamal
+    // Cetus, Lepus and Columba
+    (0.727, -17.987, 2.04), // Diphda
+    (3.038, 4.090, 2.54),   // Menkar
+    (5.546, -17.822, 2.58), // Arneb
+    (5.661, -34.074, 2.65), // Phact
+    // Southern Fish, Crane, Phoenix and Peacock
+    (22.961, -29.622, 1.16), // Fomalhaut
+    (22.137, -46.961, 1.74), // Alnair
+    (0.438, -42.306, 2.40),  // Ankaa
+    (20.427, -56.735, 1.94), // Peacock
+    // Crux
+    (12.443, -63.099, 0.77), // Acrux
+    (12.795, -59.689, 1.25), // Mimosa
+    (12.519, -57.113, 1.64), // Gacrux
+    (12.252, -58.749, 2.79), // Imai
+    // Centaurus and Triangulum Australe
+    (14.660, -60.834, -0.27), // Rigil Kentaurus
+    (14.064, -60.373, 0.61),  // Hadar
+    (14.111, -36.370, 2.06),  // Menkent
+    (16.811, -69.028, 1.91),  // Atria
+    // Carina and Vela
+    (6.399, -52.696, -0.74), // Canopus
+    (8.375, -59.510, 1.86),  // Avior
+    (9.220, -69.717, 1.67),  // Miaplacidus
+    (9.285, -59.275, 2.21),  // Aspidiske
+    (8.159, -47.337, 1.83),  // Regor
+    (8.745, -54.708, 1.93),  // Alsephina
+    // Eridanus
+    (1.629, -57.237, 0.46), // Achernar
+];
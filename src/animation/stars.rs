@@ -1,13 +1,84 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use crate::animation::star_catalog::BRIGHT_STARS;
+use crate::animation::{DAY_NIGHT_FADE_TIME, chance_over, project_sky, reference_frames};
 use crate::render::TerminalRenderer;
+use crate::weather::astronomy::{self, Equatorial, Planet};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
 
-#[derive(Clone, Copy)]
-struct Star {
+/// Faintest magnitude shown under a dark, clear sky.
+const DARK_SKY_LIMIT: f32 = 3.8;
+/// How many magnitudes a full moon's glare takes off the faintest stars.
+const FULL_MOON_GLARE: f32 = 1.2;
+/// Faintest magnitude shown in daylight, brighter than any star or planet,
+/// so the brightest come out first as night falls.
+const DAYLIGHT_LIMIT: f32 = -5.0;
+/// Haze near the horizon dims stars below this elevation.
+const EXTINCTION_ELEVATION: f64 = 10.0;
+const BASE_METEOR_CHANCE: f32 = 0.005;
+const MAX_METEORS: usize = 3;
+
+struct MeteorShower {
+    peak_month: u32,
+    peak_day: u32,
+    /// Days either side of the peak with raised activity.
+    half_width: f32,
+    /// Zenithal hourly rate at the peak.
+    zhr: f32,
+    radiant: Equatorial,
+}
+
+const METEOR_SHOWERS: [MeteorShower; 7] = [
+    shower(1, 3, 4.0, 110.0, 230.0, 49.0),   // Quadrantids
+    shower(4, 22, 5.0, 18.0, 271.0, 34.0),   // Lyrids
+    shower(5, 6, 15.0, 50.0, 338.0, -1.0),   // Eta Aquariids
+    shower(8, 12, 14.0, 100.0, 48.0, 58.0),  // Perseids
+    shower(10, 21, 12.0, 20.0, 95.0, 16.0),  // Orionids
+    shower(11, 17, 8.0, 15.0, 152.0, 22.0),  // Leonids
+    shower(12, 14, 7.0, 150.0, 112.0, 33.0), // Geminids
+];
+
+const fn shower(
+    peak_month: u32,
+    peak_day: u32,
+    half_width: f32,
+    zhr: f32,
+    right_ascension: f64,
+    declination: f64,
+) -> MeteorShower {
+    MeteorShower {
+        peak_month,
+        peak_day,
+        half_width,
+        zhr,
+        radiant: Equatorial {
+            right_ascension,
+            declination,
+        },
+    }
+}
+
+/// Typical brightness of each planet; planets hold steady instead of
+/// twinkling.
+fn planet_appearance(planet: Planet) -> (f32, Color) {
+    match planet {
+        Planet::Mercury => (0.0, Color::Grey),
+        Planet::Venus => (-4.0, Color::White),
+        Planet::Mars => (0.5, Color::Red),
+        Planet::Jupiter => (-2.2, Color::Yellow),
+        Planet::Saturn => (0.7, Color::DarkYellow),
+    }
+}
+
+struct VisibleStar {
     x: u16,
     y: u16,
-    brightness: f32,
+    magnitude: f32,
+    /// Set for planets, which get their own color and do not twinkle.
+    planet_color: Option<Color>,
     phase: f32,
 }
 
@@ -17,148 +88,201 @@ struct ShootingStar {
     speed_x: f32,
     speed_y: f32,
     length: usize,
-    active: bool,
 }
 
 pub struct StarSystem {
-    stars: Vec<Star>,
-    shooting_star: Option<ShootingStar>,
+    stars: Vec<VisibleStar>,
+    /// Twinkle phase of each catalog star.
+    phases: Vec<f32>,
+    shooting_stars: Vec<ShootingStar>,
+    latitude: f64,
+    longitude: f64,
+    moon_illumination: f32,
+    cloud_cover: f32,
     terminal_width: u16,
     terminal_height: u16,
+    /// How dark the sky has grown, from 0 to 1.
+    level: f32,
 }
 
 impl StarSystem {
-    const MIN_DISTANCE: f32 = 3.0; // Minimum distance between stars
-
     pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let stars = Self::create_stars(terminal_width, terminal_height, &[]);
+        // Spread by the golden angle so neighbours twinkle out of step
+        let phases = (0..BRIGHT_STARS.len())
+            .map(|i| (i as f32 * 2.399_963).rem_euclid(std::f32::consts::TAU))
+            .collect();
 
         Self {
-            stars,
-            shooting_star: None,
+            stars: Vec::new(),
+            phases,
+            shooting_stars: Vec::new(),
+            latitude: 0.0,
+            longitude: 0.0,
+            moon_illumination: 0.0,
+            cloud_cover: 0.0,
             terminal_width,
             terminal_height,
+            level: 1.0,
         }
     }
 
-    fn create_stars(terminal_width: u16, terminal_height: u16, inital_stars: &[Star]) -> Vec<Star> {
-        let mut rng = rand::rng();
-        let count = (terminal_width as usize * terminal_height as usize) / 80; // Density
+    pub fn set_observer(&mut self, latitude: f64, longitude: f64) {
+        self.latitude = latitude;
+        self.longitude = longitude;
+    }
 
-        if count < inital_stars.len() {
-            return inital_stars.to_vec();
-        }
+    /// Moonlight hides the fainter stars; clouds hide stars behind them.
+    pub fn set_sky_brightness(&mut self, moon_phase: Option<f64>, cloud_cover: f64) {
+        self.moon_illumination = moon_phase.map_or(0.0, astronomy::moon_illumination) as f32;
+        self.cloud_cover = (cloud_cover / 100.0).clamp(0.0, 1.0) as f32;
+    }
 
-        let mut stars = Vec::with_capacity(count);
-
-        stars.extend(inital_stars.iter().cloned());
-
-        for _ in 0..count {
-            let mut attempts = 0;
-            let max_attempts = 50;
-
-            loop {
-                let x = rng.random::<u16>() % terminal_width;
-                let y = rng.random::<u16>() % (terminal_height / 2); // Upper half
-
-                // Check if this position is far enough from existing stars
-                let too_close = stars.iter().any(|star: &Star| {
-                    let dx = (star.x as f32 - x as f32).abs();
-                    let dy = (star.y as f32 - y as f32).abs();
-                    let distance = (dx * dx + dy * dy).sqrt();
-                    distance < Self::MIN_DISTANCE
-                });
-
-                if !too_close || attempts >= max_attempts {
-                    stars.push(Star {
-                        x,
-                        y,
-                        brightness: rng.random::<f32>(),
-                        phase: rng.random::<f32>() * std::f32::consts::TAU,
-                    });
-                    break;
-                }
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        horizon_y: u16,
+        now: DateTime<Utc>,
+        rng: &mut impl Rng,
+    ) {
+        self.terminal_width = terminal_width;
+        self.terminal_height = terminal_height;
 
-                attempts += 1;
-            }
+        // Twinkle
+        let frames = reference_frames(dt);
+        for phase in &mut self.phases {
+            *phase += 0.05 * frames;
         }
 
-        stars
-    }
+        let sky = SkyView {
+            latitude: self.latitude,
+            sidereal_time: astronomy::local_sidereal_time(self.longitude, now),
+            limit: DAYLIGHT_LIMIT
+                + (DARK_SKY_LIMIT - FULL_MOON_GLARE * self.moon_illumination - DAYLIGHT_LIMIT)
+                    * self.level,
+            cloud_cover: self.cloud_cover,
+            width: terminal_width,
+            horizon_y,
+        };
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
-        if terminal_width != self.terminal_width || terminal_height != self.terminal_height {
-            // Fix stars not resizing
-            self.stars = Self::create_stars(terminal_width, terminal_height, &self.stars);
+        let catalog = BRIGHT_STARS
+            .iter()
+            .map(|&(ra_hours, declination, magnitude)| {
+                let position = Equatorial {
+                    right_ascension: ra_hours * 15.0,
+                    declination,
+                };
+                (position, magnitude, None)
+            });
+        let planets = Planet::ALL.into_iter().map(|planet| {
+            let (magnitude, color) = planet_appearance(planet);
+            (
+                astronomy::planet_position(planet, now),
+                magnitude,
+                Some(color),
+            )
+        });
+
+        self.stars = catalog
+            .chain(planets)
+            .enumerate()
+            .filter_map(|(i, (position, magnitude, planet_color))| {
+                let phase = self.phases.get(i).copied().unwrap_or(0.0);
+                sky.place(i, position, magnitude, planet_color, phase)
+            })
+            .collect();
 
-            self.terminal_width = terminal_width;
-            self.terminal_height = terminal_height;
+        // Shooting Star Logic
+        self.shooting_stars.retain_mut(|star| {
+            star.x += star.speed_x * frames;
+            star.y += star.speed_y * frames;
+            star.x >= 0.0
+                && star.x < terminal_width as f32
+                && star.y >= 0.0
+                && star.y < horizon_y as f32
+        });
 
-            return;
+        let shower = shower_activity(now.date_naive());
+        let rate = shower.map_or(1.0, |(shower, activity)| 1.0 + shower.zhr * activity / 15.0);
+        if self.shooting_stars.len() < MAX_METEORS
+            && self.cloud_cover < 0.9
+            && rng.random::<f32>() < chance_over(BASE_METEOR_CHANCE * rate * self.level, frames)
+        {
+            let radiant = shower.and_then(|(shower, _)| {
+                let position =
+                    astronomy::to_horizontal(shower.radiant, sky.latitude, sky.sidereal_time);
+                project_sky(position, sky.latitude, terminal_width, horizon_y)
+            });
+            let meteor = match radiant {
+                Some(radiant) => Self::meteor_from(radiant, rng),
+                None => Self::random_meteor(terminal_width, terminal_height, rng),
+            };
+            self.shooting_stars.push(meteor);
         }
+    }
 
-        // Twinkle
-        for star in &mut self.stars {
-            star.phase += 0.05;
-            star.brightness = (star.phase.sin() + 1.0) / 2.0; // 0.0 to 1.0
+    /// A meteor streaking away from its shower's radiant.
+    fn meteor_from((x, y): (u16, u16), rng: &mut impl Rng) -> ShootingStar {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let (sin, cos) = angle.sin_cos();
+        let start = rng.random_range(3.0..12.0);
+        ShootingStar {
+            // Cells are twice as tall as wide
+            x: x as f32 + cos * start,
+            y: y as f32 + sin * start * 0.5,
+            speed_x: cos * 1.5,
+            speed_y: sin * 0.75,
+            length: 5,
         }
+    }
 
-        // Shooting Star Logic
-        if let Some(ref mut star) = self.shooting_star {
-            star.x += star.speed_x;
-            star.y += star.speed_y;
+    fn random_meteor(
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) -> ShootingStar {
+        let start_x = (rng.random::<u16>() % (terminal_width / 2).max(1)) + (terminal_width / 4);
+        let start_y = rng.random::<u16>() % (terminal_height / 4).max(1);
 
-            if star.x < 0.0 || star.y as u16 >= terminal_height || star.length == 0 {
-                self.shooting_star = None;
-            }
-        } else if rng.random::<f32>() < 0.005 {
-            let start_x = (rng.random::<u16>() % (terminal_width / 2)) + (terminal_width / 4);
-            let start_y = rng.random::<u16>() % (terminal_height / 4);
-
-            self.shooting_star = Some(ShootingStar {
-                x: start_x as f32,
-                y: start_y as f32,
-                speed_x: if rng.random::<bool>() { 1.5 } else { -1.5 },
-                speed_y: 0.5 + (rng.random::<f32>() * 0.5),
-                length: 5,
-                active: true,
-            });
+        ShootingStar {
+            x: start_x as f32,
+            y: start_y as f32,
+            speed_x: if rng.random::<bool>() { 1.5 } else { -1.5 },
+            speed_y: 0.5 + (rng.random::<f32>() * 0.5),
+            length: 5,
         }
     }
 
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         for star in &self.stars {
-            let ch = if star.brightness > 0.8 {
-                '*'
-            } else if star.brightness > 0.4 {
-                '+'
-            } else {
-                '.'
-            };
-            let color = if star.brightness > 0.6 {
-                Color::White
-            } else {
-                Color::DarkGrey
+            let (ch, color) = match star.planet_color {
+                Some(color) => ('*', color),
+                None => {
+                    let apparent = star.magnitude + 0.4 * star.phase.sin();
+                    let ch = if apparent < 0.5 {
+                        '*'
+                    } else if apparent < 1.8 {
+                        '+'
+                    } else {
+                        '.'
+                    };
+                    let color = if apparent < 1.0 {
+                        Color::White
+                    } else if apparent < 2.3 {
+                        Color::Grey
+                    } else {
+                        Color::DarkGrey
+                    };
+                    (ch, color)
+                }
             };
 
             renderer.render_char(star.x, star.y, ch, color)?;
         }
 
-        if let Some(ref star) = self.shooting_star
-            && star.active
-        {
-            let head_x = star.x as i16;
-            let head_y = star.y as i16;
-
-            if head_x >= 0
-                && head_x < self.terminal_width as i16
-                && head_y >= 0
-                && head_y < self.terminal_height as i16
-            {
-                renderer.render_char(head_x as u16, head_y as u16, '*', Color::White)?;
-            }
-
-            for i in 1..star.length {
+        for star in &self.shooting_stars {
+            for i in 0..star.length {
                 let trail_x = (star.x - (star.speed_x * i as f32)) as i16;
                 let trail_y = (star.y - (star.speed_y * i as f32)) as i16;
 
@@ -167,7 +291,11 @@ impl StarSystem {
                     && trail_y >= 0
                     && trail_y < self.terminal_height as i16
                 {
-                    let ch = if i == 1 { '+' } else { '.' };
+                    let ch = match i {
+                        0 => '*',
+                        1 => '+',
+                        _ => '.',
+                    };
                     renderer.render_char(trail_x as u16, trail_y as u16, ch, Color::White)?;
                 }
             }
@@ -176,3 +304,135 @@ impl StarSystem {
         Ok(())
     }
 }
+
+/// Where the sky stands this frame.
+struct SkyView {
+    latitude: f64,
+    sidereal_time: f64,
+    /// Faintest magnitude that shows through the moonlight.
+    limit: f32,
+    cloud_cover: f32,
+    width: u16,
+    horizon_y: u16,
+}
+
+impl SkyView {
+    fn place(
+        &self,
+        index: usize,
+        position: Equatorial,
+        magnitude: f32,
+        planet_color: Option<Color>,
+        phase: f32,
+    ) -> Option<VisibleStar> {
+        let sky = astronomy::to_horizontal(position, self.latitude, self.sidereal_time);
+        let magnitude = if sky.elevation < EXTINCTION_ELEVATION {
+            magnitude + 1.0
+        } else {
+            magnitude
+        };
+
+        // Patchy cloud hides the same stars from frame to frame
+        let gap = ((index * 7919 + 13) % 101) as f32 / 101.0;
+        if magnitude > self.limit || gap < self.cloud_cover {
+            return None;
+        }
+
+        let (x, y) = project_sky(sky, self.latitude, self.width, self.horizon_y)?;
+        Some(VisibleStar {
+            x,
+            y,
+            magnitude,
+            planet_color,
+            phase,
+        })
+    }
+}
+
+/// The most active meteor shower on a date, with its activity from 0 to 1.
+fn shower_activity(date: NaiveDate) -> Option<(&'static MeteorShower, f32)> {
+    METEOR_SHOWERS
+        .iter()
+        .filter_map(|shower| {
+            // The nearest peak may fall in the previous or next year
+            let days = [date.year() - 1, date.year(), date.year() + 1]
+                .into_iter()
+                .filter_map(|year| {
+                    NaiveDate::from_ymd_opt(year, shower.peak_month, shower.peak_day)
+                })
+                .map(|peak| (date - peak).num_days().abs())
+                .min()?;
+            let activity = 1.0 - days as f32 / shower.half_width;
+            (activity > 0.0).then_some((shower, activity))
+        })
+        .max_by(|(a, x), (b, y)| (a.zhr * x).total_cmp(&(b.zhr * y)))
+}
+
+impl Effect for StarSystem {
+    fn name(&self) -> &'static str {
+        "stars"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.conditions.is_day
+    }
+
+    fn fade_time(&self) -> Duration {
+        DAY_NIGHT_FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.stars.is_empty() && self.shooting_stars.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        let weather = ctx.weather;
+        self.set_observer(ctx.location.latitude, ctx.location.longitude);
+        self.set_sky_brightness(
+            weather.and_then(|weather| weather.moon_phase),
+            weather.map_or(0.0, |weather| weather.cloud_cover),
+        );
+        self.update(
+            dt,
+            ctx.width,
+            ctx.height,
+            ctx.geometry.horizon_y,
+            ctx.utc,
+            &mut rng,
+        );
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meteor_shower_activity() {
+        let perseids = NaiveDate::from_ymd_opt(2025, 8, 12).unwrap();
+        let (shower, activity) = shower_activity(perseids).unwrap();
+        assert_eq!(shower.zhr, 100.0);
+        assert_eq!(activity, 1.0);
+
+        let geminids = NaiveDate::from_ymd_opt(2025, 12, 13).unwrap();
+        assert_eq!(shower_activity(geminids).unwrap().0.zhr, 150.0);
+
+        let quadrantids = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        assert_eq!(shower_activity(quadrantids).unwrap().0.zhr, 110.0);
+
+        let quiet = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert!(shower_activity(quiet).is_none());
+    }
+}
@@ -0,0 +1,398 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
+use crate::render::TerminalRenderer;
+use chrono::{NaiveDateTime, Timelike};
+use crossterm::style::Color;
+use rand::prelude::*;
+use std::io;
+use std::time::Duration;
+
+const MAX_PEDESTRIANS: usize = 6;
+const MAX_CARS: usize = 2;
+/// Spawn chances per frame at the busiest hour.
+const PEDESTRIAN_CHANCE: f32 = 0.006;
+const CAR_CHANCE: f32 = 0.008;
+/// Rows below the horizon people walk on and cars drive on.
+const SIDEWALK_ROW: u16 = 1;
+const ROAD_ROW: u16 = 4;
+const HEADLIGHT_REACH: u16 = 6;
+
+/// How busy the street is at each local hour, from 0 to 1.
+#[rustfmt::skip]
+const HOURLY_BUSYNESS: [f32; 24] = [
+    0.05, 0.03, 0.02, 0.02, 0.03, 0.1, 0.3, 0.8, 1.0, 0.7, 0.5, 0.55,
+    0.65, 0.6, 0.5, 0.55, 0.75, 1.0, 0.9, 0.6, 0.45, 0.35, 0.2, 0.1,
+];
+
+const CLOTHES: [Color; 5] = [
+    Color::Red,
+    Color::Blue,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::Magenta,
+];
+const CAR_PAINT: [Color; 5] = [
+    Color::Red,
+    Color::Blue,
+    Color::White,
+    Color::DarkCyan,
+    Color::DarkYellow,
+];
+
+/// What the weather means for people out on the street.
+pub struct StreetConditions {
+    pub is_day: bool,
+    pub raining: bool,
+    /// Puddles and spray: raining now or rained recently.
+    pub wet: bool,
+    /// Snowing or freezing, so coats and hoods come out.
+    pub cold: bool,
+}
+
+impl StreetConditions {
+    fn from_context(ctx: &EffectContext) -> Self {
+        let raining = ctx.conditions.is_raining || ctx.conditions.is_thunderstorm;
+        Self {
+            is_day: ctx.conditions.is_day,
+            raining,
+            wet: raining
+                || ctx
+                    .weather
+                    .is_some_and(|weather| weather.precipitation > 0.0),
+            cold: ctx.conditions.is_snowing
+                || ctx
+                    .weather
+                    .is_some_and(|weather| weather.apparent_temperature < 0.0),
+        }
+    }
+}
+
+struct Pedestrian {
+    x: f32,
+    speed: f32,
+    clothes: Color,
+    has_dog: bool,
+    step: f32,
+}
+
+struct Car {
+    x: f32,
+    speed: f32,
+    paint: Color,
+    /// Spray behind the wheels this frame: cells behind the car, rows
+    /// below the roof, and the drop.
+    spray: Vec<(i32, u16, char)>,
+}
+
+/// People walking along the front of the scene and cars passing on the
+/// road, busier at rush hour than at night.
+pub struct StreetLife {
+    pedestrians: Vec<Pedestrian>,
+    cars: Vec<Car>,
+    busyness: f32,
+    terminal_width: u16,
+}
+
+impl StreetLife {
+    pub fn new(terminal_width: u16) -> Self {
+        Self {
+            pedestrians: Vec::with_capacity(MAX_PEDESTRIANS),
+            cars: Vec::with_capacity(MAX_CARS),
+            busyness: busyness(12, 0),
+            terminal_width,
+        }
+    }
+
+    /// Wall-clock time at the location.
+    pub fn set_local_time(&mut self, time: NaiveDateTime) {
+        self.busyness = busyness(time.hour(), time.minute());
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        conditions: &StreetConditions,
+        rng: &mut impl Rng,
+    ) {
+        self.terminal_width = terminal_width;
+        let width = terminal_width as f32;
+        let frames = reference_frames(dt);
+
+        for pedestrian in &mut self.pedestrians {
+            // Hurrying through the rain
+            let pace = if conditions.raining { 1.4 } else { 1.0 };
+            pedestrian.x += pedestrian.speed * pace * frames;
+            pedestrian.step += pedestrian.speed.abs() * pace * frames;
+        }
+        self.pedestrians.retain(|p| p.x > -6.0 && p.x < width + 6.0);
+
+        for car in &mut self.cars {
+            car.x += car.speed * frames;
+            car.spray.clear();
+            if conditions.wet {
+                for i in 1..=4 {
+                    if rng.random::<f32>() < 0.6 {
+                        let ch = [',', '\'', '`', '.'][rng.random_range(0..4)];
+                        car.spray.push((i, 1 + rng.random_range(0..2), ch));
+                    }
+                }
+            }
+        }
+        self.cars.retain(|c| c.x > -12.0 && c.x < width + 12.0);
+
+        // Fewer people walk in the rain or cold; traffic keeps going
+        let deterrent = if conditions.raining {
+            0.4
+        } else if conditions.cold {
+            0.6
+        } else {
+            1.0
+        };
+        if self.pedestrians.len() < MAX_PEDESTRIANS
+            && rng.random::<f32>()
+                < chance_over(PEDESTRIAN_CHANCE * self.busyness * deterrent, frames)
+        {
+            let heading = if rng.random::<bool>() { 1.0 } else { -1.0 };
+            let clear_day = conditions.is_day && !conditions.wet && !conditions.cold;
+            self.pedestrians.push(Pedestrian {
+                x: if heading > 0.0 { -4.0 } else { width + 4.0 },
+                speed: heading * rng.random_range(0.05..0.09),
+                clothes: CLOTHES[rng.random_range(0..CLOTHES.len())],
+                has_dog: clear_day && rng.random::<f32>() < 0.35,
+                step: 0.0,
+            });
+        }
+
+        if self.cars.len() < MAX_CARS
+            && rng.random::<f32>() < chance_over(CAR_CHANCE * self.busyness, frames)
+        {
+            let heading = if rng.random::<bool>() { 1.0 } else { -1.0 };
+            self.cars.push(Car {
+                x: if heading > 0.0 { -10.0 } else { width + 10.0 },
+                speed: heading * rng.random_range(0.6..1.0),
+                paint: CAR_PAINT[rng.random_range(0..CAR_PAINT.len())],
+                spray: Vec::new(),
+            });
+        }
+    }
+
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        horizon_y: u16,
+        conditions: &StreetConditions,
+    ) -> io::Result<()> {
+        for pedestrian in &self.pedestrians {
+            self.render_pedestrian(renderer, pedestrian, horizon_y + SIDEWALK_ROW, conditions)?;
+        }
+        for car in &self.cars {
+            self.render_car(renderer, car, horizon_y + ROAD_ROW, conditions)?;
+        }
+        Ok(())
+    }
+
+    fn render_pedestrian(
+        &self,
+        renderer: &mut TerminalRenderer,
+        pedestrian: &Pedestrian,
+        head_y: u16,
+        conditions: &StreetConditions,
+    ) -> io::Result<()> {
+        let striding = (pedestrian.step as u32) % 2 == 0;
+        let skin = if conditions.is_day {
+            Color::White
+        } else {
+            Color::Grey
+        };
+        let (head, body) = if conditions.cold {
+            ("@", "[#]")
+        } else {
+            ("o", "/|\\")
+        };
+        let legs = if striding { "/ \\" } else { " | " };
+
+        let x = pedestrian.x.round() as i32;
+        if conditions.raining {
+            self.draw(
+                renderer,
+                x - 2,
+                head_y.saturating_sub(1),
+                "/^^^\\",
+                Color::DarkRed,
+            )?;
+            self.draw(renderer, x + 1, head_y, "|", Color::DarkGrey)?;
+        }
+        self.draw(renderer, x, head_y, head, skin)?;
+        self.draw(renderer, x - 1, head_y + 1, body, pedestrian.clothes)?;
+        self.draw(renderer, x - 1, head_y + 2, legs, Color::DarkGrey)?;
+
+        if pedestrian.has_dog {
+            // Trotting a few steps ahead on the lead
+            let heading_right = pedestrian.speed > 0.0;
+            let (dog_x, dog) = if heading_right {
+                (x + 3, ["\\__o", if striding { "/\\/\\" } else { " | |" }])
+            } else {
+                (x - 6, ["o__/", if striding { "/\\/\\" } else { "| | " }])
+            };
+            let lead_x = if heading_right { x + 2 } else { x - 2 };
+            self.draw(renderer, lead_x, head_y + 1, "-", Color::DarkGrey)?;
+            self.draw(renderer, dog_x, head_y + 1, dog[0], Color::DarkYellow)?;
+            self.draw(renderer, dog_x, head_y + 2, dog[1], Color::DarkYellow)?;
+        }
+        Ok(())
+    }
+
+    fn render_car(
+        &self,
+        renderer: &mut TerminalRenderer,
+        car: &Car,
+        roof_y: u16,
+        conditions: &StreetConditions,
+    ) -> io::Result<()> {
+        let heading_right = car.speed > 0.0;
+        let body = if heading_right {
+            ["  ____  ", "_/__|_\\_", "`-o---o-'"]
+        } else {
+            ["  ____  ", "_/_|__\\_", "'-o---o-`"]
+        };
+        let left = car.x.round() as i32 - 4;
+        for (row, line) in body.iter().enumerate() {
+            self.draw(renderer, left, roof_y + row as u16, line, car.paint)?;
+        }
+
+        let (front, back) = if heading_right {
+            (left + 8, left - 1)
+        } else {
+            (left - 1, left + 8)
+        };
+        let ahead = if heading_right { 1 } else { -1 };
+
+        if !conditions.is_day {
+            self.draw(renderer, front, roof_y + 1, "*", Color::Yellow)?;
+            self.draw(renderer, back, roof_y + 1, "*", Color::Red)?;
+            for i in 1..=HEADLIGHT_REACH as i32 {
+                let ch = if i % 2 == 0 { '-' } else { '=' };
+                let color = if i <= 3 {
+                    Color::Yellow
+                } else {
+                    Color::DarkYellow
+                };
+                self.draw_char(renderer, front + i * ahead, roof_y + 1, ch, color)?;
+            }
+        }
+
+        // Spray thrown up behind the wheels
+        for &(i, row, ch) in &car.spray {
+            self.draw_char(renderer, back - i * ahead, roof_y + row, ch, Color::Grey)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: i32,
+        y: u16,
+        text: &str,
+        color: Color,
+    ) -> io::Result<()> {
+        for (i, ch) in text.chars().enumerate() {
+            if ch != ' ' {
+                self.draw_char(renderer, x + i as i32, y, ch, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_char(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: i32,
+        y: u16,
+        ch: char,
+        color: Color,
+    ) -> io::Result<()> {
+        if x >= 0 && x < self.terminal_width as i32 {
+            renderer.render_char(x as u16, y, ch, color)?;
+        }
+        Ok(())
+    }
+}
+
+/// Busyness between the hourly values, so the street does not change all
+/// at once on the hour.
+fn busyness(hour: u32, minute: u32) -> f32 {
+    let hour = hour as usize % 24;
+    let now = HOURLY_BUSYNESS[hour];
+    let next = HOURLY_BUSYNESS[(hour + 1) % 24];
+    now + (next - now) * minute.min(59) as f32 / 60.0
+}
+
+impl Effect for StreetLife {
+    fn name(&self) -> &'static str {
+        "street"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(
+            dt,
+            ctx.width,
+            &StreetConditions::from_context(ctx),
+            &mut rng,
+        );
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        self.render(
+            renderer,
+            ctx.geometry.horizon_y,
+            &StreetConditions::from_context(ctx),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_busyness_follows_the_day() {
+        assert!(busyness(8, 0) > busyness(12, 0));
+        assert!(busyness(12, 0) > busyness(3, 0));
+        assert!(busyness(17, 30) > busyness(22, 30));
+        assert!(busyness(23, 59) < 0.1);
+    }
+
+    #[test]
+    fn test_cars_keep_their_speed_at_any_frame_rate() {
+        let conditions = StreetConditions {
+            is_day: true,
+            raining: false,
+            wet: false,
+            cold: false,
+        };
+        let distance_after_a_second = |fps: u32| {
+            let mut rng = StdRng::seed_from_u64(3);
+            let mut street = StreetLife::new(200);
+            street.cars.push(Car {
+                x: 0.0,
+                speed: 0.8,
+                paint: Color::Red,
+                spray: Vec::new(),
+            });
+            for _ in 0..fps {
+                street.update(Duration::from_secs(1) / fps, 200, &conditions, &mut rng);
+            }
+            street.cars[0].x
+        };
+
+        assert!((distance_after_a_second(60) - 48.0).abs() < 0.01);
+        assert!((distance_after_a_second(15) - 48.0).abs() < 0.01);
+    }
+}
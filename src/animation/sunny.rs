@@ -1,5 +1,13 @@
-use super::Animation;
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{Animation, AnimationController, DAY_NIGHT_FADE_TIME, faded};
+use crate::render::TerminalRenderer;
 use crossterm::style::Color;
+use rand::Rng;
+use std::io;
+use std::time::Duration;
+
+/// How long each frame of the sun stays up.
+const FRAME_DELAY: Duration = Duration::from_millis(500);
 
 pub struct SunnyAnimation {
     frames: Vec<Vec<String>>,
@@ -60,3 +68,70 @@ impl Default for SunnyAnimation {
         Self::new()
     }
 }
+
+/// The sun drawn over the sky while it is out and dry.
+pub struct Sun {
+    animation: SunnyAnimation,
+    controller: AnimationController,
+    since_frame: Duration,
+    /// How brightly the sun shows, from 0 to 1.
+    level: f32,
+}
+
+impl Sun {
+    pub fn new() -> Self {
+        Self {
+            animation: SunnyAnimation::new(),
+            controller: AnimationController::new(),
+            since_frame: Duration::ZERO,
+            level: 1.0,
+        }
+    }
+}
+
+impl Default for Sun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for Sun {
+    fn name(&self) -> &'static str {
+        "sun"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.show_sun && !ctx.precipitating()
+    }
+
+    fn fade_time(&self) -> Duration {
+        DAY_NIGHT_FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn update(&mut self, dt: Duration, _ctx: &EffectContext, _rng: &mut dyn Rng) {
+        self.since_frame += dt;
+        if self.since_frame >= FRAME_DELAY {
+            self.controller.next_frame(&self.animation);
+            self.since_frame = Duration::ZERO;
+        }
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, ctx: &EffectContext) -> io::Result<()> {
+        let y = if ctx.height > 20 { 3 } else { 2 };
+        let frame = self.animation.get_frame(self.controller.current_frame());
+        let color = faded(
+            self.animation.get_color(),
+            self.level,
+            renderer.color_support(),
+        );
+        renderer.render_centered_colored(frame, y, color)
+    }
+}
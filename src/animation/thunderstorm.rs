@@ -1,172 +1,376 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::reference_frames;
 use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
+use std::time::Duration;
 
-const MAX_BOLTS: usize = 10;
-
-#[derive(Clone, Copy, PartialEq)]
-enum LightningState {
-    Forming,
-    Strike,
-    Flash,
-    Fading,
-    Idle,
-}
+const MAX_BOLTS: usize = 4;
+const AFTERGLOW_FRAMES: f32 = 18.0;
+const FLASH_FRAMES: f32 = 4.0;
+/// Row just below the top bar where bolts leave the cloud base.
+const CLOUD_BASE_Y: u16 = 2;
+const SPEED_OF_SOUND_KM_S: f32 = 0.343;
+/// Beyond this the bolt itself is hidden and only sheet lightning shows.
+const VISIBLE_BOLT_KM: f32 = 6.0;
+const TREE_STRIKE_CHANCE: f32 = 0.35;
+/// How long a storm takes to build or blow over.
+const FADE_TIME: Duration = Duration::from_secs(10);
 
 struct LightningBolt {
     segments: Vec<(u16, u16, char)>,
-    age: u8,
-    max_age: u8,
+    impact: (u16, u16),
+    age: f32,
+}
+
+/// A burst of light centred on a column, lighting whatever is drawn nearby.
+struct Flash {
+    center_x: u16,
+    radius: u16,
+    color: Color,
+    frames_left: f32,
+}
+
+struct Rumble {
+    /// On the storm's clock.
+    start: Duration,
+    duration: Duration,
+    strength: i16,
 }
 
 pub struct ThunderstormSystem {
     bolts: VecDeque<LightningBolt>,
-    state: LightningState,
-    timer: u16,
+    flash: Option<Flash>,
+    rumbles: Vec<Rumble>,
     terminal_width: u16,
     terminal_height: u16,
-    flash_active: bool,
-    next_strike_in: u16,
+    geometry: SceneGeometry,
+    /// Time the storm has run, which thunder is scheduled against.
+    clock: Duration,
+    timer: f32,
+    next_strike_in: f32,
+    mean_interval: f32,
+    /// How fully the storm has arrived, from 0 to 1; strikes come slower
+    /// while it builds or passes.
+    level: f32,
 }
 
 impl ThunderstormSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
         Self {
             bolts: VecDeque::with_capacity(MAX_BOLTS),
-            state: LightningState::Idle,
-            timer: 0,
+            flash: None,
+            rumbles: Vec::new(),
             terminal_width,
             terminal_height,
-            flash_active: false,
-            next_strike_in: 60 + (rand::random::<u16>() % 120), // Random start delay
+            geometry: SceneGeometry::flat(terminal_width, terminal_height),
+            clock: Duration::ZERO,
+            timer: 0.0,
+            next_strike_in: 60.0 + (rng.random::<u16>() % 120) as f32, // Random start delay
+            mean_interval: strike_interval(false, None),
+            level: 1.0,
         }
     }
 
-    fn generate_bolt(&mut self, rng: &mut impl Rng) {
-        let start_x = (rng.random::<u16>() % (self.terminal_width - 10)) + 5;
-        let mut segments = Vec::new();
-        let mut x = start_x as i16;
-        let mut y = 2; // Start below top bar
+    pub fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        if self.geometry != *geometry {
+            self.geometry = geometry.clone();
+        }
+    }
+
+    /// Sets how active the storm is from the condition and the convective
+    /// energy reported by the API.
+    pub fn set_activity(&mut self, hail: bool, cape: Option<f64>) {
+        self.mean_interval = strike_interval(hail, cape);
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
+        self.terminal_width = terminal_width;
+        self.terminal_height = terminal_height;
 
-        segments.push((x as u16, y as u16, '+')); // Start point
+        let frames = reference_frames(dt);
+        self.clock += dt;
 
-        while y < (self.terminal_height - 5) as i16 {
-            let direction = (rng.random::<i8>() % 3) - 1; // -1, 0, 1
-            x += direction as i16;
-            y += 1;
+        self.bolts.retain_mut(|bolt| {
+            bolt.age += frames;
+            bolt.age < AFTERGLOW_FRAMES
+        });
 
-            // Constrain x
-            if x < 2 {
-                x = 2;
-            }
-            if x >= (self.terminal_width - 2) as i16 {
-                x = (self.terminal_width - 3) as i16;
+        if let Some(flash) = &mut self.flash {
+            flash.frames_left -= frames;
+            if flash.frames_left <= 0.0 {
+                self.flash = None;
             }
+        }
 
-            let char = match direction {
-                -1 => '/',
-                1 => '\\',
-                _ => '|',
-            };
+        let now = self.clock;
+        self.rumbles
+            .retain(|rumble| rumble.start + rumble.duration > now);
 
-            segments.push((x as u16, y as u16, char));
-
-            // Occasionally branch
-            if rng.random::<f32>() < 0.2 {
-                let branch_dir = -direction;
-                let mut bx = x + branch_dir as i16;
-                let mut by = y + 1;
-                for _ in 0..3 {
-                    if by < (self.terminal_height - 2) as i16 {
-                        segments.push((
-                            bx as u16,
-                            by as u16,
-                            if branch_dir < 0 { '/' } else { '\\' },
-                        ));
-                        bx += branch_dir as i16;
-                        by += 1;
-                    }
-                }
+        if self.timer >= self.next_strike_in {
+            self.strike(rng);
+            self.timer = 0.0;
+            self.next_strike_in = self.mean_interval * rng.random_range(0.5..1.5);
+        } else {
+            self.timer += frames * self.level;
+        }
+    }
+
+    fn strike(&mut self, rng: &mut impl Rng) {
+        let width = self.terminal_width;
+        if width < 10 {
+            return;
+        }
+
+        let distance_km = rng.random_range(0.5..12.0);
+
+        if distance_km <= VISIBLE_BOLT_KM
+            && let Some(bolt) = self.generate_bolt(rng)
+        {
+            let radius = (width as f32 * (0.35 - 0.02 * distance_km)).max(10.0) as u16;
+            self.flash = Some(Flash {
+                center_x: bolt.impact.0,
+                radius,
+                color: Color::White,
+                frames_left: FLASH_FRAMES,
+            });
+            self.bolts.push_back(bolt);
+            while self.bolts.len() > MAX_BOLTS {
+                self.bolts.pop_front();
             }
+        } else {
+            // Sheet lightning: the strike is hidden in the clouds
+            self.flash = Some(Flash {
+                center_x: rng.random_range(0..width),
+                radius: width / 3,
+                color: Color::Grey,
+                frames_left: FLASH_FRAMES - 1.0,
+            });
         }
 
-        self.bolts.push_back(LightningBolt {
-            segments,
-            age: 0,
-            max_age: 10,
+        self.rumbles.push(Rumble {
+            start: self.clock + Duration::from_secs_f32(distance_km / SPEED_OF_SOUND_KM_S),
+            duration: Duration::from_secs_f32(rng.random_range(0.4..1.2)),
+            strength: if distance_km < 3.0 { 2 } else { 1 },
         });
+    }
+
+    fn generate_bolt(&self, rng: &mut impl Rng) -> Option<LightningBolt> {
+        let width = self.terminal_width;
+        let trees = &self.geometry.lightning_targets;
 
-        while self.bolts.len() > MAX_BOLTS {
-            self.bolts.pop_front();
+        let target = if !trees.is_empty() && rng.random::<f32>() < TREE_STRIKE_CHANCE {
+            let (x, y) = trees[rng.random_range(0..trees.len())];
+            (x, y.saturating_sub(1))
+        } else {
+            let open = (0..10)
+                .map(|_| rng.random_range(2..width - 2))
+                .find(|&x| self.geometry.is_open_ground(x as i32));
+            (open?, self.geometry.horizon_y)
+        };
+
+        if target.0 >= width || target.1 <= CLOUD_BASE_Y + 3 || target.1 >= self.terminal_height {
+            return None;
         }
+
+        Some(LightningBolt {
+            segments: bolt_path(target, width, rng),
+            impact: target,
+            age: 0.0,
+        })
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
-        self.terminal_width = terminal_width;
-        self.terminal_height = terminal_height;
+    /// Light from the most recent flash: (center column, radius, color).
+    pub fn illumination(&self) -> Option<(u16, u16, Color)> {
+        self.flash.as_ref().and_then(|flash| {
+            // The return stroke flickers
+            (flash.frames_left.ceil() != 2.0).then_some((flash.center_x, flash.radius, flash.color))
+        })
+    }
 
-        match self.state {
-            LightningState::Idle => {
-                self.flash_active = false;
-                if self.timer >= self.next_strike_in {
-                    self.state = LightningState::Forming;
-                    self.timer = 0;
-                    self.generate_bolt(rng);
+    /// Horizontal sky shake while thunder rolls, in cells.
+    pub fn rumble_offset(&self) -> i16 {
+        let now = self.clock;
+        self.rumbles
+            .iter()
+            .filter(|rumble| rumble.start <= now)
+            .map(|rumble| {
+                let elapsed = now - rumble.start;
+                let sign = if (elapsed.as_millis() / 60) % 2 == 0 {
+                    1
                 } else {
-                    self.timer += 1;
-                }
-            }
-            LightningState::Forming => {
-                self.state = LightningState::Strike;
-                self.timer = 0;
-            }
-            LightningState::Strike => {
-                self.flash_active = true;
-                self.state = LightningState::Flash;
-                self.timer = 0;
-            }
-            LightningState::Flash => {
-                self.flash_active = false;
-                if self.timer > 2 {
-                    self.state = LightningState::Fading;
-                    self.timer = 0;
+                    -1
+                };
+                // Rolls off after the initial clap
+                let strength = if elapsed * 2 < rumble.duration {
+                    rumble.strength
                 } else {
-                    self.timer += 1;
+                    1
+                };
+                sign * strength
+            })
+            .max_by_key(|offset| offset.abs())
+            .unwrap_or(0)
+    }
+
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        for bolt in &self.bolts {
+            let color = match bolt.age as u8 {
+                0..=2 => Color::White,
+                3..=6 => Color::Yellow,
+                7..=11 => Color::DarkYellow,
+                _ => Color::DarkGrey,
+            };
+
+            for &(x, y, ch) in &bolt.segments {
+                if x < self.terminal_width && y < self.terminal_height {
+                    renderer.render_char(x, y, ch, color)?;
                 }
             }
-            LightningState::Fading => {
-                self.bolts.retain_mut(|bolt| {
-                    bolt.age += 1;
-                    bolt.age < bolt.max_age
-                });
-
-                if self.bolts.is_empty() {
-                    self.state = LightningState::Idle;
-                    self.timer = 0;
-                    self.next_strike_in = 30 + (rng.random::<u16>() % 200);
-                }
+
+            if bolt.age < 7.0 {
+                let (x, y) = bolt.impact;
+                renderer.render_char(x, y, '*', Color::White)?;
             }
         }
+        Ok(())
     }
+}
 
-    pub fn is_flashing(&self) -> bool {
-        self.flash_active
-    }
+/// Average frames between strikes. Hail storms are more vigorous, and
+/// convective energy (CAPE, J/kg) makes strikes more frequent.
+fn strike_interval(hail: bool, cape: Option<f64>) -> f32 {
+    let base = if hail { 90.0 } else { 130.0 };
+    let factor = cape.map_or(1.0, |cape| {
+        (1500.0 / (cape.max(0.0) + 500.0)).clamp(0.4, 2.0)
+    });
+    base * factor as f32
+}
 
-    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
-        let color = if self.flash_active {
-            Color::White
+/// Random-walks a main channel from the cloud base down to `target`, with
+/// short side branches that die out before reaching the ground.
+fn bolt_path(target: (u16, u16), width: u16, rng: &mut impl Rng) -> Vec<(u16, u16, char)> {
+    let (target_x, target_y) = (target.0 as i32, target.1 as i32);
+    let max_x = width as i32 - 1;
+    let drift = (target_y - CLOUD_BASE_Y as i32) / 2;
+    let mut x = (target_x + rng.random_range(-drift..=drift)).clamp(0, max_x);
+    let mut y = CLOUD_BASE_Y as i32;
+
+    let mut segments = vec![(x as u16, y as u16, '+')];
+
+    while y < target_y {
+        let remaining = target_y - y;
+        let offset = target_x - x;
+        let step = if offset.abs() >= remaining - 1 {
+            offset.signum()
         } else {
-            Color::Yellow
+            rng.random_range(-1..=1)
         };
+        x = (x + step).clamp(0, max_x);
+        y += 1;
+        segments.push((x as u16, y as u16, channel_char(step)));
 
-        for bolt in &self.bolts {
-            for segment in &bolt.segments {
-                renderer.render_char(segment.0, segment.1, segment.2, color)?;
+        if remaining > 4 && rng.random::<f32>() < 0.12 {
+            let dir = if step == 0 {
+                if rng.random::<bool>() { 1 } else { -1 }
+            } else {
+                -step
+            };
+            let (mut bx, mut by) = (x, y);
+            for _ in 0..rng.random_range(2..=6) {
+                bx += dir;
+                by += 1;
+                if bx < 0 || bx > max_x || by >= target_y - 1 {
+                    break;
+                }
+                segments.push((bx as u16, by as u16, channel_char(dir)));
             }
         }
+    }
+
+    segments
+}
+
+fn channel_char(step: i32) -> char {
+    match step {
+        s if s < 0 => '/',
+        s if s > 0 => '\\',
+        _ => '|',
+    }
+}
+
+impl Effect for ThunderstormSystem {
+    fn name(&self) -> &'static str {
+        "thunderstorm"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Foreground
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        ctx.conditions.is_thunderstorm
+    }
+
+    fn fade_time(&self) -> Duration {
+        FADE_TIME
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bolts.is_empty() && self.flash.is_none() && self.rumbles.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)?;
+        if let Some((x, radius, color)) = self.illumination() {
+            renderer.illuminate(x, radius, color)?;
+        }
         Ok(())
     }
+
+    fn set_geometry(&mut self, geometry: &SceneGeometry) {
+        self.set_geometry(geometry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strike_interval() {
+        assert_eq!(strike_interval(false, None), 130.0);
+        assert!(strike_interval(true, None) < strike_interval(false, None));
+        assert!(strike_interval(false, Some(3000.0)) < strike_interval(false, Some(200.0)));
+        assert_eq!(strike_interval(false, Some(0.0)), 260.0);
+    }
+
+    #[test]
+    fn test_bolt_reaches_target() {
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let path = bolt_path((40, 30), 80, &mut rng);
+            let main_end = path.iter().filter(|s| s.1 == 30).collect::<Vec<_>>();
+            assert_eq!(main_end.len(), 1);
+            assert_eq!((main_end[0].0, main_end[0].1), (40, 30));
+            assert!(path.iter().all(|s| s.0 < 80 && s.1 <= 30));
+        }
+    }
 }
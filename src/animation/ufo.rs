@@ -1,7 +1,10 @@
+use super::effect::{Effect, EffectContext, EffectLayer};
+use super::{chance_over, reference_frames};
 use crate::render::TerminalRenderer;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
+use std::time::Duration;
 
 #[derive(Clone)]
 struct Ufo {
@@ -15,7 +18,9 @@ pub struct UfoSystem {
     ufos: Vec<Ufo>,
     terminal_width: u16,
     terminal_height: u16,
-    spawn_cooldown: u16,
+    spawn_cooldown: f32,
+    /// Whether new ones still appear, from 0 to 1.
+    level: f32,
 }
 
 impl UfoSystem {
@@ -24,27 +29,37 @@ impl UfoSystem {
             ufos: Vec::with_capacity(1),
             terminal_width,
             terminal_height,
-            spawn_cooldown: 0,
+            spawn_cooldown: 0.0,
+            level: 1.0,
         }
     }
 
-    pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        terminal_width: u16,
+        terminal_height: u16,
+        rng: &mut impl Rng,
+    ) {
         self.terminal_width = terminal_width;
         self.terminal_height = terminal_height;
 
+        let frames = reference_frames(dt);
         for ufo in &mut self.ufos {
-            ufo.x += ufo.speed;
-            ufo.wobble_phase += 0.1;
-            ufo.y += ufo.wobble_phase.sin() * 0.2; // vertical wobble
+            ufo.x += ufo.speed * frames;
+            ufo.wobble_phase += 0.1 * frames;
+            ufo.y += ufo.wobble_phase.sin() * 0.2 * frames; // vertical wobble
         }
 
         self.ufos
             .retain(|p| p.x < terminal_width as f32 && p.x > -20.0);
 
-        self.spawn_cooldown = self.spawn_cooldown.saturating_sub(1);
-        if self.spawn_cooldown == 0 && rng.random::<f32>() < 0.005 {
+        self.spawn_cooldown = (self.spawn_cooldown - frames).max(0.0);
+        if self.spawn_cooldown == 0.0
+            && rng.random::<f32>() < chance_over(0.005 * self.level, frames)
+        {
             self.spawn_ufo(rng);
-            self.spawn_cooldown = 300 + (rng.random::<u16>() % 300); // Let it spawn relatively quickly for fun!
+            self.spawn_cooldown = 300.0 + (rng.random::<u16>() % 300) as f32; // Let it spawn relatively quickly for fun!
         }
     }
 
@@ -99,3 +114,33 @@ impl UfoSystem {
         Ok(())
     }
 }
+
+impl Effect for UfoSystem {
+    fn name(&self) -> &'static str {
+        "ufo"
+    }
+
+    fn layer(&self) -> EffectLayer {
+        EffectLayer::Sky
+    }
+
+    fn is_active(&self, ctx: &EffectContext) -> bool {
+        !ctx.conditions.is_day && !ctx.precipitating()
+    }
+
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.ufos.is_empty()
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &EffectContext, mut rng: &mut dyn Rng) {
+        self.update(dt, ctx.width, ctx.height, &mut rng);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, _ctx: &EffectContext) -> io::Result<()> {
+        self.render(renderer)
+    }
+}
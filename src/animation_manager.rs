@@ -1,252 +1,348 @@
+use crate::animation::clouds::CloudCover;
+use crate::animation::effect::{EffectContext, EffectLayer, EffectRegistry};
+#[cfg(feature = "scripting")]
+use crate::animation::scripted::{ScriptSystem, ScriptedEffect};
 use crate::animation::{
-    AnimationController, airplanes::AirplaneSystem, birds::BirdSystem, chimney::ChimneySmoke,
-    clouds::CloudSystem, fireflies::FireflySystem, fog::FogSystem, leaves::FallingLeaves,
-    moon::MoonSystem, raindrops::RaindropSystem, smog::Smog, snow::SnowSystem, stars::StarSystem,
-    sunny::SunnyAnimation, thunderstorm::ThunderstormSystem, ufo::UfoSystem,
+    Wind, airplanes::AirplaneSystem, aurora::AuroraSystem, birds::BirdSystem,
+    chimney::ChimneySmoke, clouds::CloudSystem, fireflies::FireflySystem,
+    fireworks::FireworksSystem, fog::FogSystem, hail::HailSystem, leaves::FallingLeaves,
+    moon::MoonSystem, rainbow::RainbowSystem, raindrops::RaindropSystem, smog::Smog,
+    snow::SnowSystem, sprites::Sprite, sprites::SpriteLayer, sprites::SpriteSystem,
+    stars::StarSystem, street::StreetLife, sunny::Sun, thunderstorm::ThunderstormSystem,
+    ufo::UfoSystem,
 };
 use crate::app_state::AppState;
 use crate::render::TerminalRenderer;
-use crate::scene::WorldScene;
-use crate::scene::house::House;
-use crate::weather::{FogIntensity, RainIntensity, SnowIntensity, WeatherConditions};
-use crossterm::style::Color;
+use crate::scene::SceneGeometry;
+use crate::scene::Season;
+use crate::weather::{Aircraft, FogIntensity, RainIntensity, SnowIntensity, sun_position};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rand::Rng;
 use std::io;
-use std::time::{Duration, Instant};
-
-const FRAME_DELAY: Duration = Duration::from_millis(500);
+use std::time::Instant;
 
 pub struct AnimationManager {
-    raindrop_system: RaindropSystem,
-    snow_system: SnowSystem,
-    fog_system: FogSystem,
-    thunderstorm_system: ThunderstormSystem,
-    cloud_system: CloudSystem,
-    bird_system: BirdSystem,
-    airplane_system: AirplaneSystem,
-    star_system: StarSystem,
-    moon_system: MoonSystem,
-    ufo_system: UfoSystem,
-    chimney_smoke: ChimneySmoke,
-    firefly_system: FireflySystem,
-    falling_leaves: FallingLeaves,
-    sunny_animation: SunnyAnimation,
-    smog_system: Smog,
-    animation_controller: AnimationController,
-    last_frame_time: Instant,
-    show_leaves: bool,
+    /// Every effect, in the order they are drawn.
+    effects: EffectRegistry,
+    season: Season,
+    /// The landscape's horizon, chimney and lights.
+    geometry: SceneGeometry,
+    /// When rain or a storm was last seen.
+    last_rain: Option<Instant>,
 }
 
 impl AnimationManager {
-    pub fn new(term_width: u16, term_height: u16, show_leaves: bool) -> Self {
+    /// Systems that start out with random state draw it from `rng`, the same
+    /// generator the frames are later drawn with.
+    /// `leaves` forces falling leaves on or off; `None` leaves them to
+    /// autumn.
+    pub fn new(
+        term_width: u16,
+        term_height: u16,
+        leaves: Option<bool>,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut falling_leaves = FallingLeaves::new(term_width, term_height, rng);
+        falling_leaves.set_forced(leaves);
+
+        let mut effects = EffectRegistry::new();
+        effects.register(AuroraSystem::new());
+        effects.register(StarSystem::new(term_width, term_height));
+        effects.register(MoonSystem::new(term_width, term_height));
+        effects.register(UfoSystem::new(term_width, term_height));
+        effects.register(FireflySystem::new(term_width, term_height));
+        effects.register(BirdSystem::new(term_width, term_height));
+        effects.register(Sun::new());
+        effects.register(RainbowSystem::new());
+        effects.register(SpriteSystem::new(SpriteLayer::Sky));
+        effects.register(CloudSystem::new(term_width, term_height));
+        effects.register(FireworksSystem::new(term_width, term_height));
+        effects.register(AirplaneSystem::new(term_width, term_height));
+        effects.register(ChimneySmoke::new());
+        effects.register(StreetLife::new(term_width));
+        effects.register(SpriteSystem::new(SpriteLayer::Ground));
+        effects.register(RaindropSystem::new(
+            term_width,
+            term_height,
+            RainIntensity::Light,
+            rng,
+        ));
+        effects.register(HailSystem::new(term_width, term_height));
+        effects.register(ThunderstormSystem::new(term_width, term_height, rng));
+        effects.register(SnowSystem::new(
+            term_width,
+            term_height,
+            SnowIntensity::Light,
+            rng,
+        ));
+        effects.register(FogSystem::new(term_width, term_height, FogIntensity::Light));
+        effects.register(falling_leaves);
+        #[cfg(feature = "scripting")]
+        effects.register(ScriptSystem::new());
+        effects.register(Smog::new(term_width, term_height));
+
         Self {
-            raindrop_system: RaindropSystem::new(term_width, term_height, RainIntensity::Light),
-            snow_system: SnowSystem::new(term_width, term_height, SnowIntensity::Light),
-            fog_system: FogSystem::new(term_width, term_height, FogIntensity::Light),
-            thunderstorm_system: ThunderstormSystem::new(term_width, term_height),
-            cloud_system: CloudSystem::new(term_width, term_height),
-            bird_system: BirdSystem::new(term_width, term_height),
-            airplane_system: AirplaneSystem::new(term_width, term_height),
-            star_system: StarSystem::new(term_width, term_height),
-            moon_system: MoonSystem::new(term_width, term_height),
-            ufo_system: UfoSystem::new(term_width, term_height),
-            chimney_smoke: ChimneySmoke::new(),
-            firefly_system: FireflySystem::new(term_width, term_height),
-            falling_leaves: FallingLeaves::new(term_width, term_height),
-            sunny_animation: SunnyAnimation::new(),
-            smog_system: Smog::new(term_width, term_height),
-            animation_controller: AnimationController::new(),
-            last_frame_time: Instant::now(),
-            show_leaves,
+            effects,
+            season: Season::default(),
+            geometry: SceneGeometry::flat(term_width, term_height),
+            last_rain: None,
+        }
+    }
+
+    /// Switches an effect on or off by its `[effects]` key.
+    pub fn set_effect_enabled(&mut self, name: &str, enabled: bool) {
+        self.effects.set_enabled(name, enabled);
+    }
+
+    pub fn set_season(&mut self, season: Season) {
+        self.season = season;
+    }
+
+    /// User sprites, each brought out on its own schedule.
+    pub fn set_sprites(&mut self, sprites: Vec<Sprite>) {
+        for system in self.effects.iter_mut::<SpriteSystem>() {
+            system.set_sprites(sprites.clone());
         }
     }
 
+    /// User particle scripts, run every frame in front of the scene.
+    #[cfg(feature = "scripting")]
+    pub fn set_scripts(&mut self, effects: Vec<ScriptedEffect>) {
+        if let Some(system) = self.effects.iter_mut::<ScriptSystem>().next() {
+            system.set_effects(effects);
+        }
+    }
+
+    /// Scripts that were stopped, and why.
+    #[cfg(feature = "scripting")]
+    pub fn script_errors(&self) -> Vec<String> {
+        self.effects
+            .get::<ScriptSystem>()
+            .map(|system| system.effects())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|effect| Some(format!("{}: {}", effect.name(), effect.error()?)))
+            .collect()
+    }
+
     pub fn update_rain_intensity(&mut self, intensity: RainIntensity) {
-        self.raindrop_system.set_intensity(intensity);
+        for rain in self.effects.iter_mut::<RaindropSystem>() {
+            rain.set_intensity(intensity);
+        }
     }
 
     pub fn update_snow_intensity(&mut self, intensity: SnowIntensity) {
-        self.snow_system.set_intensity(intensity);
+        for snow in self.effects.iter_mut::<SnowSystem>() {
+            snow.set_intensity(intensity);
+        }
     }
 
-    pub fn update_wind(&mut self, speed_kmh: f32, direction_deg: f32) {
-        self.raindrop_system.set_wind(speed_kmh, direction_deg);
-        self.snow_system.set_wind(speed_kmh, direction_deg);
+    pub fn update_wind(&mut self, speed_ms: f32, direction_deg: f32) {
+        let wind = Wind::new(speed_ms, direction_deg);
+        self.effects.for_each_mut(|effect| effect.set_wind(wind));
     }
 
-    pub fn update_fog_intensity(&mut self, intensity: FogIntensity) {
-        self.fog_system.set_intensity(intensity);
+    pub fn update_geometry(&mut self, geometry: &SceneGeometry) {
+        self.effects
+            .for_each_mut(|effect| effect.set_geometry(geometry));
+        self.geometry = geometry.clone();
     }
 
-    pub fn render_background(
-        &mut self,
-        renderer: &mut TerminalRenderer,
-        conditions: &WeatherConditions,
-        state: &AppState,
-        term_width: u16,
-        term_height: u16,
-        mut rng: &mut impl rand::Rng,
-    ) -> io::Result<()> {
-        // Calculate horizon_y early so it's available for all systems
-        let ground_height = WorldScene::GROUND_HEIGHT;
-        let horizon_y = term_height.saturating_sub(ground_height);
-
-        if !conditions.is_day {
-            self.star_system.update(term_width, term_height, &mut rng);
-            self.star_system.render(renderer)?;
-            self.moon_system.update(term_width, term_height);
-            self.moon_system.render(renderer)?;
-
-            if !conditions.is_raining && !conditions.is_thunderstorm && !conditions.is_snowing {
-                self.ufo_system.update(term_width, term_height, &mut rng);
-                self.ufo_system.render(renderer)?;
-            }
+    pub fn update_storm_activity(&mut self, hail: bool, cape: Option<f64>) {
+        for storm in self.effects.iter_mut::<ThunderstormSystem>() {
+            storm.set_activity(hail, cape);
+        }
+    }
 
-            if state.should_show_fireflies() {
-                self.firefly_system
-                    .update(term_width, term_height, horizon_y, &mut rng);
-                self.firefly_system.render(renderer)?;
-            }
+    pub fn set_fireworks(&mut self, active: bool) {
+        for fireworks in self.effects.iter_mut::<FireworksSystem>() {
+            fireworks.set_active(active);
         }
+    }
 
-        if !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-            && conditions.is_day
-        {
-            self.bird_system.update(term_width, term_height, &mut rng);
-            self.bird_system.render(renderer)?;
-        }
-
-        if state.should_show_sun()
-            && !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-        {
-            let animation_y = if term_height > 20 { 3 } else { 2 };
-            self.animation_controller
-                .render_frame(renderer, &self.sunny_animation, animation_y)?;
-        }
-
-        if conditions.is_cloudy
-            || (!conditions.is_raining && !conditions.is_thunderstorm && !conditions.is_snowing)
-        {
-            let (is_clear, cloud_color) = if let Some(weather) = &state.current_weather {
-                match weather.condition {
-                    crate::weather::WeatherCondition::Clear => (true, Color::White),
-                    crate::weather::WeatherCondition::PartlyCloudy => (false, Color::Grey),
-                    _ => (false, Color::DarkGrey),
-                }
-            } else {
-                (false, Color::DarkGrey)
-            };
-
-            if conditions.is_cloudy || is_clear {
-                self.cloud_system.set_cloud_color(is_clear);
-                self.cloud_system
-                    .update(term_width, term_height, is_clear, cloud_color, &mut rng);
-                self.cloud_system.render(renderer)?;
-            }
+    pub fn set_fireplace(&mut self, lit: bool) {
+        for smoke in self.effects.iter_mut::<ChimneySmoke>() {
+            smoke.set_burning(lit);
         }
+    }
 
-        if !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-            && !conditions.is_foggy
-        {
-            self.airplane_system
-                .update(term_width, term_height, &mut rng);
-            self.airplane_system.render(renderer)?;
+    pub fn set_local_time(&mut self, time: NaiveDateTime) {
+        for street in self.effects.iter_mut::<StreetLife>() {
+            street.set_local_time(time);
         }
+    }
 
-        Ok(())
+    pub fn set_live_traffic(&mut self, latitude: f64, longitude: f64, radius_km: f64) {
+        for airplanes in self.effects.iter_mut::<AirplaneSystem>() {
+            airplanes.set_live_traffic(latitude, longitude, radius_km);
+        }
     }
 
-    pub fn render_chimney_smoke(
-        &mut self,
-        renderer: &mut TerminalRenderer,
-        conditions: &WeatherConditions,
-        term_width: u16,
-        term_height: u16,
-        mut rng: &mut impl rand::Rng,
-    ) -> io::Result<()> {
-        if conditions.is_raining || conditions.is_thunderstorm {
-            return Ok(());
+    pub fn update_aircraft(&mut self, aircraft: Vec<Aircraft>) {
+        if let Some(airplanes) = self.effects.iter_mut::<AirplaneSystem>().next() {
+            airplanes.update_traffic(aircraft);
+        }
+    }
+
+    pub fn update_kp_index(&mut self, kp: f64) {
+        for aurora in self.effects.iter_mut::<AuroraSystem>() {
+            aurora.set_kp(kp);
         }
-        let ground_height = WorldScene::GROUND_HEIGHT;
-        let horizon_y = term_height.saturating_sub(ground_height);
-        let house_width = House::WIDTH;
-        let house_height = House::HEIGHT;
-        let house_x = (term_width / 2).saturating_sub(house_width / 2);
-        let house_y = horizon_y.saturating_sub(house_height);
-        let chimney_x = house_x + House::CHIMNEY_X_OFFSET;
-        let chimney_y = house_y;
+    }
 
-        self.chimney_smoke.update(chimney_x, chimney_y, &mut rng);
-        self.chimney_smoke.render(renderer)?;
+    pub fn simulate_aurora(&mut self) {
+        for aurora in self.effects.iter_mut::<AuroraSystem>() {
+            aurora.simulate();
+        }
+    }
 
-        Ok(())
+    pub fn simulate_rainbow(&mut self) {
+        for rainbow in self.effects.iter_mut::<RainbowSystem>() {
+            rainbow.simulate();
+        }
+    }
+
+    pub fn update_cloud_cover(&mut self, cover: CloudCover) {
+        for clouds in self.effects.iter_mut::<CloudSystem>() {
+            clouds.set_cover(cover);
+        }
+    }
+
+    pub fn update_fog_intensity(&mut self, intensity: FogIntensity) {
+        for fog in self.effects.iter_mut::<FogSystem>() {
+            fog.set_intensity(intensity);
+        }
     }
 
-    pub fn render_foreground(
+    /// Updates and draws the effects on one layer: the sky before the scene,
+    /// then what sits on the scene, then the foreground. Effects advance to
+    /// `now` and see the sky as it is at `utc`, so a fixed clock and seed
+    /// draw the same frames.
+    pub fn render_layer(
         &mut self,
+        layer: EffectLayer,
         renderer: &mut TerminalRenderer,
-        conditions: &WeatherConditions,
         state: &AppState,
-        term_width: u16,
-        term_height: u16,
-        mut rng: &mut impl rand::Rng,
+        now: Instant,
+        utc: DateTime<Utc>,
+        rng: &mut impl Rng,
     ) -> io::Result<()> {
-        if conditions.is_thunderstorm {
-            self.raindrop_system
-                .update(term_width, term_height, &mut rng);
-            self.raindrop_system.render(renderer)?;
-
-            self.thunderstorm_system
-                .update(term_width, term_height, &mut rng);
-            self.thunderstorm_system.render(renderer)?;
+        let (term_width, term_height) = renderer.get_size();
+        if state.weather_conditions.is_raining || state.weather_conditions.is_thunderstorm {
+            self.last_rain = Some(now);
+        }
+        let ctx = EffectContext {
+            conditions: &state.weather_conditions,
+            weather: state.current_weather.as_ref(),
+            location: &state.location,
+            geometry: &self.geometry,
+            season: self.season,
+            width: term_width,
+            height: term_height,
+            now,
+            utc,
+            last_rain: self.last_rain,
+            sun: sun_position(state.location.latitude, state.location.longitude, utc),
+            show_sun: state.should_show_sun(),
+            show_fireflies: state.should_show_fireflies(),
+            show_smog: state.should_show_smog(),
+            speed_multiplier: state.speed_multiplier,
+        };
+        self.effects.run(layer, renderer, &ctx, rng)?;
 
-            if self.thunderstorm_system.is_flashing() {
-                renderer.flash_screen()?;
+        // Thunder shakes the sky layer once the sound arrives
+        if layer == EffectLayer::Sky {
+            let shake = self
+                .effects
+                .get::<ThunderstormSystem>()
+                .map_or(0, |storm| storm.rumble_offset());
+            if shake != 0 {
+                renderer.shift_rows(0, self.geometry.horizon_y, shake)?;
             }
-        } else if conditions.is_raining {
-            self.raindrop_system
-                .update(term_width, term_height, &mut rng);
-            self.raindrop_system.render(renderer)?;
-        } else if conditions.is_snowing {
-            self.snow_system.update(term_width, term_height, &mut rng);
-            self.snow_system.render(renderer)?;
         }
 
-        if conditions.is_foggy {
-            self.fog_system.update(term_width, term_height, &mut rng);
-            self.fog_system.render(renderer)?;
-        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::effect::EFFECT_NAMES;
+    use crate::scene::WorldScene;
+    use crate::weather::{WeatherLocation, WeatherUnits};
+    use chrono::{TimeDelta, TimeZone};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::time::Duration;
 
-        if self.show_leaves
-            && !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-        {
-            self.falling_leaves
-                .update(term_width, term_height, &mut rng);
-            self.falling_leaves.render(renderer)?;
+    #[test]
+    fn test_every_effect_can_be_configured() {
+        let manager = AnimationManager::new(80, 24, None, &mut rand::rng());
+        let names: Vec<_> = manager.effects.names().collect();
+        for name in &names {
+            assert!(EFFECT_NAMES.contains(name), "{name} has no [effects] key");
+        }
+        for name in EFFECT_NAMES {
+            if *name != "scripts" || cfg!(feature = "scripting") {
+                assert!(
+                    names.contains(name),
+                    "[effects] key {name} matches no effect"
+                );
+            }
         }
+    }
 
-        self.smog_system.resize(term_width, term_height);
-        self.smog_system.update(state.should_show_smog(), state.speed_multiplier, conditions, &mut rng);
-        self.smog_system.render(renderer, state.should_show_smog(), conditions.is_day)?;
+    /// Every frame of a stormy night over the scene, seeded with `seed`, on a
+    /// fixed clock.
+    fn storm_frames(seed: u64) -> Vec<String> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut manager = AnimationManager::new(100, 30, Some(true), &mut rng);
+        let mut scene = WorldScene::new(100, 30);
+        scene.update_wind(Wind::new(12.0, 250.0));
+        manager.update_geometry(scene.geometry());
+        let mut renderer = TerminalRenderer::headless(100, 30);
+        let location = WeatherLocation {
+            latitude: 52.52,
+            longitude: 13.41,
+            elevation: None,
+            name: None,
+        };
+        let mut state = AppState::new(location, false, WeatherUnits::default());
+        state.weather_conditions.is_raining = true;
+        state.weather_conditions.is_thunderstorm = true;
+        state.weather_conditions.is_day = false;
+        manager.update_cloud_cover(CloudCover::estimated(70.0));
+        manager.update_wind(12.0, 250.0);
 
-        Ok(())
+        let start = Instant::now();
+        let evening = Utc.with_ymd_and_hms(2024, 10, 18, 21, 0, 0).unwrap();
+        (0..120)
+            .map(|frame| {
+                let now = start + Duration::from_millis(frame * 16);
+                let utc = evening + TimeDelta::milliseconds(frame as i64 * 16);
+                renderer.clear().unwrap();
+                manager
+                    .render_layer(EffectLayer::Sky, &mut renderer, &state, now, utc, &mut rng)
+                    .unwrap();
+                scene.update(now, utc, 1.0, state.weather_conditions.is_day);
+                manager.set_local_time(scene.local_now());
+                scene.render(&mut renderer).unwrap();
+                for layer in [EffectLayer::Scene, EffectLayer::Foreground] {
+                    manager
+                        .render_layer(layer, &mut renderer, &state, now, utc, &mut rng)
+                        .unwrap();
+                }
+                renderer.contents()
+            })
+            .collect()
     }
 
-    pub fn update_sunny_animation(&mut self, conditions: &WeatherConditions) {
-        if !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-            && self.last_frame_time.elapsed() >= FRAME_DELAY
-        {
-            self.animation_controller.next_frame(&self.sunny_animation);
-            self.last_frame_time = Instant::now();
-        }
+    #[test]
+    fn test_same_seed_draws_the_same_frames() {
+        let frames = storm_frames(7);
+        assert_eq!(frames, storm_frames(7));
+        assert_ne!(frames, storm_frames(8));
+        assert!(frames.last().unwrap().contains('|'));
     }
 }
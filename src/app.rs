@@ -1,16 +1,27 @@
+use crate::animation::clouds::CloudCover;
+use crate::animation::effect::EffectLayer;
+#[cfg(feature = "scripting")]
+use crate::animation::scripted;
+use crate::animation::{Wind, aurora, sprites};
 use crate::animation_manager::AnimationManager;
 use crate::app_state::AppState;
 use crate::config::Config;
-use crate::error::WeatherError;
+use crate::error::{ConfigError, WeatherError};
 use crate::render::TerminalRenderer;
-use crate::scene::WorldScene;
+use crate::scene::events::EventKind;
+use crate::scene::{Season, WorldScene};
+use crate::weather::astronomy::moon_phase;
 use crate::weather::{
-    OpenMeteoProvider, WeatherClient, WeatherCondition, WeatherData, WeatherLocation,
+    AdsbProvider, Aircraft, OpenMeteoProvider, SwpcKpProvider, WeatherClient, WeatherCondition,
+    WeatherData, WeatherLocation,
 };
+use chrono::{DateTime, NaiveDateTime, TimeDelta, TimeZone, Utc};
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use std::io;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 type UpdateTuple = (
@@ -19,13 +30,32 @@ type UpdateTuple = (
 );
 
 const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
-const INPUT_POLL_FPS: u64 = 60;
+/// SWPC publishes a new Kp value every three hours.
+const KP_REFRESH_INTERVAL: Duration = Duration::from_secs(900);
+/// Receivers rewrite `aircraft.json` about once a second.
+const ADSB_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where seeded runs start their clock, so the sky and the calendar come
+/// out the same every time.
+fn seeded_start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap()
+}
+
+/// The season on the scene's local date, in the location's hemisphere.
+fn current_season(scene: &WorldScene, location: &WeatherLocation) -> Season {
+    Season::from_date(scene.local_now().date(), location.latitude)
+}
 
-fn generate_offline_weather(rng: &mut impl rand::Rng) -> WeatherData {
-    use chrono::{Local, Timelike};
+/// Made-up weather for when no report can be fetched, at the local time
+/// `now` and the matching `utc`.
+fn generate_offline_weather(
+    now: NaiveDateTime,
+    utc: DateTime<Utc>,
+    rng: &mut impl rand::Rng,
+) -> WeatherData {
+    use chrono::Timelike;
     use rand::RngExt;
 
-    let now = Local::now();
     let hour = now.hour();
     let is_day = (6..18).contains(&hour);
 
@@ -51,10 +81,16 @@ fn generate_offline_weather(rng: &mut impl rand::Rng) -> WeatherData {
         wind_speed: rng.random_range(5.0..15.0),
         wind_direction: rng.random_range(0.0..360.0),
         cloud_cover: rng.random_range(20.0..80.0),
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: rng.random_range(1000.0..1020.0),
         visibility: Some(10000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day,
-        moon_phase: Some(0.5),
+        moon_phase: Some(moon_phase(utc)),
         timestamp: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
         hourly_forecast: None,
     }
@@ -65,9 +101,21 @@ pub struct App {
     animations: AnimationManager,
     scene: WorldScene,
     weather_receiver: mpsc::Receiver<UpdateTuple>,
+    kp_receiver: Option<mpsc::Receiver<Result<f64, WeatherError>>>,
+    adsb_receiver: Option<mpsc::Receiver<Result<Vec<Aircraft>, WeatherError>>>,
     hide_hud: bool,
     show_aqi: bool,
     show_hourly_forecast: bool,
+    /// Time between redraws; input is handled as it arrives in between.
+    frame_interval: Duration,
+    /// The one source of randomness for the scene, seeded from `--seed` when
+    /// given so a run can be reproduced.
+    rng: StdRng,
+    /// With `--seed`, frames run on a virtual clock from this instant and
+    /// time, exactly one frame interval apart, instead of the wall clock.
+    fixed_clock: Option<(Instant, DateTime<Utc>)>,
+    /// Frames drawn so far on the fixed clock.
+    frame: u32,
 }
 
 impl App {
@@ -75,10 +123,11 @@ impl App {
         config: &Config,
         simulate_condition: Option<String>,
         simulate_night: bool,
-        show_leaves: bool,
+        leaves: Option<bool>,
         term_width: u16,
         term_height: u16,
-    ) -> Self {
+        seed: Option<u64>,
+    ) -> Result<Self, ConfigError> {
         let location = WeatherLocation {
             latitude: config.location.latitude,
             longitude: config.location.longitude,
@@ -86,57 +135,95 @@ impl App {
             name: config.location.name.clone(),
         };
 
-        let mut state = AppState::new(location.clone(), config.location.hide, config.units);
-        let mut animations = AnimationManager::new(term_width, term_height, show_leaves);
-        let scene = WorldScene::new(term_width, term_height);
+        let state = AppState::new(location.clone(), config.location.hide, config.units);
+        let mut rng = seed.map_or_else(rand::make_rng, StdRng::seed_from_u64);
+        let fixed_clock = seed.map(|_| (Instant::now(), seeded_start()));
+        let start_utc = fixed_clock.map_or_else(Utc::now, |(_, utc)| utc);
+        let mut animations = AnimationManager::new(term_width, term_height, leaves, &mut rng);
+        for (name, enabled) in config.effects.toggles() {
+            animations.set_effect_enabled(name, enabled);
+        }
+        let mut scene = WorldScene::new(term_width, term_height);
+        scene.set_utc(start_utc);
+        scene.set_events(config.events.calendar());
+        match config.scene.file_path()? {
+            Some(path) => scene.load_landscape(&path)?,
+            None => scene.set_landscape(config.scene.theme.build()),
+        }
+        scene.set_fireplace_temperature(config.house.fireplace_temperature);
+        let season = current_season(&scene, &location);
+        scene.set_season(season);
+        animations.set_season(season);
+        animations.update_geometry(scene.geometry());
+        if let Ok(dir) = Config::sprites_dir() {
+            animations.set_sprites(sprites::load_dir(&dir)?);
+        }
+        #[cfg(feature = "scripting")]
+        if config.scripting.enabled {
+            if let Ok(dir) = Config::scripts_dir() {
+                let budget = Duration::from_secs_f64(config.scripting.budget_ms / 1000.0);
+                animations.set_scripts(scripted::load_dir(&dir, budget)?);
+            }
+        }
 
         let (tx, rx) = mpsc::channel(1);
         let show_aqi = config.show_aqi;
+        let mut kp_receiver = None;
+        let mut adsb_receiver = None;
+        let mut simulated = None;
 
         if let Some(ref condition_str) = simulate_condition {
-            let simulated_condition =
+            // A rainbow is a clear sky right after a shower, an aurora a
+            // clear night
+            let simulate_rainbow = condition_str.eq_ignore_ascii_case("rainbow");
+            let simulate_aurora = condition_str.eq_ignore_ascii_case("aurora");
+            let simulated_condition = if simulate_rainbow || simulate_aurora {
+                WeatherCondition::Clear
+            } else {
                 condition_str
                     .parse::<WeatherCondition>()
                     .unwrap_or_else(|e| {
                         eprintln!("{}", e);
                         WeatherCondition::Clear
-                    });
+                    })
+            };
+
+            let freezing = simulated_condition.is_snowing()
+                || simulated_condition == WeatherCondition::FreezingRain;
 
             let weather = WeatherData {
                 condition: simulated_condition,
-                temperature: 20.0,
-                apparent_temperature: 19.0,
+                temperature: if freezing { -3.0 } else { 20.0 },
+                apparent_temperature: if freezing { -6.0 } else { 19.0 },
                 humidity: 65.0,
                 precipitation: if simulated_condition.is_raining() {
                     2.5
+                } else if simulate_rainbow {
+                    0.3
                 } else {
                     0.0
                 },
                 wind_speed: if simulated_condition.is_thunderstorm() {
-                    45.0
+                    12.5
                 } else {
-                    10.0
+                    2.8
                 },
                 wind_direction: 225.0,
-                cloud_cover: 50.0,
+                cloud_cover: simulated_condition.typical_cloud_cover(),
+                cloud_cover_low: None,
+                cloud_cover_mid: None,
+                cloud_cover_high: None,
                 pressure: 1013.0,
                 visibility: Some(10000.0),
-                is_day: !simulate_night,
-                moon_phase: Some(0.5),
+                snow_depth: None,
+                cape: None,
+                utc_offset_seconds: None,
+                is_day: !simulate_night && !simulate_aurora,
+                moon_phase: Some(moon_phase(start_utc)),
                 timestamp: "simulated".to_string(),
                 hourly_forecast: None,
             };
-
-            let rain_intensity = weather.condition.rain_intensity();
-            let snow_intensity = weather.condition.snow_intensity();
-
-            let wind_speed = weather.wind_speed;
-            let wind_direction = weather.wind_direction;
-
-            state.update_weather(weather);
-            animations.update_rain_intensity(rain_intensity);
-            animations.update_snow_intensity(snow_intensity);
-            animations.update_wind(wind_speed as f32, wind_direction as f32);
+            simulated = Some((weather, simulate_rainbow, simulate_aurora));
         } else {
             let provider = Arc::new(OpenMeteoProvider::new());
             let weather_client = WeatherClient::new(provider, REFRESH_INTERVAL);
@@ -163,22 +250,127 @@ impl App {
                     tokio::time::sleep(REFRESH_INTERVAL).await;
                 }
             });
+
+            if config.aurora.enabled && config.location.latitude.abs() >= aurora::MIN_LATITUDE {
+                let (kp_tx, kp_rx) = mpsc::channel(1);
+                let kp_provider = SwpcKpProvider::new(config.aurora.kp_url.clone());
+                kp_receiver = Some(kp_rx);
+
+                tokio::spawn(async move {
+                    loop {
+                        let kp = kp_provider.get_current_kp().await;
+                        if kp_tx.send(kp).await.is_err() {
+                            break;
+                        }
+                        tokio::time::sleep(KP_REFRESH_INTERVAL).await;
+                    }
+                });
+            }
+
+            if let Some(ref url) = config.adsb.url {
+                animations.set_live_traffic(
+                    config.location.latitude,
+                    config.location.longitude,
+                    config.adsb.radius_km,
+                );
+                let (adsb_tx, adsb_rx) = mpsc::channel(1);
+                let adsb_provider = AdsbProvider::new(url.clone());
+                adsb_receiver = Some(adsb_rx);
+
+                tokio::spawn(async move {
+                    loop {
+                        let aircraft = adsb_provider.get_aircraft().await;
+                        if adsb_tx.send(aircraft).await.is_err() {
+                            break;
+                        }
+                        tokio::time::sleep(ADSB_REFRESH_INTERVAL).await;
+                    }
+                });
+            }
         }
 
-        Self {
+        let mut app = Self {
             state,
             animations,
             scene,
             weather_receiver: rx,
+            kp_receiver,
+            adsb_receiver,
             hide_hud: config.hide_hud,
             show_aqi: config.show_aqi,
             show_hourly_forecast: config.show_hourly_forecast,
+            frame_interval: Duration::from_secs_f64(1.0 / config.animation.fps.max(1) as f64),
+            rng,
+            fixed_clock,
+            frame: 0,
+        };
+        if let Some((weather, rainbow, aurora)) = simulated {
+            app.apply_weather(weather);
+            if rainbow {
+                app.animations.simulate_rainbow();
+            }
+            if aurora {
+                app.animations.simulate_aurora();
+            }
         }
+        Ok(app)
+    }
+
+    /// The instant and time the next frame shows: the wall clock, or with
+    /// `--seed` the fixed clock moved on by one frame interval per frame.
+    fn frame_time(&mut self) -> (Instant, DateTime<Utc>) {
+        let Some((start, start_utc)) = self.fixed_clock else {
+            return (Instant::now(), Utc::now());
+        };
+        let elapsed = self.frame_interval * self.frame;
+        self.frame = self.frame.saturating_add(1);
+        let utc = start_utc + TimeDelta::from_std(elapsed).unwrap_or_default();
+        (start + elapsed, utc)
+    }
+
+    /// Hands a weather report to the state, the scene and the effects.
+    fn apply_weather(&mut self, weather: WeatherData) {
+        let wind_speed = weather.wind_speed as f32;
+        let wind_direction = weather.wind_direction as f32;
+
+        self.scene.update_weather(&weather);
+        self.scene
+            .update_wind(Wind::new(wind_speed, wind_direction));
+        self.animations
+            .update_storm_activity(weather.condition.is_hail(), weather.cape);
+        self.animations
+            .update_rain_intensity(weather.condition.rain_intensity());
+        self.animations
+            .update_snow_intensity(weather.condition.snow_intensity());
+        self.animations
+            .update_fog_intensity(weather.condition.fog_intensity());
+        self.animations.update_wind(wind_speed, wind_direction);
+        self.animations
+            .update_cloud_cover(CloudCover::from_weather(&weather));
+        self.state.update_weather(weather);
+    }
+
+    /// Particle scripts that were stopped, and why, to report on exit.
+    #[cfg(feature = "scripting")]
+    pub fn script_errors(&self) -> Vec<String> {
+        self.animations.script_errors()
     }
 
     pub async fn run(&mut self, renderer: &mut TerminalRenderer) -> io::Result<()> {
-        let mut rng = rand::rng();
-        loop {
+        'frames: loop {
+            let paced_from = Instant::now();
+            let (frame_start, utc) = self.frame_time();
+
+            // The aurora simply stays dark if the Kp feed is unavailable
+            if let Some(Ok(Ok(kp))) = self.kp_receiver.as_mut().map(|rx| rx.try_recv()) {
+                self.animations.update_kp_index(kp);
+            }
+
+            // Aircraft fade out on their own once reports stop arriving
+            if let Some(Ok(Ok(aircraft))) = self.adsb_receiver.as_mut().map(|rx| rx.try_recv()) {
+                self.animations.update_aircraft(aircraft);
+            }
+
             if let Ok((weather_result, aqi_result_opt)) = self.weather_receiver.try_recv() {
                 if let Some(aqi_result) = aqi_result_opt {
                     match aqi_result {
@@ -189,18 +381,13 @@ impl App {
 
                 match weather_result {
                     Ok(weather) => {
-                        let rain_intensity = weather.condition.rain_intensity();
-                        let snow_intensity = weather.condition.snow_intensity();
-                        let fog_intensity = weather.condition.fog_intensity();
-                        let wind_speed = weather.wind_speed;
-                        let wind_direction = weather.wind_direction;
-
-                        self.state.update_weather(weather);
-                        self.animations.update_rain_intensity(rain_intensity);
-                        self.animations.update_snow_intensity(snow_intensity);
-                        self.animations.update_fog_intensity(fog_intensity);
-                        self.animations
-                            .update_wind(wind_speed as f32, wind_direction as f32);
+                        self.apply_weather(weather);
+
+                        // Long sessions can run into the next season
+                        let season = current_season(&self.scene, &self.state.location);
+                        self.scene.set_season(season);
+                        self.animations.set_season(season);
+                        self.animations.update_geometry(self.scene.geometry());
                     }
                     Err(error) => {
                         let _error_msg = match &error {
@@ -209,20 +396,13 @@ impl App {
                         };
 
                         if self.state.current_weather.is_none() {
-                            let offline_weather = generate_offline_weather(&mut rng);
-                            let rain_intensity = offline_weather.condition.rain_intensity();
-                            let snow_intensity = offline_weather.condition.snow_intensity();
-                            let fog_intensity = offline_weather.condition.fog_intensity();
-                            let wind_speed = offline_weather.wind_speed;
-                            let wind_direction = offline_weather.wind_direction;
-
-                            self.state.update_weather(offline_weather);
+                            let offline_weather = generate_offline_weather(
+                                self.scene.local_now(),
+                                self.scene.utc(),
+                                &mut self.rng,
+                            );
+                            self.apply_weather(offline_weather);
                             self.state.set_offline_mode(true);
-                            self.animations.update_rain_intensity(rain_intensity);
-                            self.animations.update_snow_intensity(snow_intensity);
-                            self.animations.update_fog_intensity(fog_intensity);
-                            self.animations
-                                .update_wind(wind_speed as f32, wind_direction as f32);
                         } else {
                             self.state.set_offline_mode(true);
                         }
@@ -234,33 +414,43 @@ impl App {
 
             let (term_width, term_height) = renderer.get_size();
 
-            self.animations.render_background(
+            self.animations.render_layer(
+                EffectLayer::Sky,
                 renderer,
-                &self.state.weather_conditions,
                 &self.state,
-                term_width,
-                term_height,
-                &mut rng,
+                frame_start,
+                utc,
+                &mut self.rng,
             )?;
 
-            self.scene
-                .render(renderer, &self.state.weather_conditions)?;
+            self.scene.update(
+                frame_start,
+                utc,
+                self.state.speed_multiplier,
+                self.state.weather_conditions.is_day,
+            );
+            self.animations
+                .set_fireworks(self.scene.has_event(EventKind::Fireworks));
+            self.animations.set_local_time(self.scene.local_now());
+            self.animations.set_fireplace(self.scene.fireplace_lit());
+            self.scene.render(renderer)?;
 
-            self.animations.render_chimney_smoke(
+            self.animations.render_layer(
+                EffectLayer::Scene,
                 renderer,
-                &self.state.weather_conditions,
-                term_width,
-                term_height,
-                &mut rng,
+                &self.state,
+                frame_start,
+                utc,
+                &mut self.rng,
             )?;
 
-            self.animations.render_foreground(
+            self.animations.render_layer(
+                EffectLayer::Foreground,
                 renderer,
-                &self.state.weather_conditions,
                 &self.state,
-                term_width,
-                term_height,
-                &mut rng,
+                frame_start,
+                utc,
+                &mut self.rng,
             )?;
 
             if self.show_hourly_forecast {
@@ -287,20 +477,23 @@ impl App {
 
             renderer.flush()?;
 
-            let current_poll_fps = INPUT_POLL_FPS as f32 * self.state.speed_multiplier;
-            let current_frame_duration = Duration::from_millis((1000.0 / current_poll_fps.max(1.0)) as u64);
-
-            if event::poll(current_frame_duration)? {
+            // Handle input as it arrives until the next frame is due
+            let next_frame = paced_from + self.frame_interval;
+            loop {
+                let timeout = next_frame.saturating_duration_since(Instant::now());
+                if timeout.is_zero() || !event::poll(timeout)? {
+                    break;
+                }
                 match event::read()? {
                     Event::Resize(width, height) => {
                         renderer.manual_resize(width, height)?;
                     }
                     Event::Key(key_event) => match key_event.code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') => break,
+                        KeyCode::Char('q') | KeyCode::Char('Q') => break 'frames,
                         KeyCode::Char('c')
                             if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
                         {
-                            break;
+                            break 'frames;
                         }
                         KeyCode::Char('+') | KeyCode::Char('=') => {
                             self.state.speed_multiplier = (self.state.speed_multiplier + 0.25).min(4.0);
@@ -318,9 +511,7 @@ impl App {
 
             let (term_width, term_height) = renderer.get_size();
             self.scene.update_size(term_width, term_height);
-
-            self.animations
-                .update_sunny_animation(&self.state.weather_conditions);
+            self.animations.update_geometry(self.scene.geometry());
         }
 
         Ok(())
@@ -360,7 +551,7 @@ impl App {
                     }
 
                     // Time
-                    let time_str = forecast.time.split('T').last().unwrap_or("00:00");
+                    let time_str = forecast.time.split('T').next_back().unwrap_or("00:00");
                     let time_display = format!("{:^12}", time_str);
                     renderer.render_line_colored(
                         col_x as u16,
@@ -1,6 +1,6 @@
 use crate::weather::{
     WeatherCondition, WeatherConditions, WeatherData, WeatherLocation, WeatherUnits,
-    format_precipitation, format_temperature, format_wind_speed,
+    beaufort_scale, compass_direction, format_precipitation, format_temperature, format_wind_speed,
 };
 use std::time::Instant;
 
@@ -37,6 +37,8 @@ impl AppState {
 
     pub fn update_weather(&mut self, weather: WeatherData) {
         self.weather_conditions.is_thunderstorm = weather.condition.is_thunderstorm();
+        self.weather_conditions.is_hail = weather.condition.is_hail();
+        self.weather_conditions.is_freezing_rain = weather.condition.is_freezing_rain();
         self.weather_conditions.is_snowing = weather.condition.is_snowing();
         self.weather_conditions.is_raining =
             weather.condition.is_raining() && !self.weather_conditions.is_thunderstorm;
@@ -136,13 +138,15 @@ impl AppState {
             };
 
             format!(
-                "{}Weather: {} | Temp: {:.1}{} | Wind: {:.1}{} | Precip: {:.1}{}{}{} | +/- to change speed | Press 'q' to quit",
+                "{}Weather: {} | Temp: {:.1}{} | Wind: {:.1}{} {} (Bft {}) | Precip: {:.1}{}{}{} | +/- to change speed | Press 'q' to quit",
                 offline_indicator,
                 self.get_condition_text(),
                 temp,
                 temp_unit,
                 wind,
                 wind_unit,
+                compass_direction(weather.wind_direction),
+                beaufort_scale(weather.wind_speed),
                 precip,
                 precip_unit,
                 location_str,
@@ -249,8 +253,14 @@ mod tests {
             wind_speed: 10.0,
             wind_direction: 0.0,
             cloud_cover: 0.0,
+            cloud_cover_low: None,
+            cloud_cover_mid: None,
+            cloud_cover_high: None,
             pressure: 1013.0,
             visibility: Some(10.0),
+            snow_depth: None,
+            cape: None,
+            utc_offset_seconds: None,
             is_day: true,
             moon_phase: Some(0.5),
             timestamp: "2024-01-01T12:00:00Z".to_string(),
@@ -316,6 +326,14 @@ mod tests {
         assert!(app.cached_weather_info.contains("139.65°E"));
     }
 
+    #[test]
+    fn test_wind_direction_and_beaufort() {
+        let mut app = create_app_state(51.5074, -0.1278);
+        app.update_cached_info();
+
+        assert!(app.cached_weather_info.contains("Wind: 36.0km/h N (Bft 5)"));
+    }
+
     #[test]
     fn test_equator_prime_meridian() {
         // Null Island: 0°, 0° (exactly at equator and prime meridian)
@@ -1,8 +1,14 @@
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::animation::effect::EFFECT_NAMES;
 use crate::error::ConfigError;
+use crate::scene::SceneTheme;
+use crate::scene::events::{CalendarEvent, builtin_events};
+use crate::scene::house::DEFAULT_FIREPLACE_TEMPERATURE;
+use crate::weather::kp_index::SWPC_KP_URL;
 use crate::weather::types::WeatherUnits;
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -19,6 +25,227 @@ pub struct Config {
     pub show_aqi: bool,
     #[serde(default)]
     pub show_hourly_forecast: bool,
+    #[serde(default)]
+    pub events: EventsConfig,
+    #[serde(default)]
+    pub aurora: AuroraConfig,
+    #[serde(default)]
+    pub adsb: AdsbConfig,
+    #[serde(default)]
+    pub house: HouseConfig,
+    #[serde(default)]
+    pub scene: SceneConfig,
+    #[serde(default)]
+    #[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+    pub scripting: ScriptingConfig,
+    #[serde(default)]
+    pub effects: EffectsConfig,
+    #[serde(default)]
+    pub animation: AnimationConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventsConfig {
+    /// December lights, Halloween pumpkin and New Year fireworks.
+    #[serde(default = "default_builtin_events")]
+    pub builtin: bool,
+    #[serde(default)]
+    pub custom: Vec<CalendarEvent>,
+}
+
+fn default_builtin_events() -> bool {
+    true
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            builtin: default_builtin_events(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl EventsConfig {
+    /// Built-in events, if enabled, followed by the user's own.
+    pub fn calendar(&self) -> Vec<CalendarEvent> {
+        let mut events = if self.builtin {
+            builtin_events()
+        } else {
+            Vec::new()
+        };
+        events.extend(self.custom.iter().cloned());
+        events
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AuroraConfig {
+    /// Fetch the Kp index when the location is far enough north or south.
+    #[serde(default = "default_aurora_enabled")]
+    pub enabled: bool,
+    /// SWPC planetary K-index feed, or a local file in the same format.
+    #[serde(default = "default_kp_url")]
+    pub kp_url: String,
+}
+
+fn default_aurora_enabled() -> bool {
+    true
+}
+
+fn default_kp_url() -> String {
+    SWPC_KP_URL.to_string()
+}
+
+impl Default for AuroraConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_aurora_enabled(),
+            kp_url: default_kp_url(),
+        }
+    }
+}
+
+/// Real aircraft from a local dump1090 or readsb receiver, in place of the
+/// random planes.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AdsbConfig {
+    /// The receiver's `aircraft.json`, by URL or local path.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Aircraft further away than this are left out.
+    #[serde(default = "default_adsb_radius_km")]
+    pub radius_km: f64,
+}
+
+fn default_adsb_radius_km() -> f64 {
+    50.0
+}
+
+impl Default for AdsbConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            radius_km: default_adsb_radius_km(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HouseConfig {
+    /// Apparent temperature in °C below which the fireplace is lit and the
+    /// chimney smokes.
+    #[serde(default = "default_fireplace_temperature")]
+    pub fireplace_temperature: f64,
+}
+
+fn default_fireplace_temperature() -> f64 {
+    DEFAULT_FIREPLACE_TEMPERATURE
+}
+
+impl Default for HouseConfig {
+    fn default() -> Self {
+        Self {
+            fireplace_temperature: default_fireplace_temperature(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct SceneConfig {
+    /// Landscape the weather plays over.
+    #[serde(default)]
+    pub theme: SceneTheme,
+    /// Scene file drawn instead of the theme: a name from the `scenes`
+    /// folder of the config directory, or a path to a `.toml` file.
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+}
+
+impl SceneConfig {
+    pub fn file_path(&self) -> Result<Option<PathBuf>, ConfigError> {
+        let Some(file) = &self.file else {
+            return Ok(None);
+        };
+        if file.is_absolute() {
+            return Ok(Some(file.clone()));
+        }
+        let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
+        Ok(Some(resolve_scene_file(&config_dir.join("weathr"), file)))
+    }
+}
+
+/// A bare name such as `office` means `scenes/office.toml`; anything else is
+/// relative to the config directory.
+fn resolve_scene_file(weathr_dir: &Path, file: &Path) -> PathBuf {
+    if file.components().count() == 1 && file.extension().is_none() {
+        weathr_dir.join("scenes").join(file).with_extension("toml")
+    } else {
+        weathr_dir.join(file)
+    }
+}
+
+/// Rhai particle scripts, for builds with the `scripting` feature.
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct ScriptingConfig {
+    #[serde(default = "default_scripting_enabled")]
+    pub enabled: bool,
+    /// Milliseconds each script may run per frame before it is cut off.
+    #[serde(default = "default_script_budget_ms")]
+    pub budget_ms: f64,
+}
+
+fn default_scripting_enabled() -> bool {
+    true
+}
+
+fn default_script_budget_ms() -> f64 {
+    2.0
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_scripting_enabled(),
+            budget_ms: default_script_budget_ms(),
+        }
+    }
+}
+
+/// Effects switched on or off by name, such as `ufo = false`. Effects
+/// not listed stay on.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(transparent)]
+pub struct EffectsConfig {
+    toggles: BTreeMap<String, bool>,
+}
+
+impl EffectsConfig {
+    pub fn toggles(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.toggles
+            .iter()
+            .map(|(name, &enabled)| (name.as_str(), enabled))
+    }
+}
+
+/// How often the scene is redrawn. Animations move at the same speed
+/// whatever the frame rate.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnimationConfig {
+    /// Frames drawn per second, from 1 to 240.
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+}
+
+fn default_fps() -> u32 {
+    60
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self { fps: default_fps() }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -80,6 +307,22 @@ impl Config {
             return Err(ConfigError::InvalidLongitude(self.location.longitude));
         }
 
+        if let Some((name, _)) = self
+            .effects
+            .toggles()
+            .find(|(name, _)| !EFFECT_NAMES.contains(name))
+        {
+            return Err(ConfigError::UnknownEffect(name.to_string()));
+        }
+
+        if !(1..=240).contains(&self.animation.fps) {
+            return Err(ConfigError::InvalidFps(self.animation.fps));
+        }
+
+        if !(0.0..=1000.0).contains(&self.scripting.budget_ms) {
+            return Err(ConfigError::InvalidScriptBudget(self.scripting.budget_ms));
+        }
+
         Ok(())
     }
 
@@ -92,6 +335,19 @@ impl Config {
         toml::from_str(&content).map_err(ConfigError::ParseError)
     }
 
+    /// Folder of sprite files, one `.toml` file per sprite.
+    pub fn sprites_dir() -> Result<PathBuf, ConfigError> {
+        let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
+        Ok(config_dir.join("weathr").join("sprites"))
+    }
+
+    /// Folder of Rhai particle scripts.
+    #[cfg(feature = "scripting")]
+    pub fn scripts_dir() -> Result<PathBuf, ConfigError> {
+        let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
+        Ok(config_dir.join("weathr").join("scripts"))
+    }
+
     fn get_config_path() -> Result<PathBuf, ConfigError> {
         let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
         Ok(config_dir.join("weathr").join("config.toml"))
@@ -228,6 +484,7 @@ longitude = 0.0
             show_hourly_forecast: false,
             units: WeatherUnits::default(),
             silent: false,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -249,6 +506,7 @@ longitude = 0.0
             show_hourly_forecast: false,
             units: WeatherUnits::default(),
             silent: false,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -270,6 +528,7 @@ longitude = 0.0
             show_hourly_forecast: false,
             units: WeatherUnits::default(),
             silent: false,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -291,6 +550,7 @@ longitude = 0.0
             show_hourly_forecast: false,
             units: WeatherUnits::default(),
             silent: false,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -312,6 +572,7 @@ longitude = 0.0
             show_hourly_forecast: false,
             units: WeatherUnits::default(),
             silent: false,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_ok());
@@ -365,4 +626,189 @@ precipitation = "inch"
             crate::weather::types::PrecipitationUnit::Inch
         );
     }
+
+    #[test]
+    fn test_config_custom_events() {
+        let toml_content = r#"
+[events]
+builtin = false
+
+[[events.custom]]
+kind = "cake"
+start = "07-14"
+
+[[events.custom]]
+kind = "fireworks"
+start = "07-04"
+at = "21:30"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let events = config.events.calendar();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, crate::scene::events::EventKind::Cake);
+        assert!(events[1].at.is_some());
+    }
+
+    #[test]
+    fn test_config_invalid_event_date() {
+        let toml_content = r#"
+[[events.custom]]
+kind = "cake"
+start = "13-01"
+"#;
+        let error = toml::from_str::<Config>(toml_content).unwrap_err();
+        assert!(error.to_string().contains("invalid date '13-01'"));
+    }
+
+    #[test]
+    fn test_config_aurora_kp_source() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.aurora.enabled);
+        assert_eq!(config.aurora.kp_url, SWPC_KP_URL);
+
+        let toml_content = r#"
+[aurora]
+kp_url = "/tmp/kp.json"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.aurora.kp_url, "/tmp/kp.json");
+    }
+
+    #[test]
+    fn test_config_adsb_feed() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.adsb.url.is_none());
+
+        let toml_content = r#"
+[adsb]
+url = "http://localhost:8080/data/aircraft.json"
+radius_km = 25
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(
+            config.adsb.url.as_deref(),
+            Some("http://localhost:8080/data/aircraft.json")
+        );
+        assert_eq!(config.adsb.radius_km, 25.0);
+    }
+
+    #[test]
+    fn test_config_fireplace_temperature() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.house.fireplace_temperature, 12.0);
+
+        let toml_content = r#"
+[house]
+fireplace_temperature = 5.5
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.house.fireplace_temperature, 5.5);
+    }
+
+    #[test]
+    fn test_config_scene_theme() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.scene.theme, SceneTheme::Cottage);
+
+        let toml_content = r#"
+[scene]
+theme = "coast"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.scene.theme, SceneTheme::Coast);
+
+        let toml_content = r#"
+[scene]
+theme = "volcano"
+"#;
+        assert!(toml::from_str::<Config>(toml_content).is_err());
+    }
+
+    #[test]
+    fn test_config_scene_file() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.scene.file, None);
+        assert_eq!(config.scene.file_path().unwrap(), None);
+
+        let toml_content = r#"
+[scene]
+file = "office"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.scene.file, Some(PathBuf::from("office")));
+
+        let dir = Path::new("/home/me/.config/weathr");
+        assert_eq!(
+            resolve_scene_file(dir, Path::new("office")),
+            dir.join("scenes/office.toml")
+        );
+        assert_eq!(
+            resolve_scene_file(dir, Path::new("art/office.toml")),
+            dir.join("art/office.toml")
+        );
+        assert_eq!(
+            resolve_scene_file(dir, Path::new("/srv/office.toml")),
+            PathBuf::from("/srv/office.toml")
+        );
+    }
+
+    #[test]
+    fn test_config_scripting() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.scripting.enabled);
+        assert_eq!(config.scripting.budget_ms, 2.0);
+
+        let toml_content = r#"
+[scripting]
+enabled = false
+budget_ms = 0.5
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(!config.scripting.enabled);
+        assert_eq!(config.scripting.budget_ms, 0.5);
+
+        for budget in ["inf", "nan", "-1.0", "1e300"] {
+            let config: Config =
+                toml::from_str(&format!("[scripting]\nbudget_ms = {budget}")).unwrap();
+            assert_eq!(config.validate().unwrap_err().kind(), "InvalidScriptBudget");
+        }
+    }
+
+    #[test]
+    fn test_config_effects() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.effects.toggles().count(), 0);
+
+        let toml_content = r#"
+[effects]
+ufo = false
+airplanes = false
+rain = true
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(
+            config.effects.toggles().collect::<Vec<_>>(),
+            [("airplanes", false), ("rain", true), ("ufo", false)]
+        );
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str("[effects]\nufos = false").unwrap();
+        let error = config.validate().unwrap_err();
+        assert_eq!(error.kind(), "UnknownEffect");
+        assert!(error.to_string().contains("\"ufos\""));
+        assert!(error.to_string().contains("ufo, fireflies"));
+    }
+
+    #[test]
+    fn test_config_animation_fps() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.animation.fps, 60);
+
+        let config: Config = toml::from_str("[animation]\nfps = 24").unwrap();
+        assert_eq!(config.animation.fps, 24);
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str("[animation]\nfps = 0").unwrap();
+        assert_eq!(config.validate().unwrap_err().kind(), "InvalidFps");
+    }
 }
@@ -1,3 +1,4 @@
+use crate::animation::effect::EFFECT_NAMES;
 use std::io;
 use thiserror::Error as ThisError;
 
@@ -49,6 +50,9 @@ pub enum NetworkError {
         source: reqwest::Error,
     },
 
+    #[error("unexpected data from {url}: {reason}")]
+    InvalidData { url: String, reason: String },
+
     #[error("network error: {0}")]
     Other(#[from] reqwest::Error),
 }
@@ -118,7 +122,7 @@ impl NetworkError {
             NetworkError::HttpError { url, status, .. } => {
                 format!("Server error from {url}: HTTP {status}")
             }
-            NetworkError::JsonParse { url, .. } => {
+            NetworkError::JsonParse { url, .. } | NetworkError::InvalidData { url, .. } => {
                 format!("Received invalid data from {url}")
             }
             NetworkError::ClientCreation(_) => "Failed to initialize HTTP client".to_string(),
@@ -147,6 +151,25 @@ pub enum ConfigError {
 
     #[error("invalid longitude: {0} (must be between -180 and 180)")]
     InvalidLongitude(f64),
+
+    #[error("invalid scene file {path}: {reason}")]
+    InvalidScene { path: String, reason: String },
+
+    #[error("invalid sprite file {path}: {reason}")]
+    InvalidSprite { path: String, reason: String },
+
+    #[error("invalid script {path}: {reason}")]
+    #[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+    InvalidScript { path: String, reason: String },
+
+    #[error("unknown effect \"{0}\" in [effects] (known effects: {known})", known = EFFECT_NAMES.join(", "))]
+    UnknownEffect(String),
+
+    #[error("invalid fps: {0} in [animation] (must be between 1 and 240)")]
+    InvalidFps(u32),
+
+    #[error("invalid budget_ms: {0} in [scripting] (must be between 0 and 1000)")]
+    InvalidScriptBudget(f64),
 }
 
 impl ConfigError {
@@ -158,6 +181,12 @@ impl ConfigError {
             ConfigError::NoConfigDir => "NoConfigDir",
             ConfigError::InvalidLatitude(_) => "InvalidLatitude",
             ConfigError::InvalidLongitude(_) => "InvalidLongitude",
+            ConfigError::InvalidScene { .. } => "InvalidScene",
+            ConfigError::InvalidSprite { .. } => "InvalidSprite",
+            ConfigError::InvalidScript { .. } => "InvalidScript",
+            ConfigError::UnknownEffect(_) => "UnknownEffect",
+            ConfigError::InvalidFps(_) => "InvalidFps",
+            ConfigError::InvalidScriptBudget(_) => "InvalidScriptBudget",
         }
     }
 }
@@ -254,9 +283,11 @@ impl GeolocationError {
                          Using configured/default location."
                     )
                 }
-                NetworkError::JsonParse { .. } => "Received invalid data from location service.\n\
+                NetworkError::JsonParse { .. } | NetworkError::InvalidData { .. } => {
+                    "Received invalid data from location service.\n\
                      Using configured/default location."
-                    .to_string(),
+                        .to_string()
+                }
                 NetworkError::ClientCreation(_) => "Failed to initialize network client.\n\
                      Using configured/default location."
                     .to_string(),
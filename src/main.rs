@@ -10,7 +10,7 @@ mod render;
 mod scene;
 mod weather;
 
-use clap::{CommandFactory, Parser};
+use clap::{CommandFactory, Parser, ValueEnum};
 use clap_complete::{Shell, generate};
 use config::Config;
 use crossterm::{
@@ -46,7 +46,7 @@ struct Cli {
         short,
         long,
         value_name = "CONDITION",
-        help = "Simulate weather condition (clear, rain, drizzle, snow, etc.)"
+        help = "Simulate weather condition (clear, rain, drizzle, snow, rainbow, aurora, etc.)"
     )]
     simulate: Option<String>,
 
@@ -57,8 +57,17 @@ struct Cli {
     )]
     night: bool,
 
-    #[arg(short, long, help = "Enable falling autumn leaves")]
-    leaves: bool,
+    #[arg(
+        short,
+        long,
+        value_name = "WHEN",
+        value_enum,
+        default_value_t = Leaves::Auto,
+        num_args = 0..=1,
+        default_missing_value = "on",
+        help = "Falling leaves: on in any season, off, or auto to fall in autumn"
+    )]
+    leaves: Leaves,
 
     #[arg(short, long, help = "Specify a city name to get weather for")]
     city: Option<String>,
@@ -89,10 +98,35 @@ struct Cli {
     #[arg(long, help = "Run silently (suppress non-error output)")]
     silent: bool,
 
+    #[arg(
+        long,
+        value_name = "SEED",
+        help = "Seed the animations so the same weather and terminal size play out the same way"
+    )]
+    seed: Option<u64>,
+
     #[arg(long, value_name = "SHELL", value_enum)]
     pub completions: Option<Shell>,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum Leaves {
+    Auto,
+    On,
+    Off,
+}
+
+impl Leaves {
+    /// Whether leaves are forced on or off, or left to the season.
+    fn forced(self) -> Option<bool> {
+        match self {
+            Leaves::Auto => None,
+            Leaves::On => Some(true),
+            Leaves::Off => Some(false),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> io::Result<()> {
     let default_hook = panic::take_hook();
@@ -133,6 +167,10 @@ async fn main() -> io::Result<()> {
                 eprintln!("    thunderstorm       - Thunderstorm");
                 eprintln!("    thunderstorm-hail  - Thunderstorm with hail");
                 eprintln!();
+                eprintln!("  Special:");
+                eprintln!("    rainbow            - Rainbow after a shower");
+                eprintln!("    aurora             - Northern lights on a clear night");
+                eprintln!();
                 eprintln!("Examples:");
                 eprintln!("  weathr --simulate rain");
                 eprintln!("  weathr --simulate snow --night");
@@ -261,14 +299,25 @@ async fn main() -> io::Result<()> {
 
     let (term_width, term_height) = renderer.get_size();
 
-    let mut app = app::App::new(
+    let mut app = match app::App::new(
         &config,
         cli.simulate,
         cli.night,
-        cli.leaves,
+        cli.leaves.forced(),
         term_width,
         term_height,
-    );
+        cli.seed,
+    ) {
+        Ok(app) => app,
+        Err(e) => {
+            renderer.cleanup()?;
+            eprintln!("Error starting weathr: {}", e);
+            if let Some(source) = std::error::Error::source(&e) {
+                eprintln!("  {}", source);
+            }
+            std::process::exit(1);
+        }
+    };
 
     let result = tokio::select! {
         res = app.run(&mut renderer) => res,
@@ -279,6 +328,11 @@ async fn main() -> io::Result<()> {
 
     renderer.cleanup()?;
 
+    #[cfg(feature = "scripting")]
+    for error in app.script_errors() {
+        eprintln!("Script stopped: {}", error);
+    }
+
     if let Err(e) = result {
         eprintln!("Application error: {}", e);
         std::process::exit(1);
@@ -0,0 +1,192 @@
+//! ASCII art read from user files, colored through a mask: each mask
+//! character names an entry in a palette of day and night colors.
+
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A palette entry as written in a file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColorEntry {
+    day: String,
+    /// Defaults to the day color.
+    night: Option<String>,
+}
+
+/// Day and night colors per mask character.
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    colors: HashMap<char, (Color, Color)>,
+}
+
+impl Palette {
+    pub fn parse(entries: &HashMap<String, ColorEntry>) -> Result<Self, String> {
+        let mut colors = HashMap::new();
+        for (key, entry) in entries {
+            let mut chars = key.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                return Err(format!(
+                    "color key \"{key}\" must be a single mask character"
+                ));
+            };
+            let day = parse_color(&entry.day).ok_or_else(|| unknown_color(key, &entry.day))?;
+            let night = match &entry.night {
+                Some(night) => parse_color(night).ok_or_else(|| unknown_color(key, night))?,
+                None => day,
+            };
+            colors.insert(ch, (day, night));
+        }
+        Ok(Self { colors })
+    }
+
+    pub fn get(&self, ch: char, is_day: bool) -> Option<Color> {
+        self.colors
+            .get(&ch)
+            .map(|&(day, night)| if is_day { day } else { night })
+    }
+}
+
+/// Lines of art and the mask over them.
+#[derive(Debug, Clone)]
+pub struct MaskedArt {
+    pub lines: Vec<Vec<char>>,
+    mask: Vec<Vec<char>>,
+}
+
+impl MaskedArt {
+    /// Checks the mask fits the art and only uses colors in the palette.
+    /// Errors start with `name` so they point at the right part of a file.
+    pub fn parse(
+        name: &str,
+        art: &str,
+        mask: Option<&str>,
+        palette: &Palette,
+    ) -> Result<Self, String> {
+        if art.contains('\t') {
+            return Err(format!("{name}: art contains tabs; use spaces"));
+        }
+        let lines = art_lines(art);
+        if lines.is_empty() {
+            return Err(format!("{name}: art is empty"));
+        }
+
+        let mask = mask.map(art_lines).unwrap_or_default();
+        if mask.len() > lines.len() {
+            return Err(format!(
+                "{name}: mask has {} lines but the art only has {}",
+                mask.len(),
+                lines.len()
+            ));
+        }
+        for (row, (mask_line, art_line)) in mask.iter().zip(&lines).enumerate() {
+            if mask_line.len() > art_line.len() {
+                return Err(format!(
+                    "{name}: mask line {} is longer than the art ({} > {} characters)",
+                    row + 1,
+                    mask_line.len(),
+                    art_line.len()
+                ));
+            }
+            if let Some(&ch) = mask_line
+                .iter()
+                .find(|&&ch| ch != ' ' && !palette.colors.contains_key(&ch))
+            {
+                return Err(format!(
+                    "{name}: mask character '{ch}' on line {} has no entry in [colors]",
+                    row + 1
+                ));
+            }
+        }
+
+        Ok(Self { lines, mask })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16
+    }
+
+    pub fn height(&self) -> u16 {
+        self.lines.len() as u16
+    }
+
+    /// The mask's color for a cell, if it has one.
+    pub fn color(&self, row: usize, col: usize, palette: &Palette, is_day: bool) -> Option<Color> {
+        let ch = *self.mask.get(row)?.get(col)?;
+        palette.get(ch, is_day)
+    }
+}
+
+/// Lines of a multi-line string, without trailing blank lines.
+fn art_lines(text: &str) -> Vec<Vec<char>> {
+    let mut lines = text
+        .lines()
+        .map(|line| line.trim_end().chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// A color name such as `dark_red`, or `#rrggbb`.
+pub fn parse_color(text: &str) -> Option<Color> {
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+    Color::try_from(text).ok()
+}
+
+fn unknown_color(key: &str, color: &str) -> String {
+    format!(
+        "unknown color \"{color}\" for '{key}'; use a name such as \"dark_red\" or a hex code such as \"#8c5a3c\""
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("dark_red"), Some(Color::DarkRed));
+        assert_eq!(
+            parse_color("#8c5a3c"),
+            Some(Color::Rgb {
+                r: 140,
+                g: 90,
+                b: 60
+            })
+        );
+        assert_eq!(parse_color("#8c5a"), None);
+        assert_eq!(parse_color("#8c5a3g"), None);
+        assert_eq!(parse_color("blurple"), None);
+    }
+
+    #[test]
+    fn test_masked_art_colors_cells() {
+        let entries = HashMap::from([(
+            "r".to_string(),
+            ColorEntry {
+                day: "red".to_string(),
+                night: Some("dark_red".to_string()),
+            },
+        )]);
+        let palette = Palette::parse(&entries).unwrap();
+        let art = MaskedArt::parse("sign", "/--\\\n|  |\n\n", Some(" rr"), &palette).unwrap();
+
+        assert_eq!((art.width(), art.height()), (4, 2));
+        assert_eq!(art.color(0, 1, &palette, true), Some(Color::Red));
+        assert_eq!(art.color(0, 2, &palette, false), Some(Color::DarkRed));
+        assert_eq!(art.color(0, 0, &palette, true), None);
+        assert_eq!(art.color(1, 0, &palette, true), None);
+    }
+}
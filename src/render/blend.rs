@@ -0,0 +1,58 @@
+//! Mixing terminal colours for fades and day/night blends.
+
+use crossterm::style::Color;
+
+/// The usual xterm shade of a colour, or `None` for the terminal's default
+/// and palette indices, which have no fixed shade.
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    Some(match color {
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::DarkGrey => (127, 127, 127),
+        Color::Red => (255, 0, 0),
+        Color::DarkRed => (205, 0, 0),
+        Color::Green => (0, 255, 0),
+        Color::DarkGreen => (0, 205, 0),
+        Color::Yellow => (255, 255, 0),
+        Color::DarkYellow => (205, 205, 0),
+        Color::Blue => (92, 92, 255),
+        Color::DarkBlue => (0, 0, 238),
+        Color::Magenta => (255, 0, 255),
+        Color::DarkMagenta => (205, 0, 205),
+        Color::Cyan => (0, 255, 255),
+        Color::DarkCyan => (0, 205, 205),
+        Color::White => (255, 255, 255),
+        Color::Grey => (229, 229, 229),
+        Color::Reset | Color::AnsiValue(_) => return None,
+    })
+}
+
+/// The colour `t` of the way from `from` to `to`, if both have a shade.
+pub fn mix(from: Color, to: Color, t: f32) -> Option<Color> {
+    let (r1, g1, b1) = rgb(from)?;
+    let (r2, g2, b2) = rgb(to)?;
+    let t = t.clamp(0.0, 1.0);
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+    Some(Color::Rgb {
+        r: channel(r1, r2),
+        g: channel(g1, g2),
+        b: channel(b1, b2),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_named_and_rgb_colors() {
+        let grey = Color::Rgb {
+            r: 127,
+            g: 127,
+            b: 127,
+        };
+        assert_eq!(mix(Color::Black, Color::White, 0.5), Some(grey));
+        assert_eq!(mix(grey, Color::Red, 0.0), Some(grey));
+        assert_eq!(mix(Color::Reset, Color::Red, 0.5), None);
+    }
+}
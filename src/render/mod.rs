@@ -1,13 +1,16 @@
+pub mod art;
+pub mod blend;
 mod capabilities;
 
 use crate::error::TerminalError;
+pub use capabilities::ColorSupport;
 use capabilities::TerminalCapabilities;
 use crossterm::{
     cursor, execute, queue,
     style::{Color, Print, ResetColor, SetForegroundColor},
     terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::io::{self, BufWriter, IsTerminal, Stdout, Write};
+use std::io::{self, BufWriter, IsTerminal, Write};
 
 const MIN_TERMINAL_WIDTH: u16 = 70;
 const MIN_TERMINAL_HEIGHT: u16 = 15;
@@ -27,8 +30,15 @@ impl Default for Cell {
     }
 }
 
+/// What was drawn at one moment, to draw something else over and blend
+/// back with.
+pub struct Snapshot {
+    cells: Vec<Cell>,
+}
+
 pub struct TerminalRenderer {
-    stdout: BufWriter<Stdout>,
+    /// The terminal, or nowhere for a headless renderer.
+    stdout: BufWriter<Box<dyn Write + Send>>,
     width: u16,
     height: u16,
     buffer: Vec<Cell>,
@@ -53,7 +63,7 @@ impl TerminalRenderer {
             });
         }
 
-        let stdout = BufWriter::new(io::stdout());
+        let stdout = BufWriter::new(Box::new(io::stdout()) as Box<dyn Write + Send>);
         let buffer_size = (width as usize) * (height as usize);
         let capabilities = TerminalCapabilities::detect();
 
@@ -67,6 +77,24 @@ impl TerminalRenderer {
         })
     }
 
+    /// A renderer that only draws into its buffer, for checking frames
+    /// without a terminal.
+    #[cfg(test)]
+    pub fn headless(width: u16, height: u16) -> Self {
+        let buffer_size = (width as usize) * (height as usize);
+        Self {
+            stdout: BufWriter::new(Box::new(io::sink())),
+            width,
+            height,
+            buffer: vec![Cell::default(); buffer_size],
+            last_buffer: vec![Cell::default(); buffer_size],
+            capabilities: TerminalCapabilities {
+                color_support: ColorSupport::TrueColor,
+                is_tty: false,
+            },
+        }
+    }
+
     pub fn init(&mut self) -> Result<(), TerminalError> {
         terminal::enable_raw_mode().map_err(TerminalError::RawModeError)?;
         execute!(self.stdout, EnterAlternateScreen, cursor::Hide)
@@ -92,10 +120,60 @@ impl TerminalRenderer {
         Ok(())
     }
 
+    pub fn color_support(&self) -> ColorSupport {
+        self.capabilities.color_support
+    }
+
     pub fn get_size(&self) -> (u16, u16) {
         (self.width, self.height)
     }
 
+    /// The characters drawn since the last clear, one line per row.
+    #[cfg(test)]
+    pub fn contents(&self) -> String {
+        let width = (self.width as usize).max(1);
+        self.buffer
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.character).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            cells: self.buffer.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        if snapshot.cells.len() == self.buffer.len() {
+            self.buffer.copy_from_slice(&snapshot.cells);
+        }
+    }
+
+    /// Moves what is drawn `weight` of the way toward `snapshot`. Cells
+    /// showing the same character blend their colours; others, and every
+    /// cell on terminals without true colour, take whichever side is nearer.
+    pub fn blend(&mut self, snapshot: &Snapshot, weight: f32) {
+        if snapshot.cells.len() != self.buffer.len() {
+            return;
+        }
+        let blends = matches!(
+            self.capabilities.color_support,
+            ColorSupport::Ansi256 | ColorSupport::TrueColor
+        );
+        for (cell, other) in self.buffer.iter_mut().zip(&snapshot.cells) {
+            let mixed = (blends && cell.character == other.character)
+                .then(|| blend::mix(cell.color, other.color, weight))
+                .flatten();
+            match mixed {
+                Some(color) => cell.color = color,
+                None if weight >= 0.5 => *cell = *other,
+                None => {}
+            }
+        }
+    }
+
     pub fn clear(&mut self) -> io::Result<()> {
         self.buffer.fill(Cell::default());
         Ok(())
@@ -176,10 +254,41 @@ impl TerminalRenderer {
         Ok(())
     }
 
-    pub fn flash_screen(&mut self) -> io::Result<()> {
-        let flash_color = self.capabilities.adjust_color(Color::White);
-        for cell in &mut self.buffer {
-            cell.color = flash_color;
+    /// Lights up everything drawn within `radius` columns of `center_x`,
+    /// leaving empty sky dark so only silhouettes catch the flash.
+    pub fn illuminate(&mut self, center_x: u16, radius: u16, color: Color) -> io::Result<()> {
+        let lit_color = self.capabilities.adjust_color(color);
+        let width = self.width as usize;
+        if width == 0 {
+            return Ok(());
+        }
+        for (idx, cell) in self.buffer.iter_mut().enumerate() {
+            let x = (idx % width) as u16;
+            if cell.character != ' ' && x.abs_diff(center_x) <= radius {
+                cell.color = lit_color;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shifts rows `top..bottom` sideways by `dx` cells, blanking what is
+    /// uncovered.
+    pub fn shift_rows(&mut self, top: u16, bottom: u16, dx: i16) -> io::Result<()> {
+        let width = self.width as usize;
+        let shift = dx.unsigned_abs() as usize;
+        if dx == 0 || shift >= width {
+            return Ok(());
+        }
+        for y in top..bottom.min(self.height) {
+            let start = (y as usize) * width;
+            let row = &mut self.buffer[start..start + width];
+            if dx > 0 {
+                row.rotate_right(shift);
+                row[..shift].fill(Cell::default());
+            } else {
+                row.rotate_left(shift);
+                row[width - shift..].fill(Cell::default());
+            }
         }
         Ok(())
     }
@@ -231,3 +340,35 @@ impl Drop for TerminalRenderer {
         let _ = self.cleanup();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_mixes_matching_cells_and_picks_the_nearer_otherwise() {
+        let mut renderer = TerminalRenderer::headless(2, 1);
+        renderer.render_char(0, 0, '#', Color::Black).unwrap();
+        renderer.render_char(1, 0, 'a', Color::White).unwrap();
+        let night = renderer.snapshot();
+        renderer.render_char(0, 0, '#', Color::White).unwrap();
+        renderer.render_char(1, 0, 'b', Color::White).unwrap();
+
+        renderer.blend(&night, 0.25);
+        let grey = Color::Rgb {
+            r: 191,
+            g: 191,
+            b: 191,
+        };
+        assert_eq!(renderer.buffer[0].color, grey);
+        assert_eq!(renderer.contents(), "#b");
+
+        renderer.blend(&night, 0.75);
+        assert_eq!(renderer.contents(), "#a");
+
+        renderer.capabilities.color_support = ColorSupport::Basic;
+        renderer.render_char(0, 0, '#', Color::White).unwrap();
+        renderer.blend(&night, 0.25);
+        assert_eq!(renderer.buffer[0].color, Color::White);
+    }
+}
@@ -1,16 +1,50 @@
+use crate::animation::Wind;
 use crate::render::TerminalRenderer;
+use crate::scene::season::Season;
+use crate::scene::snow_cover::SnowCover;
 use crossterm::style::Color;
 use std::io;
 
 #[derive(Default)]
 pub struct Decorations;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlacementKind {
+    Tree,
+    /// Evergreen, so it ignores the season.
+    Pine,
+    Fence,
+    Mailbox,
+}
+
+struct Placement {
+    kind: PlacementKind,
+    lines: Vec<&'static str>,
+    color: Color,
+    x: u16,
+    y: u16,
+}
+
+impl Placement {
+    fn width(&self) -> u16 {
+        self.lines.iter().map(|l| l.len()).max().unwrap_or(0) as u16
+    }
+}
+
 pub struct DecorationRenderConfig {
     pub horizon_y: u16,
     pub house_x: u16,
     pub house_width: u16,
     pub width: u16,
     pub is_day: bool,
+    /// Crown displacement in cells; the trunk stays put.
+    pub sway: f32,
+    pub wind: Wind,
+    /// Seconds since the scene started, for fluttering.
+    pub elapsed: f32,
+    /// Rows of snow resting on the tree crowns.
+    pub snow_level: u8,
+    pub season: Season,
 }
 
 impl Decorations {
@@ -23,70 +57,153 @@ impl Decorations {
         renderer: &mut TerminalRenderer,
         config: &DecorationRenderConfig,
     ) -> io::Result<()> {
-        // Render Tree (Left of house)
-        let (tree_lines, tree_color) = self.get_tree(config.is_day);
-        let tree_height = tree_lines.len() as u16;
-        let tree_y = config.horizon_y.saturating_sub(tree_height);
-        let tree_x = config.house_x.saturating_sub(20);
+        let layout = self.layout(config);
 
-        if tree_x > 0 {
-            for (i, line) in tree_lines.iter().enumerate() {
+        for item in &layout {
+            match item.kind {
+                PlacementKind::Tree | PlacementKind::Pine => {
+                    self.render_tree(renderer, item, config)?
+                }
+                PlacementKind::Fence | PlacementKind::Mailbox => {
+                    for (i, line) in item.lines.iter().enumerate() {
+                        for (j, ch) in line.chars().enumerate() {
+                            if ch != ' ' {
+                                renderer.render_char(
+                                    item.x + j as u16,
+                                    item.y + i as u16,
+                                    ch,
+                                    item.color,
+                                )?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Render Wind Sock (just past the end of the fence)
+        if let Some(fence) = layout.iter().find(|p| p.kind == PlacementKind::Fence) {
+            let pole_x = fence.x + fence.width() + 1;
+            if pole_x + 6 < config.width {
+                self.render_wind_sock(renderer, pole_x, config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lowers `surface` (topmost solid row per column) wherever a decoration
+    /// stands, so precipitation lands on tree crowns, the fence and mailbox.
+    pub fn add_surfaces(&self, config: &DecorationRenderConfig, surface: &mut [u16]) {
+        for item in self.layout(config) {
+            for (i, line) in item.lines.iter().enumerate() {
                 for (j, ch) in line.chars().enumerate() {
-                    if ch != ' ' {
-                        renderer.render_char(
-                            tree_x + j as u16,
-                            tree_y + i as u16,
-                            ch,
-                            tree_color,
-                        )?;
+                    let col = (item.x + j as u16) as usize;
+                    if ch != ' ' && col < surface.len() {
+                        surface[col] = surface[col].min(item.y + i as u16);
                     }
                 }
             }
         }
+    }
+
+    /// Top of each tree crown, as (column, row).
+    pub fn tree_tops(&self, config: &DecorationRenderConfig) -> Vec<(u16, u16)> {
+        self.layout(config)
+            .iter()
+            .filter(|item| matches!(item.kind, PlacementKind::Tree | PlacementKind::Pine))
+            .filter_map(|item| {
+                let top = item.lines.first()?;
+                let col = top.find(|ch| ch != ' ')?;
+                let len = top.trim().len();
+                Some((item.x + (col + len / 2) as u16, item.y))
+            })
+            .collect()
+    }
+
+    /// Cells just above the fence posts, the mailbox and the tree tops,
+    /// where birds can sit.
+    pub fn perches(&self, config: &DecorationRenderConfig) -> Vec<(u16, u16)> {
+        let mut perches = self
+            .tree_tops(config)
+            .into_iter()
+            .filter(|&(_, y)| y > 0)
+            .map(|(x, y)| (x, y - 1))
+            .collect::<Vec<_>>();
+
+        for item in self.layout(config) {
+            let Some(top) = item.lines.first() else {
+                continue;
+            };
+            let y = item.y.saturating_sub(1);
+            match item.kind {
+                PlacementKind::Fence => perches.extend(
+                    top.match_indices('|')
+                        .map(|(col, _)| (item.x + col as u16, y)),
+                ),
+                PlacementKind::Mailbox => {
+                    perches.extend(top.find('_').map(|col| (item.x + col as u16 + 1, y)))
+                }
+                PlacementKind::Tree | PlacementKind::Pine => {}
+            }
+        }
+
+        perches.retain(|&(x, _)| x < config.width);
+        perches
+    }
+
+    fn layout(&self, config: &DecorationRenderConfig) -> Vec<Placement> {
+        let mut layout = Vec::new();
 
-        // Render Fence (Right of house)
+        // Tree (Left of house)
+        let (tree_lines, tree_color) = self.get_tree(config.is_day, config.season);
+        let tree_height = tree_lines.len() as u16;
+        let tree_y = config.horizon_y.saturating_sub(tree_height);
+        let tree_x = config.house_x.saturating_sub(20);
+
+        if tree_x > 0 {
+            layout.push(Placement {
+                kind: PlacementKind::Tree,
+                lines: tree_lines,
+                color: tree_color,
+                x: tree_x,
+                y: tree_y,
+            });
+        }
+
+        // Fence (Right of house)
         let (fence_lines, fence_color) = self.get_fence(config.is_day);
         let fence_height = fence_lines.len() as u16;
         let fence_y = config.horizon_y.saturating_sub(fence_height); // Sitting on ground
         let fence_x = config.house_x + config.house_width + 2; // Slight gap
 
         if fence_x < config.width {
-            for (i, line) in fence_lines.iter().enumerate() {
-                for (j, ch) in line.chars().enumerate() {
-                    if ch != ' ' {
-                        renderer.render_char(
-                            fence_x + j as u16,
-                            fence_y + i as u16,
-                            ch,
-                            fence_color,
-                        )?;
-                    }
-                }
-            }
+            layout.push(Placement {
+                kind: PlacementKind::Fence,
+                lines: fence_lines,
+                color: fence_color,
+                x: fence_x,
+                y: fence_y,
+            });
         }
 
-        // Render Mailbox (On ground top level, left of tree)
+        // Mailbox (On ground top level, left of tree)
         let (mailbox_lines, mailbox_color) = self.get_mailbox(config.is_day);
         let mailbox_height = mailbox_lines.len() as u16;
         let mailbox_x = tree_x.saturating_sub(10); // Left of tree
         let mailbox_y = config.horizon_y.saturating_sub(mailbox_height); // On ground top
 
         if mailbox_x < config.width {
-            for (i, line) in mailbox_lines.iter().enumerate() {
-                for (j, ch) in line.chars().enumerate() {
-                    if ch != ' ' {
-                        renderer.render_char(
-                            mailbox_x + j as u16,
-                            mailbox_y + i as u16,
-                            ch,
-                            mailbox_color,
-                        )?;
-                    }
-                }
-            }
+            layout.push(Placement {
+                kind: PlacementKind::Mailbox,
+                lines: mailbox_lines,
+                color: mailbox_color,
+                x: mailbox_x,
+                y: mailbox_y,
+            });
         }
 
-        // Render Second Tree (Right of house, only if terminal is wide enough)
+        // Second Tree (Right of house, only if terminal is wide enough)
         if config.width > 120 {
             let (pine_lines, pine_color) = self.get_pine_tree(config.is_day);
             let pine_height = pine_lines.len() as u16;
@@ -95,25 +212,177 @@ impl Decorations {
 
             if pine_x + 10 < config.width {
                 // Check if full tree fits
-                for (i, line) in pine_lines.iter().enumerate() {
-                    for (j, ch) in line.chars().enumerate() {
-                        if ch != ' ' {
-                            renderer.render_char(
-                                pine_x + j as u16,
-                                pine_y + i as u16,
-                                ch,
-                                pine_color,
-                            )?;
-                        }
-                    }
+                layout.push(Placement {
+                    kind: PlacementKind::Pine,
+                    lines: pine_lines,
+                    color: pine_color,
+                    x: pine_x,
+                    y: pine_y,
+                });
+            }
+        }
+
+        layout
+    }
+
+    /// Draws a tree whose last line is the trunk. Crown rows are shifted by
+    /// the sway, the top row the most.
+    fn render_tree(
+        &self,
+        renderer: &mut TerminalRenderer,
+        tree: &Placement,
+        config: &DecorationRenderConfig,
+    ) -> io::Result<()> {
+        let season = if tree.kind == PlacementKind::Pine {
+            Season::Summer
+        } else {
+            config.season
+        };
+        let crown_rows = tree.lines.len().saturating_sub(1).max(1);
+
+        for (i, line) in tree.lines.iter().enumerate() {
+            let bend = (crown_rows.saturating_sub(i)) as f32 / crown_rows as f32;
+            let offset = (config.sway * bend).round() as i32;
+            let snowy = i < crown_rows && i < config.snow_level as usize;
+
+            for (j, ch) in line.chars().enumerate() {
+                let cx = tree.x as i32 + j as i32 + offset;
+                if ch == ' ' || cx < 0 || cx >= config.width as i32 {
+                    continue;
                 }
+
+                let (ch, color) = if snowy {
+                    (ch, SnowCover::color(config.is_day))
+                } else if i < crown_rows {
+                    Self::foliage(season, config.is_day, i, j, ch, tree.color)
+                } else {
+                    (ch, tree.color)
+                };
+                renderer.render_char(cx as u16, tree.y + i as u16, ch, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Crown cell for the season: blossom dotted in spring, turning colors
+    /// in autumn. Bare winter branches keep the plain color.
+    fn foliage(
+        season: Season,
+        is_day: bool,
+        row: usize,
+        col: usize,
+        ch: char,
+        color: Color,
+    ) -> (char, Color) {
+        match season {
+            Season::Spring if (row * 7 + col * 3) % 5 == 0 => {
+                let blossom = if is_day {
+                    rgb(255, 183, 197)
+                } else {
+                    rgb(140, 100, 110)
+                };
+                ('*', blossom)
+            }
+            Season::Autumn => {
+                let turned = if is_day {
+                    [rgb(205, 102, 0), rgb(218, 165, 32), rgb(178, 34, 34)]
+                } else {
+                    [rgb(100, 50, 0), rgb(105, 80, 15), rgb(85, 17, 17)]
+                };
+                (ch, turned[(row * 3 + col) % turned.len()])
+            }
+            _ => (ch, color),
+        }
+    }
+
+    /// Pole with a striped sock on top. One segment fills per ~3 knots, so
+    /// the sock stands out fully from about 7.7 m/s; the rest droops.
+    fn render_wind_sock(
+        &self,
+        renderer: &mut TerminalRenderer,
+        pole_x: u16,
+        config: &DecorationRenderConfig,
+    ) -> io::Result<()> {
+        const POLE_HEIGHT: u16 = 5;
+        const SEGMENTS: usize = 5;
+
+        let pole_color = if config.is_day {
+            Color::Grey
+        } else {
+            Color::DarkGrey
+        };
+        let stripes = if config.is_day {
+            [Color::Red, Color::White]
+        } else {
+            [Color::DarkRed, Color::Grey]
+        };
+
+        let top_y = config.horizon_y.saturating_sub(POLE_HEIGHT);
+        for row in top_y..config.horizon_y {
+            renderer.render_char(pole_x, row, '|', pole_color)?;
+        }
+
+        let flow = config.wind.horizontal();
+        let dir: i32 = if flow < 0.0 { -1 } else { 1 };
+        let filled = ((config.wind.speed_ms / 1.54) as usize).min(SEGMENTS);
+        let flutter = config.wind.beaufort() >= 4
+            && (config.elapsed * (2.0 + config.wind.speed_ms * 0.3)).sin() > 0.0;
+
+        let mut x = pole_x as i32;
+        let mut y = top_y;
+        for segment in 0..SEGMENTS {
+            let ch = if segment < filled {
+                x += dir;
+                if flutter && segment + 1 == filled {
+                    '~'
+                } else {
+                    '='
+                }
+            } else {
+                // Limp fabric hangs from the last filled ring
+                if segment == filled {
+                    x += dir;
+                }
+                y += 1;
+                match (segment == filled, dir) {
+                    (false, _) => '|',
+                    (true, 1) => '\\',
+                    (true, _) => '/',
+                }
+            };
+
+            if y >= config.horizon_y || x < 0 || x >= config.width as i32 {
+                break;
+            }
+            renderer.render_char(x as u16, y, ch, stripes[segment % 2])?;
+
+            // A limp sock is shorter than a filled one
+            if segment >= filled + 2 {
+                break;
             }
         }
 
         Ok(())
     }
 
-    fn get_tree(&self, is_day: bool) -> (Vec<&'static str>, Color) {
+    fn get_tree(&self, is_day: bool, season: Season) -> (Vec<&'static str>, Color) {
+        if season == Season::Winter {
+            return (
+                vec![
+                    "  \\ \\  ||  / /  ",
+                    "   \\_\\ || /_/   ",
+                    "     \\_||_/     ",
+                    "       ||       ",
+                    "      _||_      ",
+                ],
+                if is_day {
+                    rgb(101, 67, 33)
+                } else {
+                    rgb(60, 40, 20)
+                },
+            );
+        }
+
         (
             vec![
                 "      ####      ",
@@ -161,3 +430,7 @@ impl Decorations {
         )
     }
 }
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
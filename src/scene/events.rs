@@ -0,0 +1,339 @@
+use crate::render::TerminalRenderer;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::io;
+
+/// Timed events start a little before their moment and linger after it.
+const LEAD_MINUTES: i64 = 15;
+const LINGER_MINUTES: i64 = 45;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Blinking lights along the eaves and the porch rail.
+    Lights,
+    /// A carved pumpkin in the front yard.
+    Pumpkin,
+    /// Rockets bursting over the house.
+    Fireworks,
+    /// A cake with flickering candles in the front yard.
+    Cake,
+}
+
+/// A day of the year, written `"MM-DD"` in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub struct MonthDay {
+    month: u32,
+    day: u32,
+}
+
+impl MonthDay {
+    pub const fn new(month: u32, day: u32) -> Self {
+        Self { month, day }
+    }
+
+    fn of(date: NaiveDate) -> Self {
+        Self::new(date.month(), date.day())
+    }
+}
+
+impl TryFrom<String> for MonthDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid date '{}', expected MM-DD", value);
+        let (month, day) = value.split_once('-').ok_or_else(invalid)?;
+        let month: u32 = month.trim().parse().map_err(|_| invalid())?;
+        let day: u32 = day.trim().parse().map_err(|_| invalid())?;
+
+        // 2000 is a leap year, so 02-29 is accepted
+        NaiveDate::from_ymd_opt(2000, month, day).ok_or_else(invalid)?;
+        Ok(Self::new(month, day))
+    }
+}
+
+/// Local time of day, written `"HH:MM"` in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TimeOfDay(NaiveTime);
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        NaiveTime::parse_from_str(value.trim(), "%H:%M")
+            .map(Self)
+            .map_err(|_| format!("invalid time '{}', expected HH:MM", value))
+    }
+}
+
+/// One dated event. Without `at` it lasts whole days from `start` to `end`,
+/// wrapping over New Year if `end` comes first. With `at` it happens around
+/// that time on `start`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CalendarEvent {
+    pub kind: EventKind,
+    pub start: MonthDay,
+    #[serde(default)]
+    pub end: Option<MonthDay>,
+    #[serde(default)]
+    pub at: Option<TimeOfDay>,
+}
+
+impl CalendarEvent {
+    fn is_active(&self, now: NaiveDateTime) -> bool {
+        match self.at {
+            Some(at) => self.is_near(now, at.0),
+            None => {
+                let today = MonthDay::of(now.date());
+                let end = self.end.unwrap_or(self.start);
+                if self.start <= end {
+                    self.start <= today && today <= end
+                } else {
+                    today >= self.start || today <= end
+                }
+            }
+        }
+    }
+
+    /// Whether `now` falls in the window around this year's or next year's
+    /// occurrence, so a midnight event starts on the evening before.
+    fn is_near(&self, now: NaiveDateTime, at: NaiveTime) -> bool {
+        [now.year(), now.year() + 1].iter().any(|&year| {
+            NaiveDate::from_ymd_opt(year, self.start.month, self.start.day).is_some_and(|date| {
+                let moment = date.and_time(at);
+                now >= moment - Duration::minutes(LEAD_MINUTES)
+                    && now <= moment + Duration::minutes(LINGER_MINUTES)
+            })
+        })
+    }
+}
+
+/// Events shown every year unless turned off in config.
+pub fn builtin_events() -> Vec<CalendarEvent> {
+    vec![
+        CalendarEvent {
+            kind: EventKind::Lights,
+            start: MonthDay::new(12, 1),
+            end: Some(MonthDay::new(12, 31)),
+            at: None,
+        },
+        CalendarEvent {
+            kind: EventKind::Pumpkin,
+            start: MonthDay::new(10, 24),
+            end: Some(MonthDay::new(10, 31)),
+            at: None,
+        },
+        CalendarEvent {
+            kind: EventKind::Fireworks,
+            start: MonthDay::new(1, 1),
+            end: None,
+            at: Some(TimeOfDay(NaiveTime::MIN)),
+        },
+    ]
+}
+
+pub struct EventRenderConfig {
+    /// Column of the front door; seasonal decorations stand either side.
+    pub door_x: u16,
+    /// Runs holiday lights hang along, as (first column, last column, row).
+    pub light_strings: Vec<(u16, u16, u16)>,
+    pub horizon_y: u16,
+    pub width: u16,
+    pub is_day: bool,
+    /// Seconds since the scene started, for blinking and flicker.
+    pub elapsed: f32,
+}
+
+#[derive(Default)]
+pub struct EventCalendar {
+    events: Vec<CalendarEvent>,
+    active: Vec<EventKind>,
+}
+
+impl EventCalendar {
+    pub fn new(events: Vec<CalendarEvent>) -> Self {
+        Self {
+            events,
+            active: Vec::new(),
+        }
+    }
+
+    /// Re-evaluates which events are on at the location's local time.
+    pub fn update(&mut self, now: NaiveDateTime) {
+        self.active.clear();
+        for event in &self.events {
+            if event.is_active(now) && !self.active.contains(&event.kind) {
+                self.active.push(event.kind);
+            }
+        }
+    }
+
+    pub fn is_active(&self, kind: EventKind) -> bool {
+        self.active.contains(&kind)
+    }
+
+    /// Draws the decorations for active events. Fireworks are an animation
+    /// and are drawn with the sky.
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        config: &EventRenderConfig,
+    ) -> io::Result<()> {
+        if self.is_active(EventKind::Lights) {
+            self.render_lights(renderer, config)?;
+        }
+        if self.is_active(EventKind::Pumpkin) {
+            let x = config.door_x.saturating_sub(8);
+            self.render_pumpkin(renderer, x, config)?;
+        }
+        if self.is_active(EventKind::Cake) {
+            let x = config.door_x + 8;
+            self.render_cake(renderer, x, config)?;
+        }
+        Ok(())
+    }
+
+    fn render_lights(
+        &self,
+        renderer: &mut TerminalRenderer,
+        config: &EventRenderConfig,
+    ) -> io::Result<()> {
+        const BULBS: [Color; 4] = [Color::Red, Color::Green, Color::Yellow, Color::Blue];
+        let phase = (config.elapsed * 2.0) as usize;
+
+        for &(first, last, y) in &config.light_strings {
+            for (i, x) in (first..=last).step_by(2).enumerate() {
+                if x >= config.width {
+                    break;
+                }
+                // Every other bulb goes dark in turn
+                let lit = (i + phase) % 2 == 0 || config.is_day;
+                let ch = if lit { 'o' } else { '.' };
+                renderer.render_char(x, y, ch, BULBS[(i + phase / 2) % BULBS.len()])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_pumpkin(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        config: &EventRenderConfig,
+    ) -> io::Result<()> {
+        let shell = if config.is_day {
+            Color::Rgb {
+                r: 255,
+                g: 117,
+                b: 24,
+            }
+        } else {
+            Color::Rgb {
+                r: 150,
+                g: 70,
+                b: 15,
+            }
+        };
+        // The candle inside shows through the face after dark
+        let face = if config.is_day {
+            Color::Black
+        } else {
+            Color::Yellow
+        };
+
+        let y = config.horizon_y;
+        renderer.render_char(x + 2, y, ')', Color::DarkGreen)?;
+        for (i, ch) in "(^v^)".chars().enumerate() {
+            let color = if matches!(ch, '^' | 'v') { face } else { shell };
+            renderer.render_char(x + i as u16, y + 1, ch, color)?;
+        }
+        Ok(())
+    }
+
+    fn render_cake(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        config: &EventRenderConfig,
+    ) -> io::Result<()> {
+        let y = config.horizon_y;
+        let flicker = (config.elapsed * 6.0) as usize;
+
+        for i in 0..3u16 {
+            let flame = if (flicker + i as usize) % 3 == 0 {
+                '.'
+            } else {
+                '*'
+            };
+            renderer.render_char(x + 1 + i, y, flame, Color::Yellow)?;
+        }
+        let icing = if config.is_day {
+            Color::Magenta
+        } else {
+            Color::DarkMagenta
+        };
+        for (i, ch) in "[iii]".chars().enumerate() {
+            let color = if ch == 'i' { Color::White } else { icing };
+            renderer.render_char(x + i as u16, y + 1, ch, color)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_builtin_events() {
+        let mut calendar = EventCalendar::new(builtin_events());
+
+        calendar.update(at(2025, 12, 10, 12, 0));
+        assert!(calendar.is_active(EventKind::Lights));
+        assert!(!calendar.is_active(EventKind::Fireworks));
+
+        calendar.update(at(2025, 10, 31, 20, 0));
+        assert!(calendar.is_active(EventKind::Pumpkin));
+        assert!(!calendar.is_active(EventKind::Lights));
+
+        calendar.update(at(2025, 12, 31, 23, 50));
+        assert!(calendar.is_active(EventKind::Fireworks));
+        calendar.update(at(2026, 1, 1, 0, 30));
+        assert!(calendar.is_active(EventKind::Fireworks));
+        calendar.update(at(2026, 1, 1, 1, 0));
+        assert!(!calendar.is_active(EventKind::Fireworks));
+    }
+
+    #[test]
+    fn test_range_wraps_over_new_year() {
+        let event = CalendarEvent {
+            kind: EventKind::Lights,
+            start: MonthDay::new(12, 20),
+            end: Some(MonthDay::new(1, 6)),
+            at: None,
+        };
+        assert!(event.is_active(at(2025, 12, 24, 9, 0)));
+        assert!(event.is_active(at(2026, 1, 3, 9, 0)));
+        assert!(!event.is_active(at(2026, 1, 7, 9, 0)));
+    }
+
+    #[test]
+    fn test_month_day_parse() {
+        assert_eq!(
+            MonthDay::try_from("02-29".to_string()),
+            Ok(MonthDay::new(2, 29))
+        );
+        assert!(MonthDay::try_from("02-30".to_string()).is_err());
+        assert!(MonthDay::try_from("March 3".to_string()).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+/// Solid parts of the scene that falling particles interact with, and the
+/// landmarks animations attach to, in terminal cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneGeometry {
+    pub horizon_y: u16,
+    pub ground_height: u16,
+    /// Topmost solid row per column; open ground reports the horizon.
+    surface: Vec<u16>,
+    /// Roof edges water runs off, as (column, row).
+    pub eaves: Vec<(u16, u16)>,
+    /// Tall points lightning favours, such as tree tops.
+    pub lightning_targets: Vec<(u16, u16)>,
+    /// Empty cells birds can sit in, just above a roof, post or tree top.
+    pub perches: Vec<(u16, u16)>,
+    /// Top of the chimney smoke rises from, if the scene has a fireplace.
+    pub chimney: Option<(u16, u16)>,
+    /// Lamps and windows that glow through fog after dark.
+    pub lights: Vec<(u16, u16)>,
+}
+
+impl SceneGeometry {
+    pub fn new(width: u16, horizon_y: u16, ground_height: u16) -> Self {
+        Self {
+            horizon_y,
+            ground_height,
+            surface: vec![horizon_y; width as usize],
+            eaves: Vec::new(),
+            lightning_targets: Vec::new(),
+            perches: Vec::new(),
+            chimney: None,
+            lights: Vec::new(),
+        }
+    }
+
+    /// Bare terminal: the bottom row is the only surface.
+    pub fn flat(width: u16, height: u16) -> Self {
+        Self::new(width, height.saturating_sub(1), 1)
+    }
+
+    pub fn surface_mut(&mut self) -> &mut [u16] {
+        &mut self.surface
+    }
+
+    /// Topmost solid row at a column. Columns off screen count as open ground.
+    pub fn surface_at(&self, x: i32) -> u16 {
+        usize::try_from(x)
+            .ok()
+            .and_then(|x| self.surface.get(x))
+            .copied()
+            .unwrap_or(self.horizon_y)
+    }
+
+    pub fn is_open_ground(&self, x: i32) -> bool {
+        self.surface_at(x) >= self.horizon_y
+    }
+
+    /// Row a particle stops at. On open ground `depth` spreads landings over
+    /// the ground band so near drops land closer to the viewer.
+    pub fn landing_row(&self, x: i32, depth: u16) -> u16 {
+        if self.is_open_ground(x) {
+            self.horizon_y + depth.min(self.ground_height.saturating_sub(1))
+        } else {
+            self.surface_at(x)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_landing_row() {
+        let mut geometry = SceneGeometry::new(10, 20, 7);
+        geometry.surface_mut()[4] = 12;
+
+        assert_eq!(geometry.landing_row(4, 3), 12);
+        assert_eq!(geometry.landing_row(5, 3), 23);
+        assert_eq!(geometry.landing_row(5, 30), 26);
+        assert_eq!(geometry.landing_row(-3, 0), 20);
+    }
+}
@@ -1,26 +1,56 @@
 use crate::render::TerminalRenderer;
+use crate::scene::season::Season;
+use crate::scene::snow_cover::SnowCover;
+use crate::scene::surface::Surface;
 use crossterm::style::Color;
 use std::io;
 
 #[derive(Default)]
 pub struct Ground;
 
+pub struct GroundRenderConfig {
+    pub width: u16,
+    pub height: u16,
+    pub y_start: u16,
+    pub is_day: bool,
+    pub snow_level: u8,
+    /// Half-width of the puddles in cells, 0 when dry.
+    pub puddle_size: u8,
+    pub surface: Surface,
+    /// Seconds since the scene started, for frost glitter.
+    pub elapsed: f32,
+    pub season: Season,
+}
+
 impl Ground {
     pub fn render(
         &self,
         renderer: &mut TerminalRenderer,
-        width: u16,
-        height: u16,
-        y_start: u16,
-        is_day: bool,
+        config: &GroundRenderConfig,
     ) -> io::Result<()> {
-        let width = width as usize;
-        let height = height as usize;
+        let width = config.width as usize;
+        let height = config.height as usize;
+        let y_start = config.y_start;
+        let is_day = config.is_day;
+        let snow_level = config.snow_level;
+        let surface = config.surface;
 
-        let grass_colors = if is_day {
-            [Color::Green, Color::DarkGreen]
-        } else {
-            [Color::DarkGreen, Color::Rgb { r: 0, g: 50, b: 0 }]
+        let grass_colors = match (surface, is_day) {
+            (Surface::Frosty, true) => [rgb(170, 190, 180), rgb(120, 150, 130)],
+            (Surface::Frosty, false) => [Color::Grey, Color::DarkGrey],
+            (Surface::Icy, true) => [rgb(170, 220, 240), rgb(120, 170, 200)],
+            (Surface::Icy, false) => [rgb(90, 120, 140), rgb(60, 90, 110)],
+            (Surface::Parched, true) => [rgb(180, 160, 70), rgb(140, 115, 50)],
+            (Surface::Parched, false) => [rgb(90, 80, 35), rgb(70, 57, 25)],
+            (_, true) => [Color::Green, Color::DarkGreen],
+            (_, false) => [Color::DarkGreen, Color::Rgb { r: 0, g: 50, b: 0 }],
+        };
+
+        // Flowers follow the season; frost kills them, drought thins them out
+        let flower_chance = match surface {
+            Surface::Frosty | Surface::Icy => 0,
+            Surface::Parched => config.season.flower_chance().min(2),
+            _ => config.season.flower_chance(),
         };
 
         let flower_colors = if is_day {
@@ -34,20 +64,26 @@ impl Ground {
             ]
         };
 
-        let soil_color = if is_day {
-            Color::Rgb {
-                r: 101,
-                g: 67,
-                b: 33,
-            }
-        } else {
-            Color::Rgb {
-                r: 60,
-                g: 40,
-                b: 20,
-            }
+        let snow_color = SnowCover::color(is_day);
+        let frozen = matches!(surface, Surface::Frosty | Surface::Icy);
+        let puddle_color = match (frozen, is_day) {
+            (true, true) => rgb(170, 220, 240),
+            (true, false) => rgb(90, 120, 140),
+            (false, true) => Color::Blue,
+            (false, false) => Color::DarkBlue,
+        };
+
+        let soil_color = match (surface, is_day) {
+            (Surface::Wet, true) => rgb(70, 45, 22),
+            (Surface::Wet, false) => rgb(40, 26, 13),
+            (Surface::Parched, true) => rgb(150, 110, 70),
+            (Surface::Parched, false) => rgb(85, 62, 40),
+            (_, true) => rgb(101, 67, 33),
+            (_, false) => rgb(60, 40, 20),
         };
 
+        let twinkle = (config.elapsed * 3.0) as usize;
+
         // Simple seeded RNG for deterministic decoration positions
         fn pseudo_rand(x: usize, y: usize) -> u32 {
             ((x as u32 ^ 0x5DEECE6).wrapping_mul(y as u32 ^ 0xB)) % 100
@@ -55,11 +91,37 @@ impl Ground {
 
         for y in 0..height {
             for x in 0..width {
-                let (ch, color) = if y == 0 {
+                let (ch, color) = if y == 0 && snow_level > 0 {
+                    // Dusting lets grass poke through; deeper snow hides it
+                    let r = pseudo_rand(x, y);
+                    if snow_level == 1 && r >= 40 {
+                        ('^', grass_colors[1])
+                    } else if r < 20 {
+                        ('.', snow_color)
+                    } else {
+                        ('~', snow_color)
+                    }
+                } else if y == 1 && snow_level >= 3 {
+                    let r = pseudo_rand(x, y);
+                    (if r < 30 { '~' } else { ' ' }, snow_color)
+                } else if snow_level == 0 && Self::in_puddle(x, y, config.puddle_size) {
+                    let ch = if frozen {
+                        '='
+                    } else if (x + y) % 3 == 0 {
+                        '~'
+                    } else {
+                        '_'
+                    };
+                    (ch, puddle_color)
+                } else if y == 0 && frozen && pseudo_rand(x, 7) < 30 && (x * 7 + twinkle) % 11 == 0
+                {
+                    // Frost glitter catching the light
+                    ('*', if is_day { Color::White } else { Color::Grey })
+                } else if y == 0 {
                     // Top layer: Grass/Flowers only (no path)
                     let r = pseudo_rand(x, y);
-                    if r < 5 {
-                        // 5% chance of flower
+                    if r < flower_chance {
+                        // Seasonal chance of flower
                         let f_idx = (x + y) % flower_colors.len();
                         ('*', flower_colors[f_idx])
                     } else if r < 15 {
@@ -86,4 +148,22 @@ impl Ground {
         }
         Ok(())
     }
+
+    /// Puddles sit in the dips just below the grass line and widen as the
+    /// ground gets wetter.
+    fn in_puddle(x: usize, y: usize, size: u8) -> bool {
+        if size == 0 || !(1..=3).contains(&y) {
+            return false;
+        }
+        let size = size as usize;
+        (x.saturating_sub(size)..=x + size).any(|cx| Self::is_puddle_site(cx, y))
+    }
+
+    fn is_puddle_site(x: usize, y: usize) -> bool {
+        (x * 7 + y * 13) % 53 == 0
+    }
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
 }
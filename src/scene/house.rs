@@ -1,7 +1,13 @@
+use crate::animation::Wind;
 use crate::render::TerminalRenderer;
+use crate::scene::snow_cover::SnowCover;
+use chrono::{NaiveDateTime, Timelike};
 use crossterm::style::Color;
 use std::io;
 
+/// Apparent temperature in °C below which the fireplace is lit.
+pub const DEFAULT_FIREPLACE_TEMPERATURE: f64 = 12.0;
+
 const WOOD_COLOR: Color = Color::Rgb {
     r: 210,
     g: 180,
@@ -13,13 +19,138 @@ const DOOR_COLOR: Color = Color::Rgb {
     b: 19,
 };
 
+const WINDOW_COUNT: usize = 8;
+/// The living room window, where the fireplace is.
+const HEARTH_WINDOW: usize = 7;
+/// Rooms nobody uses; their windows stay dark.
+const EMPTY_ROOMS: [usize; 2] = [2, 6];
+
 #[derive(Default)]
 pub struct House;
 
+/// What a window shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowLight {
+    /// Reflecting the sky.
+    Daylight,
+    Dark,
+    Lit,
+    /// Lit behind drawn curtains.
+    Curtained,
+    /// Glowing with the fire in the hearth.
+    Firelight,
+}
+
+impl WindowLight {
+    /// Rooms light up after dusk, draw their curtains through the evening
+    /// and go dark one by one at bedtime; early risers switch on again
+    /// before dawn. `room` staggers the times so windows change one by one.
+    pub fn at(room: usize, time: NaiveDateTime, is_day: bool) -> Self {
+        if is_day {
+            return WindowLight::Daylight;
+        }
+
+        // Counting from noon keeps an evening and the night after it together
+        let since_noon = (time.hour() * 60 + time.minute() + 12 * 60) % (24 * 60);
+        let spread = room as u32;
+        let curtains = 8 * 60 + spread * 29 % 120; // 20:00 to 22:00
+        let bedtime = 10 * 60 + spread * 41 % 150; // 22:00 to 00:30
+        let wake = 18 * 60 + 30 + spread * 17 % 60; // 06:30 to 07:30
+        if since_noon >= bedtime && since_noon < wake {
+            WindowLight::Dark
+        } else if since_noon >= curtains && since_noon < bedtime {
+            WindowLight::Curtained
+        } else {
+            WindowLight::Lit
+        }
+    }
+
+    /// `elapsed` is seconds since the scene started, for the firelight
+    /// flicker.
+    pub fn color(self, elapsed: f32) -> Color {
+        match self {
+            WindowLight::Daylight => Color::Cyan,
+            WindowLight::Dark => Color::DarkGrey,
+            WindowLight::Lit => Color::Yellow,
+            WindowLight::Curtained => Color::Rgb {
+                r: 200,
+                g: 110,
+                b: 60,
+            },
+            WindowLight::Firelight => {
+                let t = elapsed;
+                let flicker = ((t * 7.0).sin() * 0.5 + (t * 13.0 + 1.3).sin() * 0.3 + 0.8) / 1.6;
+                Color::Rgb {
+                    r: 255,
+                    g: (110.0 + flicker * 90.0) as u8,
+                    b: (20.0 + flicker * 30.0) as u8,
+                }
+            }
+        }
+    }
+}
+
+/// The lights behind the windows this frame.
+pub struct Interior {
+    windows: [WindowLight; WINDOW_COUNT],
+    /// Seconds since the scene started, for the firelight flicker.
+    elapsed: f32,
+}
+
+impl Interior {
+    /// Occupied rooms follow [`WindowLight::at`]; the living room glows
+    /// while the fire is lit.
+    pub fn new(time: NaiveDateTime, is_day: bool, fireplace_lit: bool, elapsed: f32) -> Self {
+        let windows = std::array::from_fn(|i| {
+            if is_day {
+                return WindowLight::Daylight;
+            }
+            if EMPTY_ROOMS.contains(&i) {
+                return WindowLight::Dark;
+            }
+
+            match WindowLight::at(i, time, is_day) {
+                WindowLight::Dark => WindowLight::Dark,
+                _ if i == HEARTH_WINDOW && fireplace_lit => WindowLight::Firelight,
+                light => light,
+            }
+        });
+
+        Self { windows, elapsed }
+    }
+
+    fn color(&self, window: usize) -> Color {
+        self.windows[window].color(self.elapsed)
+    }
+}
+
 impl House {
     pub const WIDTH: u16 = 64;
     pub const HEIGHT: u16 = 13;
     pub const CHIMNEY_X_OFFSET: u16 = 10;
+    /// Column of the weather vane mast, standing on the roof ridge.
+    pub const VANE_X_OFFSET: u16 = 17;
+    /// Roof edges that drip in the rain, as (column, row) offsets.
+    pub const EAVES: [(u16, u16); 2] = [(3, 7), (34, 6)];
+    /// Spots on the roof slope and porch rail birds sit on, as (column, row)
+    /// offsets of the empty cell above.
+    pub const PERCHES: [(u16, u16); 7] =
+        [(5, 5), (20, 3), (24, 4), (28, 5), (40, 9), (48, 9), (56, 9)];
+    /// Window frames as (column of the left bracket, row, width).
+    pub const WINDOWS: [(u16, u16, u16); WINDOW_COUNT] = [
+        (8, 8, 2),
+        (12, 8, 2),
+        (17, 8, 2),
+        (22, 8, 2),
+        (27, 8, 2),
+        (8, 10, 2),
+        (12, 10, 2),
+        (22, 10, 5),
+    ];
+    /// Left edge of the front door.
+    pub const DOOR_X_OFFSET: u16 = 16;
+    /// Runs holiday lights hang along, as (first column, last column, row).
+    pub const LIGHT_STRINGS: [(u16, u16, u16); 2] = [(3, 31, 7), (31, 63, 10)];
 
     pub fn height(&self) -> u16 {
         Self::HEIGHT
@@ -53,8 +184,11 @@ impl House {
         x: u16,
         y: u16,
         is_day: bool,
+        snow_level: u8,
+        interior: &Interior,
     ) -> io::Result<()> {
         let ascii = self.get_ascii();
+        let snow_color = SnowCover::color(is_day);
 
         let wood_color = if is_day {
             WOOD_COLOR
@@ -70,7 +204,6 @@ impl House {
         } else {
             Color::DarkMagenta
         };
-        let window_color = if is_day { Color::Cyan } else { Color::Yellow };
 
         for (i, line) in ascii.iter().enumerate() {
             let row = y + i as u16;
@@ -80,7 +213,9 @@ impl House {
                     for (j, ch) in line.chars().enumerate() {
                         if ch != ' ' {
                             let col = x + j as u16;
-                            let color = if i < 4 && (ch == '(' || ch == ')' || ch == '_') {
+                            let color = if Self::is_snow_capped(i, j, snow_level) {
+                                snow_color
+                            } else if i < 4 && (ch == '(' || ch == ')' || ch == '_') {
                                 Color::DarkGrey
                             } else if i < 4 {
                                 Color::Grey
@@ -92,15 +227,27 @@ impl House {
                     }
                 }
                 7 => {
-                    renderer.render_line_colored(x, row, line, roof_color)?;
+                    let eave_color = if snow_level >= 3 {
+                        snow_color
+                    } else {
+                        roof_color
+                    };
+                    renderer.render_line_colored(x, row, line, eave_color)?;
                 }
                 8..=10 => {
                     for (j, ch) in line.chars().enumerate() {
                         if ch != ' ' {
                             let col = x + j as u16;
-                            let color = if ch == '[' || ch == ']' {
-                                window_color
-                            } else if ch == '|' || ch == '.' || ch == '_' {
+                            if let Some(window) = Self::window_at(j as u16, i as u16) {
+                                // Drawn curtains hang in folds
+                                let ch = match interior.windows[window] {
+                                    WindowLight::Curtained if ch == '_' => '|',
+                                    _ => ch,
+                                };
+                                renderer.render_char(col, row, ch, interior.color(window))?;
+                                continue;
+                            }
+                            let color = if ch == '|' || ch == '.' || ch == '_' {
                                 wood_color
                             } else if ch == '(' || ch == ')' {
                                 DOOR_COLOR
@@ -132,7 +279,9 @@ impl House {
                     for (j, ch) in line.chars().enumerate() {
                         if ch != ' ' {
                             let col = x + j as u16;
-                            let color = if ch == '^' {
+                            let color = if ch == '^' && snow_level >= 2 {
+                                snow_color
+                            } else if ch == '^' {
                                 if is_day {
                                     Color::Green
                                 } else {
@@ -154,4 +303,164 @@ impl House {
         }
         Ok(())
     }
+
+    fn window_at(col: u16, row: u16) -> Option<usize> {
+        Self::WINDOWS
+            .iter()
+            .position(|&(x, y, width)| y == row && (x..x + width).contains(&col))
+    }
+
+    /// Lowers `surface` (topmost solid row per column) to the house outline.
+    /// The smoke placeholders above the chimney are not solid.
+    pub fn add_surfaces(&self, x: u16, y: u16, surface: &mut [u16]) {
+        for (i, line) in self.get_ascii().iter().enumerate().skip(3) {
+            for (j, ch) in line.chars().enumerate() {
+                let col = (x + j as u16) as usize;
+                if ch != ' ' && ch != '(' && col < surface.len() {
+                    surface[col] = surface[col].min(y + i as u16);
+                }
+            }
+        }
+    }
+
+    /// Snow settles on the ridge first and creeps down the roof rows as it
+    /// deepens; the chimney (left of the ridge) stays clear.
+    fn is_snow_capped(row: usize, col: usize, snow_level: u8) -> bool {
+        match row {
+            3 => snow_level >= 1 && col >= 14,
+            4..=6 => row - 3 <= snow_level as usize && !(row == 4 && col < 14),
+            _ => false,
+        }
+    }
+
+    /// Ice glaze on the eave line with icicles hanging below it. Skipped
+    /// under snow, which hides the glaze.
+    pub fn render_ice(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        y: u16,
+        icicle_length: u8,
+        is_day: bool,
+    ) -> io::Result<()> {
+        if icicle_length == 0 {
+            return Ok(());
+        }
+
+        let ice_color = if is_day {
+            Color::Rgb {
+                r: 190,
+                g: 230,
+                b: 255,
+            }
+        } else {
+            Color::Rgb {
+                r: 100,
+                g: 130,
+                b: 160,
+            }
+        };
+
+        let ascii = self.get_ascii();
+        let eave_row = 7;
+        renderer.render_line_colored(
+            x + 3,
+            y + eave_row,
+            ascii[eave_row as usize].trim(),
+            ice_color,
+        )?;
+
+        // Uneven icicles every few columns along the overhang
+        for col in (3..=31u16).step_by(3) {
+            let length = match col % 4 {
+                0 => icicle_length,
+                1 => icicle_length.saturating_sub(1),
+                _ => icicle_length.div_ceil(2),
+            };
+            for i in 0..length as u16 {
+                let ch = if i + 1 == length as u16 { 'v' } else { '|' };
+                renderer.render_char(x + col, y + eave_row + 1 + i, ch, ice_color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the weather vane on the ridge. The arrow points into the wind
+    /// and swings around the reported direction, harder in stronger wind.
+    pub fn render_weather_vane(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        y: u16,
+        wind: Wind,
+        elapsed: f32,
+        is_day: bool,
+    ) -> io::Result<()> {
+        let beaufort = wind.beaufort() as f32;
+        let swing = if wind.speed_ms < 0.5 {
+            0.0
+        } else {
+            (elapsed * (0.6 + beaufort * 0.4)).sin() * (5.0 + beaufort * 4.0)
+        };
+        let heading = (wind.direction_deg + swing).to_radians();
+
+        // Viewer faces north: west is left, south is towards the viewer
+        let across = -heading.sin();
+        let arrow = if across > 0.38 {
+            "<-="
+        } else if across < -0.38 {
+            "=->"
+        } else if heading.cos() < 0.0 {
+            " o "
+        } else {
+            " * "
+        };
+
+        let color = if is_day {
+            Color::DarkYellow
+        } else {
+            Color::Grey
+        };
+        let mast_x = x + Self::VANE_X_OFFSET;
+        for (j, ch) in arrow.chars().enumerate() {
+            if ch != ' ' {
+                renderer.render_char(mast_x - 1 + j as u16, y + 1, ch, color)?;
+            }
+        }
+        renderer.render_char(mast_x, y + 2, '|', color)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 15)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_window_lights_through_the_night() {
+        let count =
+            |interior: &Interior, light| interior.windows.iter().filter(|&&w| w == light).count();
+
+        let day = Interior::new(at(12, 0), true, true, 0.0);
+        assert_eq!(count(&day, WindowLight::Daylight), WINDOW_COUNT);
+
+        let evening = Interior::new(at(19, 0), false, true, 0.0);
+        assert_eq!(evening.windows[HEARTH_WINDOW], WindowLight::Firelight);
+        assert_eq!(count(&evening, WindowLight::Dark), EMPTY_ROOMS.len());
+
+        let late = Interior::new(at(23, 30), false, false, 0.0);
+        assert!(count(&late, WindowLight::Dark) > EMPTY_ROOMS.len());
+        assert!(count(&late, WindowLight::Curtained) > 0);
+
+        let small_hours = Interior::new(at(3, 0), false, true, 0.0);
+        assert_eq!(count(&small_hours, WindowLight::Dark), WINDOW_COUNT);
+    }
 }
@@ -1,15 +1,52 @@
 pub mod decorations;
+pub mod events;
+pub mod geometry;
 pub mod ground;
 pub mod house;
+pub mod puddles;
+pub mod season;
+pub mod snow_cover;
+pub mod surface;
+pub mod themes;
 
+pub use geometry::SceneGeometry;
+pub use season::Season;
+pub use themes::{Scene, SceneTheme};
+
+use crate::animation::{DAY_NIGHT_FADE_TIME, Wind, animation_step, fade_toward};
+use crate::error::ConfigError;
 use crate::render::TerminalRenderer;
-use crate::weather::WeatherConditions;
+use crate::weather::WeatherData;
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, Utc};
+use events::{CalendarEvent, EventCalendar, EventKind};
 use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub struct WorldScene {
-    house: house::House,
+    landscape: Box<dyn Scene>,
     ground: ground::Ground,
-    decorations: decorations::Decorations,
+    snow_cover: snow_cover::SnowCover,
+    puddles: puddles::Puddles,
+    surface: surface::SurfaceState,
+    events: EventCalendar,
+    /// Offset of the location's clock from UTC, once the weather reports it.
+    utc_offset_seconds: Option<i32>,
+    /// Apparent temperature in °C below which the fireplace is lit.
+    fireplace_temperature: f64,
+    fireplace_lit: bool,
+    geometry: SceneGeometry,
+    wind: Wind,
+    season: Season,
+    /// Animation time the scene has run, which sway and flicker follow.
+    clock: Duration,
+    /// When the scene last advanced.
+    last_update: Option<Instant>,
+    /// The time of the frame being drawn.
+    utc: DateTime<Utc>,
+    /// How far the palette has turned from night at 0 to day at 1, once the
+    /// scene has been told which it is.
+    daylight: Option<f32>,
     width: u16,
     height: u16,
 }
@@ -18,64 +55,271 @@ impl WorldScene {
     pub const GROUND_HEIGHT: u16 = 7;
 
     pub fn new(width: u16, height: u16) -> Self {
-        let house = house::House;
-        let ground = ground::Ground;
-        let decorations = decorations::Decorations::new();
-
-        Self {
-            house,
-            ground,
-            decorations,
+        let mut scene = Self {
+            landscape: SceneTheme::default().build(),
+            ground: ground::Ground,
+            snow_cover: snow_cover::SnowCover::new(),
+            puddles: puddles::Puddles::new(),
+            surface: surface::SurfaceState::new(),
+            events: EventCalendar::default(),
+            utc_offset_seconds: None,
+            fireplace_temperature: house::DEFAULT_FIREPLACE_TEMPERATURE,
+            fireplace_lit: false,
+            geometry: SceneGeometry::flat(width, height),
+            wind: Wind::default(),
+            season: Season::default(),
+            clock: Duration::ZERO,
+            last_update: None,
+            utc: Utc::now(),
+            daylight: None,
             width,
             height,
+        };
+        scene.landscape.layout(width, scene.horizon_y());
+        scene.geometry = scene.build_geometry();
+        scene
+    }
+
+    /// Swaps the landscape standing on the ground, such as a built-in
+    /// [`SceneTheme`].
+    pub fn set_landscape(&mut self, landscape: Box<dyn Scene>) {
+        self.landscape = landscape;
+        self.landscape.layout(self.width, self.horizon_y());
+        self.geometry = self.build_geometry();
+    }
+
+    /// Replaces the landscape with one drawn in a scene file.
+    pub fn load_landscape(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let landscape = themes::custom::CustomScene::load(path)?;
+        self.set_landscape(Box::new(landscape));
+        Ok(())
+    }
+
+    pub fn update_wind(&mut self, wind: Wind) {
+        self.wind = wind;
+    }
+
+    /// Bare winter branches change where snow and rain land, so the
+    /// geometry is rebuilt.
+    pub fn set_season(&mut self, season: Season) {
+        if self.season != season {
+            self.season = season;
+            self.geometry = self.build_geometry();
         }
     }
 
-    pub fn update_size(&mut self, width: u16, height: u16) {
-        self.width = width;
-        self.height = height;
+    pub fn set_events(&mut self, events: Vec<CalendarEvent>) {
+        self.events = EventCalendar::new(events);
+    }
+
+    pub fn has_event(&self, kind: EventKind) -> bool {
+        self.events.is_active(kind)
+    }
+
+    pub fn set_fireplace_temperature(&mut self, celsius: f64) {
+        self.fireplace_temperature = celsius;
+    }
+
+    /// Whether it feels cold enough outside to light the fire.
+    pub fn fireplace_lit(&self) -> bool {
+        self.fireplace_lit
+    }
+
+    pub fn update_weather(&mut self, weather: &WeatherData) {
+        if weather.utc_offset_seconds.is_some() {
+            self.utc_offset_seconds = weather.utc_offset_seconds;
+        }
+        self.fireplace_lit = weather.apparent_temperature < self.fireplace_temperature;
+        self.snow_cover.set_weather(weather);
+        self.puddles.set_weather(weather);
+        self.surface.set_weather(weather);
+    }
+
+    /// Advances scene state that changes over time to `now`, once per frame,
+    /// at the same pace as the effects, with clocks in the scene showing
+    /// `utc` and the palette turning toward day or night.
+    pub fn update(
+        &mut self,
+        now: Instant,
+        utc: DateTime<Utc>,
+        speed_multiplier: f32,
+        is_day: bool,
+    ) {
+        let elapsed = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_update = Some(now);
+        self.utc = utc;
+        let dt = animation_step(elapsed, speed_multiplier);
+
+        let target = if is_day { 1.0 } else { 0.0 };
+        self.daylight = Some(self.daylight.map_or(target, |level| {
+            fade_toward(level, target, dt, DAY_NIGHT_FADE_TIME)
+        }));
+        self.clock += dt;
+        self.snow_cover.update(dt);
+        self.puddles.update(dt);
+        self.surface.update(dt);
+        self.events.update(self.local_now());
+    }
+
+    /// Sets the time shown until the first frame.
+    pub fn set_utc(&mut self, utc: DateTime<Utc>) {
+        self.utc = utc;
+    }
+
+    /// The time of the current frame.
+    pub fn utc(&self) -> DateTime<Utc> {
+        self.utc
+    }
+
+    /// Wall-clock time at the location for the current frame, falling back
+    /// to this machine's timezone until the weather reports one.
+    pub fn local_now(&self) -> NaiveDateTime {
+        match self.utc_offset_seconds {
+            Some(offset) => self.utc.naive_utc() + TimeDelta::seconds(offset as i64),
+            None => self.utc.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// Surfaces precipitation collides with, for the current terminal size.
+    pub fn geometry(&self) -> &SceneGeometry {
+        &self.geometry
+    }
+
+    fn horizon_y(&self) -> u16 {
+        self.height.saturating_sub(self.landscape.ground_height())
     }
 
-    pub fn render(
-        &self,
-        renderer: &mut TerminalRenderer,
-        conditions: &WeatherConditions,
-    ) -> io::Result<()> {
-        let horizon_y = self.height.saturating_sub(Self::GROUND_HEIGHT);
+    fn build_geometry(&self) -> SceneGeometry {
+        let mut geometry =
+            SceneGeometry::new(self.width, self.horizon_y(), self.landscape.ground_height());
+        self.landscape.add_geometry(&mut geometry, self.season);
+        geometry.perches.retain(|&(x, _)| x < self.width);
+        geometry.eaves.retain(|&(x, _)| x < self.width);
+        geometry.chimney = self.landscape.chimney();
+        geometry.lights = self.landscape.lights();
+        geometry
+    }
 
-        // House position
-        let house_width = self.house.width();
-        let house_height = self.house.height();
-        let house_x = (self.width / 2).saturating_sub(house_width / 2);
-        let house_y = horizon_y.saturating_sub(house_height);
+    /// Horizontal offset of the tree crowns in cells. Light air leaves them
+    /// still; from a light breeze on they lean downwind and rock with gusts,
+    /// up to two cells and a quicker rhythm by a gale.
+    fn tree_sway(&self) -> f32 {
+        let beaufort = self.wind.beaufort() as f32;
+        let strength = ((beaufort - 2.0) / 6.0).clamp(0.0, 1.0);
+        if strength == 0.0 {
+            return 0.0;
+        }
 
-        // Door/Path alignment
+        let amplitude = strength * 2.0;
+        let lean = self.wind.horizontal().signum() * amplitude * 0.5;
+        let t = self.clock.as_secs_f32();
+        let gust = (t * (0.8 + beaufort * 0.25)).sin() * amplitude * 0.5;
+        lean + gust
+    }
+
+    pub fn update_size(&mut self, width: u16, height: u16) {
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.landscape.layout(width, self.horizon_y());
+            self.geometry = self.build_geometry();
+        }
+    }
+
+    /// Draws the scene, blending the night and day palettes while dawn or
+    /// dusk is under way.
+    pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let daylight = self.daylight.unwrap_or(1.0);
+        if daylight <= 0.0 || daylight >= 1.0 {
+            return self.render_palette(renderer, daylight >= 1.0);
+        }
+        let sky = renderer.snapshot();
+        self.render_palette(renderer, false)?;
+        let night = renderer.snapshot();
+        renderer.restore(&sky);
+        self.render_palette(renderer, true)?;
+        renderer.blend(&night, 1.0 - daylight);
+        Ok(())
+    }
+
+    fn render_palette(&self, renderer: &mut TerminalRenderer, is_day: bool) -> io::Result<()> {
+        let horizon_y = self.horizon_y();
+        let snow_level = self.snow_cover.level();
+        let elapsed = self.clock.as_secs_f32();
 
         // Render Ground
         self.ground.render(
             renderer,
-            self.width,
-            Self::GROUND_HEIGHT,
-            horizon_y,
-            conditions.is_day,
+            &ground::GroundRenderConfig {
+                width: self.width,
+                height: self.landscape.ground_height(),
+                y_start: horizon_y,
+                is_day,
+                snow_level,
+                puddle_size: self.puddles.size(),
+                surface: self.surface.surface(),
+                elapsed,
+                season: self.season,
+            },
         )?;
 
-        // Render House
-        self.house
-            .render(renderer, house_x, house_y, conditions.is_day)?;
+        self.landscape.render(
+            renderer,
+            &themes::SceneFrame {
+                is_day,
+                snow_level,
+                season: self.season,
+                wind: self.wind,
+                sway: self.tree_sway(),
+                local_time: self.local_now(),
+                fireplace_lit: self.fireplace_lit,
+                elapsed,
+                icicle_length: self.surface.icicle_length(),
+            },
+        )?;
 
-        // Render Decorations
-        self.decorations.render(
+        self.events.render(
             renderer,
-            &crate::scene::decorations::DecorationRenderConfig {
+            &events::EventRenderConfig {
+                door_x: self.landscape.door_x(),
+                light_strings: self.landscape.light_strings(),
                 horizon_y,
-                house_x,
-                house_width,
                 width: self.width,
-                is_day: conditions.is_day,
+                is_day,
+                elapsed,
             },
         )?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest crown offset over a few seconds of wind blowing from the west.
+    fn peak_sway(speed_ms: f32) -> f32 {
+        let mut scene = WorldScene::new(80, 24);
+        scene.update_wind(Wind::new(speed_ms, 270.0));
+        (0..200)
+            .map(|step| {
+                scene.clock = Duration::from_millis(step * 50);
+                scene.tree_sway().abs()
+            })
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_tree_sway_grows_with_wind_force() {
+        // Middle of Beaufort 2 through 8.
+        let speeds = [2.5, 4.5, 6.7, 9.4, 12.3, 15.5, 19.0];
+        let peaks: Vec<f32> = speeds.iter().map(|&speed| peak_sway(speed)).collect();
+
+        assert_eq!(peaks[0], 0.0);
+        assert!(peaks.windows(2).all(|pair| pair[1] > pair[0]), "{peaks:?}");
+        assert!(peaks[6] > 1.5 && peaks[6] <= 2.0, "{peaks:?}");
+    }
+}
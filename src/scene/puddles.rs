@@ -0,0 +1,76 @@
+use crate::weather::WeatherData;
+use std::time::Duration;
+
+/// Standing water on the ground. Fills during sustained rain and dries out
+/// afterwards, faster when it is warm.
+pub struct Puddles {
+    wetness: f32,
+    fill_rate: f32,
+    dry_rate: f32,
+}
+
+impl Puddles {
+    pub const MAX_SIZE: u8 = 4;
+
+    pub fn new() -> Self {
+        Self {
+            wetness: 0.0,
+            fill_rate: 0.0,
+            dry_rate: 0.0,
+        }
+    }
+
+    pub fn set_weather(&mut self, weather: &WeatherData) {
+        // Rates in wetness (0-1) per minute
+        self.fill_rate = if weather.condition.is_raining() {
+            (0.05 + weather.precipitation as f32 * 0.03).min(0.3)
+        } else {
+            0.0
+        };
+        self.dry_rate = if weather.condition.is_raining() {
+            0.0
+        } else {
+            0.02 + weather.temperature.max(0.0) as f32 * 0.002
+        };
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.advance(dt.as_secs_f32() / 60.0);
+    }
+
+    fn advance(&mut self, minutes: f32) {
+        self.wetness = (self.wetness + (self.fill_rate - self.dry_rate) * minutes).clamp(0.0, 1.0);
+    }
+
+    /// Puddle half-width in cells, 0 when the ground is dry.
+    pub fn size(&self) -> u8 {
+        (self.wetness * Self::MAX_SIZE as f32).round() as u8
+    }
+}
+
+impl Default for Puddles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_and_dry() {
+        let mut puddles = Puddles::new();
+        puddles.fill_rate = 0.1;
+        puddles.advance(5.0);
+        assert_eq!(puddles.size(), 2);
+
+        puddles.advance(10.0);
+        assert_eq!(puddles.size(), Puddles::MAX_SIZE);
+
+        puddles.fill_rate = 0.0;
+        puddles.dry_rate = 0.05;
+        puddles.advance(20.0);
+        assert_eq!(puddles.size(), 0);
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+
+/// Meteorological season at the viewer's location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    #[default]
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Season for a date, with the months flipped south of the equator.
+    pub fn from_date(date: NaiveDate, latitude: f64) -> Self {
+        let northern = match date.month() {
+            3..=5 => Self::Spring,
+            6..=8 => Self::Summer,
+            9..=11 => Self::Autumn,
+            _ => Self::Winter,
+        };
+
+        if latitude < 0.0 {
+            northern.opposite()
+        } else {
+            northern
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            Self::Spring => Self::Autumn,
+            Self::Summer => Self::Winter,
+            Self::Autumn => Self::Spring,
+            Self::Winter => Self::Summer,
+        }
+    }
+
+    /// Percent chance of a flower in each grass cell.
+    pub fn flower_chance(self) -> u32 {
+        match self {
+            Self::Spring => 9,
+            Self::Summer => 5,
+            Self::Autumn => 2,
+            Self::Winter => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_season_from_date_and_hemisphere() {
+        let april = NaiveDate::from_ymd_opt(2025, 4, 10).unwrap();
+        let october = NaiveDate::from_ymd_opt(2025, 10, 10).unwrap();
+        let december = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+
+        assert_eq!(Season::from_date(april, 52.5), Season::Spring);
+        assert_eq!(Season::from_date(october, 52.5), Season::Autumn);
+        assert_eq!(Season::from_date(december, 52.5), Season::Winter);
+
+        assert_eq!(Season::from_date(april, -33.9), Season::Autumn);
+        assert_eq!(Season::from_date(december, -33.9), Season::Summer);
+    }
+}
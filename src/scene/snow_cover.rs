@@ -0,0 +1,166 @@
+use crate::weather::{SnowIntensity, WeatherData};
+use crossterm::style::Color;
+use std::time::Duration;
+
+const MAX_DEPTH_CM: f32 = 40.0;
+
+/// Snow lying on the scene. Follows the provider's snow depth when it is
+/// reported, otherwise builds up while it snows and melts above freezing.
+pub struct SnowCover {
+    depth_cm: f32,
+    measured_cm: Option<f32>,
+    has_measurement: bool,
+    accumulation_rate: f32,
+    melt_rate: f32,
+}
+
+impl SnowCover {
+    pub fn new() -> Self {
+        Self {
+            depth_cm: 0.0,
+            measured_cm: None,
+            has_measurement: false,
+            accumulation_rate: 0.0,
+            melt_rate: 0.0,
+        }
+    }
+
+    pub fn set_weather(&mut self, weather: &WeatherData) {
+        self.measured_cm = weather
+            .snow_depth
+            .map(|metres| (metres * 100.0).clamp(0.0, MAX_DEPTH_CM as f64) as f32);
+
+        // First report: start from the measured depth instead of growing into it
+        if let Some(measured) = self.measured_cm
+            && !self.has_measurement
+        {
+            self.depth_cm = measured;
+            self.has_measurement = true;
+        }
+
+        // Rates in cm per minute, sped up so changes are visible in a session
+        self.accumulation_rate = if weather.condition.is_snowing() && weather.temperature <= 2.0 {
+            match weather.condition.snow_intensity() {
+                SnowIntensity::Light => 0.3,
+                SnowIntensity::Medium => 0.6,
+                SnowIntensity::Heavy => 1.2,
+            }
+        } else {
+            0.0
+        };
+
+        self.melt_rate = if weather.temperature > 0.0 {
+            let rate = (weather.temperature as f32 * 0.15).min(3.0);
+            if weather.condition.is_raining() {
+                rate * 2.0
+            } else {
+                rate
+            }
+        } else {
+            0.0
+        };
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.advance(dt.as_secs_f32() / 60.0);
+    }
+
+    fn advance(&mut self, minutes: f32) {
+        self.depth_cm = match self.measured_cm {
+            Some(target) => {
+                // Ease towards the report so a new fetch doesn't pop the caps
+                let step = 2.0 * minutes;
+                if self.depth_cm < target {
+                    (self.depth_cm + step).min(target)
+                } else {
+                    (self.depth_cm - step).max(target)
+                }
+            }
+            None => self.depth_cm + (self.accumulation_rate - self.melt_rate) * minutes,
+        }
+        .clamp(0.0, MAX_DEPTH_CM);
+    }
+
+    #[allow(dead_code)]
+    pub fn depth_cm(&self) -> f32 {
+        self.depth_cm
+    }
+
+    /// Cap depth in rows: 0 bare, 1 dusting, 2 covered, 3 deep.
+    pub fn level(&self) -> u8 {
+        match self.depth_cm {
+            d if d < 0.5 => 0,
+            d if d < 5.0 => 1,
+            d if d < 15.0 => 2,
+            _ => 3,
+        }
+    }
+
+    pub fn color(is_day: bool) -> Color {
+        if is_day { Color::White } else { Color::Grey }
+    }
+}
+
+impl Default for SnowCover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::WeatherCondition;
+
+    fn weather(
+        condition: WeatherCondition,
+        temperature: f64,
+        snow_depth: Option<f64>,
+    ) -> WeatherData {
+        WeatherData {
+            condition,
+            temperature,
+            apparent_temperature: temperature,
+            humidity: 80.0,
+            precipitation: 0.0,
+            wind_speed: 2.0,
+            wind_direction: 0.0,
+            cloud_cover: 100.0,
+            cloud_cover_low: None,
+            cloud_cover_mid: None,
+            cloud_cover_high: None,
+            pressure: 1013.0,
+            visibility: None,
+            snow_depth,
+            cape: None,
+            utc_offset_seconds: None,
+            is_day: true,
+            moon_phase: None,
+            timestamp: "2024-01-01T12:00".to_string(),
+            hourly_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_accumulates_while_snowing_and_melts_when_warm() {
+        let mut cover = SnowCover::new();
+        cover.set_weather(&weather(WeatherCondition::Snow, -2.0, None));
+        cover.advance(10.0);
+        assert_eq!(cover.level(), 2);
+
+        cover.set_weather(&weather(WeatherCondition::Clear, 8.0, None));
+        cover.advance(10.0);
+        assert_eq!(cover.level(), 0);
+    }
+
+    #[test]
+    fn test_follows_measured_depth() {
+        let mut cover = SnowCover::new();
+        cover.set_weather(&weather(WeatherCondition::Clear, -5.0, Some(0.2)));
+        assert_eq!(cover.level(), 3);
+
+        cover.set_weather(&weather(WeatherCondition::Clear, -5.0, Some(0.02)));
+        cover.advance(1.0);
+        assert!((cover.depth_cm() - 18.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::weather::{WeatherCondition, WeatherData};
+use std::time::Duration;
+
+/// How the ground and outdoor surfaces look right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Normal,
+    Wet,
+    Frosty,
+    Icy,
+    Parched,
+}
+
+/// Surface conditions built up from recent weather: soil wetness after rain,
+/// ice from freezing rain or refrozen water, frost and heat-browned grass.
+pub struct SurfaceState {
+    wetness: f32,
+    ice: f32,
+    temperature: f32,
+    humidity: f32,
+    raining: bool,
+    freezing_rain: bool,
+}
+
+impl SurfaceState {
+    pub fn new() -> Self {
+        Self {
+            wetness: 0.0,
+            ice: 0.0,
+            temperature: 15.0,
+            humidity: 60.0,
+            raining: false,
+            freezing_rain: false,
+        }
+    }
+
+    pub fn set_weather(&mut self, weather: &WeatherData) {
+        self.temperature = weather.temperature as f32;
+        self.humidity = weather.humidity as f32;
+        self.freezing_rain = weather.condition == WeatherCondition::FreezingRain;
+        self.raining = weather.condition.is_raining() || weather.precipitation > 0.0;
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.advance(dt.as_secs_f32() / 60.0);
+    }
+
+    /// Rates are per minute, sped up so changes are visible in a session.
+    fn advance(&mut self, minutes: f32) {
+        let freezing = self.temperature <= 0.0;
+
+        if self.freezing_rain {
+            self.ice += 0.3 * minutes;
+        } else if freezing && self.wetness > 0.3 {
+            // Standing water refreezes
+            self.ice += 0.05 * minutes;
+            self.wetness -= 0.05 * minutes;
+        } else if !freezing {
+            self.ice -= (0.05 + self.temperature * 0.02) * minutes;
+        }
+
+        if self.raining && !self.freezing_rain {
+            self.wetness += 0.5 * minutes;
+        } else if !freezing {
+            self.wetness -= (0.05 + self.temperature.max(0.0) * 0.005) * minutes;
+        }
+
+        self.ice = self.ice.clamp(0.0, 1.0);
+        self.wetness = self.wetness.clamp(0.0, 1.0);
+    }
+
+    pub fn surface(&self) -> Surface {
+        if self.ice > 0.1 {
+            Surface::Icy
+        } else if self.temperature <= 0.0 {
+            Surface::Frosty
+        } else if self.wetness > 0.1 {
+            Surface::Wet
+        } else if self.temperature >= 30.0 && self.humidity < 40.0 {
+            Surface::Parched
+        } else {
+            Surface::Normal
+        }
+    }
+
+    /// Icicle length in rows, grown from the ice glaze.
+    pub fn icicle_length(&self) -> u8 {
+        if self.ice <= 0.1 {
+            0
+        } else {
+            (self.ice * 3.0).ceil() as u8
+        }
+    }
+}
+
+impl Default for SurfaceState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freezing_rain_builds_ice_that_melts() {
+        let mut state = SurfaceState::new();
+        state.temperature = -1.0;
+        state.freezing_rain = true;
+        state.advance(5.0);
+        assert_eq!(state.surface(), Surface::Icy);
+        assert_eq!(state.icicle_length(), 3);
+
+        state.freezing_rain = false;
+        state.temperature = 10.0;
+        state.advance(5.0);
+        assert_eq!(state.icicle_length(), 0);
+    }
+
+    #[test]
+    fn test_wet_after_rain_then_dries() {
+        let mut state = SurfaceState::new();
+        state.raining = true;
+        state.advance(1.0);
+        assert_eq!(state.surface(), Surface::Wet);
+
+        state.raining = false;
+        state.advance(10.0);
+        assert_eq!(state.surface(), Surface::Normal);
+    }
+
+    #[test]
+    fn test_parched_when_hot_and_dry() {
+        let mut state = SurfaceState::new();
+        state.temperature = 34.0;
+        state.humidity = 20.0;
+        assert_eq!(state.surface(), Surface::Parched);
+    }
+}
@@ -0,0 +1,151 @@
+use super::{SceneFrame, add_art_surface, draw_art, night_shade};
+use crate::render::TerminalRenderer;
+use crate::scene::SceneGeometry;
+use crate::scene::house::WindowLight;
+use crate::scene::snow_cover::SnowCover;
+use crossterm::style::Color;
+use std::io;
+
+/// A small house drawn from ASCII art, for the landscapes that have one.
+/// By convention the top row holds the chimney pot `=`, windows are `[]`
+/// with the first one by the hearth, and the roof ends at the eave row.
+pub struct Cabin {
+    art: &'static [&'static str],
+    /// Row of the eaves; everything above it is chimney and roof.
+    eave_row: usize,
+    /// Left edge of the door, from the left of the art.
+    door_offset: u16,
+    wall: Color,
+    roof: Color,
+    x: u16,
+    y: u16,
+}
+
+impl Cabin {
+    pub fn new(
+        art: &'static [&'static str],
+        eave_row: usize,
+        door_offset: u16,
+        wall: Color,
+        roof: Color,
+    ) -> Self {
+        Self {
+            art,
+            eave_row,
+            door_offset,
+            wall,
+            roof,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.art.iter().map(|l| l.len()).max().unwrap_or(0) as u16
+    }
+
+    pub fn height(&self) -> u16 {
+        self.art.len() as u16
+    }
+
+    /// Stands the cabin on the horizon with its left edge at `x`.
+    pub fn place(&mut self, x: u16, horizon_y: u16) {
+        self.x = x;
+        self.y = horizon_y.saturating_sub(self.height());
+    }
+
+    pub fn door_x(&self) -> u16 {
+        self.x + self.door_offset
+    }
+
+    pub fn chimney(&self) -> Option<(u16, u16)> {
+        let col = self.art.first()?.find('=')?;
+        Some((self.x + col as u16, self.y))
+    }
+
+    /// Left bracket of each window, as (column, row).
+    pub fn windows(&self) -> Vec<(u16, u16)> {
+        self.art
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.match_indices("[]")
+                    .map(move |(col, _)| (self.x + col as u16, self.y + row as u16))
+            })
+            .collect()
+    }
+
+    /// Along the eaves, where holiday lights hang.
+    pub fn light_string(&self) -> (u16, u16, u16) {
+        let eaves = self.art[self.eave_row];
+        let first = eaves.find(|ch| ch != ' ').unwrap_or(0) as u16;
+        let last = eaves.rfind(|ch| ch != ' ').unwrap_or(0) as u16;
+        (
+            self.x + first + 1,
+            self.x + last.saturating_sub(1),
+            self.y + self.eave_row as u16,
+        )
+    }
+
+    pub fn add_geometry(&self, geometry: &mut SceneGeometry) {
+        add_art_surface(geometry.surface_mut(), self.x, self.y, self.art);
+
+        let (first, last, row) = self.light_string();
+        geometry.eaves.extend([(first - 1, row), (last + 1, row)]);
+
+        // Either end of the ridge, below the chimney row
+        let ridge = self.art[1];
+        if let (Some(left), Some(right)) = (ridge.find(|ch| ch != ' '), ridge.rfind(|ch| ch != ' '))
+        {
+            geometry.perches.extend([
+                (self.x + left as u16, self.y),
+                (self.x + right as u16, self.y),
+            ]);
+        }
+        geometry.lightning_targets.extend(self.chimney());
+    }
+
+    /// Snow settles on the ridge first and creeps down to the eaves as it
+    /// deepens.
+    pub fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let windows = self.windows();
+        let snow = SnowCover::color(frame.is_day);
+        let wall = night_shade(self.wall, frame.is_day);
+        let roof = night_shade(self.roof, frame.is_day);
+
+        draw_art(renderer, self.x as i32, self.y, self.art, |row, col, ch| {
+            let cell = (self.x + col as u16, self.y + row as u16);
+            let window = windows
+                .iter()
+                .position(|&(x, y)| y == cell.1 && (x..x + 2).contains(&cell.0));
+            if let Some(room) = window {
+                let light = match WindowLight::at(room, frame.local_time, frame.is_day) {
+                    WindowLight::Dark => WindowLight::Dark,
+                    _ if room == 0 && frame.fireplace_lit => WindowLight::Firelight,
+                    light => light,
+                };
+                return (ch, light.color(frame.elapsed));
+            }
+
+            let color = match row {
+                0 => Color::DarkGrey,
+                r if r <= self.eave_row && r <= frame.snow_level as usize => snow,
+                r if r <= self.eave_row => roof,
+                _ => wall,
+            };
+            (ch, color)
+        })?;
+
+        if frame.snow_level == 0 && frame.icicle_length > 0 {
+            let (first, last, row) = self.light_string();
+            for col in (first..=last).step_by(3) {
+                let length = (frame.icicle_length as u16).saturating_sub(col % 2);
+                for i in 0..length {
+                    let ch = if i + 1 == length { 'v' } else { '|' };
+                    renderer.render_char(col, row + 1 + i, ch, Color::Cyan)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
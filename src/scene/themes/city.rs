@@ -0,0 +1,321 @@
+use super::{Scene, SceneFrame, night_shade, scramble};
+use crate::render::TerminalRenderer;
+use crate::scene::house::WindowLight;
+use crate::scene::snow_cover::SnowCover;
+use crate::scene::{SceneGeometry, Season};
+use chrono::{Datelike, NaiveDateTime, Timelike, Weekday};
+use crossterm::style::Color;
+use std::io;
+
+/// Share of offices still occupied at each local hour on a working day.
+#[rustfmt::skip]
+const OFFICE_OCCUPANCY: [f32; 24] = [
+    0.04, 0.03, 0.03, 0.03, 0.03, 0.05, 0.15, 0.45, 0.8, 0.9, 0.9, 0.9,
+    0.85, 0.9, 0.9, 0.85, 0.75, 0.55, 0.35, 0.2, 0.12, 0.08, 0.06, 0.05,
+];
+/// Weekends see a few people catching up on work.
+const WEEKEND_OCCUPANCY: f32 = 0.25;
+const STREET_LAMP_SPACING: u16 = 26;
+const ANTENNA_HEIGHT: u16 = 3;
+
+const FACADES: [Color; 3] = [
+    Color::Rgb {
+        r: 150,
+        g: 150,
+        b: 160,
+    },
+    Color::Rgb {
+        r: 150,
+        g: 95,
+        b: 75,
+    },
+    Color::Rgb {
+        r: 110,
+        g: 120,
+        b: 135,
+    },
+];
+
+struct Tower {
+    x: u16,
+    width: u16,
+    height: u16,
+    seed: u32,
+    antenna: bool,
+}
+
+impl Tower {
+    fn top(&self, horizon_y: u16) -> u16 {
+        horizon_y.saturating_sub(self.height)
+    }
+
+    fn middle(&self) -> u16 {
+        self.x + self.width / 2
+    }
+
+    /// The cell at (`row`, `col`) from the top-left of the tower, and for
+    /// windows the number identifying it.
+    fn cell(&self, row: u16, col: u16) -> (char, Option<u32>) {
+        let last_col = self.width - 1;
+        let inner = col.wrapping_sub(1);
+        if row == 0 {
+            return (
+                if col == 0 || col == last_col {
+                    '.'
+                } else {
+                    '-'
+                },
+                None,
+            );
+        }
+        if col == 0 || col == last_col {
+            return ('|', None);
+        }
+        if row + 1 == self.height {
+            let door = self.middle() - self.x;
+            return match col {
+                c if c + 1 == door || c == door + 1 => ('|', None),
+                c if c == door => (' ', None),
+                _ => ('_', None),
+            };
+        }
+        // Pairs of brackets on every other floor, with a full pair only
+        if row % 2 == 1 && inner / 3 * 3 + 2 < last_col - 1 {
+            let window = (row as u32) << 8 | (inner / 3) as u32;
+            match inner % 3 {
+                1 => return ('[', Some(window)),
+                2 => return (']', Some(window)),
+                _ => {}
+            }
+        }
+        (' ', None)
+    }
+}
+
+/// A skyline of office towers. Windows light up through the working day
+/// and go dark one by one as people head home; a few stay lit all night.
+pub struct City {
+    towers: Vec<Tower>,
+    lamps: Vec<u16>,
+    width: u16,
+    horizon_y: u16,
+}
+
+impl City {
+    pub fn new() -> Self {
+        Self {
+            towers: Vec::new(),
+            lamps: Vec::new(),
+            width: 0,
+            horizon_y: 0,
+        }
+    }
+
+    fn central_tower(&self) -> Option<&Tower> {
+        self.towers
+            .iter()
+            .min_by_key(|t| t.middle().abs_diff(self.width / 2))
+    }
+}
+
+impl Default for City {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for City {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        self.width = width;
+        self.horizon_y = horizon_y;
+        self.towers.clear();
+
+        let tallest = (horizon_y * 2 / 3).max(6);
+        let mut x = 1;
+        for i in 0u32.. {
+            let tower_width = 8 + (scramble(i * 3) % 9) as u16;
+            if x + tower_width > width {
+                break;
+            }
+            let height = 5 + (scramble(i * 3 + 1) % (tallest - 4) as u32) as u16;
+            self.towers.push(Tower {
+                x,
+                width: tower_width,
+                height: height.min(horizon_y.saturating_sub(ANTENNA_HEIGHT + 1)),
+                seed: scramble(i * 3 + 2),
+                antenna: scramble(i * 3 + 2) % 3 == 0,
+            });
+            x += tower_width + 1 + (scramble(i * 7) % 3) as u16;
+        }
+        self.towers.retain(|t| t.height >= 3);
+
+        self.lamps = if horizon_y > 5 {
+            (STREET_LAMP_SPACING / 2..width.saturating_sub(3))
+                .step_by(STREET_LAMP_SPACING as usize)
+                .collect()
+        } else {
+            Vec::new()
+        };
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let occupancy = occupancy(frame.local_time);
+        let snow = SnowCover::color(frame.is_day);
+
+        for tower in &self.towers {
+            let top = tower.top(self.horizon_y);
+            let facade = night_shade(FACADES[tower.seed as usize % FACADES.len()], frame.is_day);
+
+            for row in 0..tower.height {
+                for col in 0..tower.width {
+                    let (ch, window) = tower.cell(row, col);
+                    let color = match window {
+                        Some(window) => {
+                            office_light(tower.seed ^ scramble(window), occupancy, frame.is_day)
+                                .color(frame.elapsed)
+                        }
+                        None if row == 0 && frame.snow_level > 0 => snow,
+                        None => facade,
+                    };
+                    // Walls are drawn solid so the sky does not show through
+                    renderer.render_char(tower.x + col, top + row, ch, color)?;
+                }
+            }
+
+            if tower.antenna {
+                let mast = tower.middle();
+                for i in 1..ANTENNA_HEIGHT {
+                    renderer.render_char(mast, top - i, '|', Color::DarkGrey)?;
+                }
+                // Aviation warning light, blinking after dark
+                let blink = !frame.is_day && frame.elapsed % 2.0 < 1.0;
+                let beacon = if blink { Color::Red } else { Color::DarkGrey };
+                renderer.render_char(mast, top - ANTENNA_HEIGHT, '*', beacon)?;
+            }
+        }
+
+        for &x in &self.lamps {
+            for row in 1..=3 {
+                renderer.render_char(x, self.horizon_y - row, '|', Color::DarkGrey)?;
+            }
+            let bulb = if frame.is_day {
+                Color::Grey
+            } else {
+                Color::Yellow
+            };
+            renderer.render_line_colored(x, self.horizon_y - 4, ",-", Color::DarkGrey)?;
+            renderer.render_char(x + 2, self.horizon_y - 4, 'o', bulb)?;
+        }
+        Ok(())
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, _season: Season) {
+        for tower in &self.towers {
+            let top = tower.top(self.horizon_y);
+            let right = tower.x + tower.width - 1;
+            for x in tower.x..=right.min(self.width.saturating_sub(1)) {
+                geometry.surface_mut()[x as usize] = top;
+            }
+            geometry.eaves.extend([(tower.x, top), (right, top)]);
+            geometry
+                .perches
+                .extend([(tower.x, top - 1), (right, top - 1)]);
+            let tip = if tower.antenna {
+                top - ANTENNA_HEIGHT
+            } else {
+                top
+            };
+            geometry.lightning_targets.push((tower.middle(), tip));
+        }
+        geometry
+            .perches
+            .extend(self.lamps.iter().map(|&x| (x + 2, self.horizon_y - 5)));
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        self.lamps
+            .iter()
+            .map(|&x| (x + 2, self.horizon_y - 4))
+            .collect()
+    }
+
+    fn door_x(&self) -> u16 {
+        self.central_tower()
+            .map_or(self.width / 2, |tower| tower.middle())
+    }
+
+    /// Festive lights along the ground floor of the tower in the middle.
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        self.central_tower()
+            .map(|tower| (tower.x + 1, tower.x + tower.width - 2, self.horizon_y - 2))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Share of offices occupied, between the hourly values so windows change
+/// gradually.
+fn occupancy(time: NaiveDateTime) -> f32 {
+    let hour = time.hour() as usize;
+    let now = OFFICE_OCCUPANCY[hour];
+    let next = OFFICE_OCCUPANCY[(hour + 1) % 24];
+    let occupancy = now + (next - now) * time.minute() as f32 / 60.0;
+    match time.weekday() {
+        Weekday::Sat | Weekday::Sun => occupancy * WEEKEND_OCCUPANCY,
+        _ => occupancy,
+    }
+}
+
+/// Each window keeps its own threshold, so as occupancy falls the same
+/// offices empty first every evening.
+fn office_light(window_seed: u32, occupancy: f32, is_day: bool) -> WindowLight {
+    if is_day {
+        WindowLight::Daylight
+    } else if (scramble(window_seed) % 1000) as f32 / 1000.0 < occupancy {
+        WindowLight::Lit
+    } else {
+        WindowLight::Dark
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        // 2024-03-04 was a Monday
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn lit_windows(time: NaiveDateTime) -> usize {
+        (0..500)
+            .filter(|&w| office_light(w, occupancy(time), false) == WindowLight::Lit)
+            .count()
+    }
+
+    #[test]
+    fn test_offices_empty_through_the_evening() {
+        let working = lit_windows(at(4, 11));
+        let evening = lit_windows(at(4, 19));
+        let night = lit_windows(at(4, 2));
+        assert!(working > 400);
+        assert!(working > evening && evening > night);
+        assert!(night > 0, "some offices stay lit all night");
+        assert!(lit_windows(at(9, 11)) < working / 2, "Saturday is quiet");
+    }
+
+    #[test]
+    fn test_towers_fit_the_terminal() {
+        let mut city = City::new();
+        city.layout(100, 30);
+        assert!(!city.towers.is_empty());
+        for tower in &city.towers {
+            assert!(tower.x + tower.width <= 100);
+            assert!(tower.top(30) > ANTENNA_HEIGHT);
+        }
+    }
+}
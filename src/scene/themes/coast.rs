@@ -0,0 +1,226 @@
+use super::cabin::Cabin;
+use super::{Scene, SceneFrame, add_art_surface, draw_art};
+use crate::render::TerminalRenderer;
+use crate::scene::snow_cover::SnowCover;
+use crate::scene::{SceneGeometry, Season};
+use crossterm::style::Color;
+use std::io;
+
+const LIGHTHOUSE: [&str; 12] = [
+    "    ^    ",
+    "   /_\\   ",
+    "  |(*)|  ",
+    " ==|_|== ",
+    "   |#|   ",
+    "   | |   ",
+    "   |#|   ",
+    "   | |   ",
+    "   |#|   ",
+    "  /   \\  ",
+    " /_____\\ ",
+    "/^^^^^^^\\",
+];
+/// The lamp, as (column, row) in the lighthouse art.
+const LAMP: (u16, u16) = (4, 2);
+const HUT: [&str; 5] = [
+    "   |=|        ",
+    " __|_|______  ",
+    "/___________\\ ",
+    " |[] |  | []| ",
+    " |___|__|___| ",
+];
+const HUT_DOOR: u16 = 5;
+/// Rows of sea between the shore and the horizon line.
+const SEA_ROWS: u16 = 3;
+/// Longest reach of the beam across the sky, in columns.
+const BEAM_LENGTH: f32 = 30.0;
+
+/// A fisherman's hut on the shore, with a lighthouse on the rocks whose
+/// beam sweeps the sky after dark. Waves build with the wind.
+pub struct Coast {
+    hut: Cabin,
+    lighthouse_x: u16,
+    width: u16,
+    horizon_y: u16,
+}
+
+impl Coast {
+    pub fn new() -> Self {
+        Self {
+            hut: Cabin::new(
+                &HUT,
+                2,
+                HUT_DOOR,
+                Color::Rgb {
+                    r: 120,
+                    g: 140,
+                    b: 150,
+                },
+                Color::Rgb {
+                    r: 90,
+                    g: 70,
+                    b: 60,
+                },
+            ),
+            lighthouse_x: 0,
+            width: 0,
+            horizon_y: 0,
+        }
+    }
+
+    fn lighthouse_y(&self) -> u16 {
+        self.horizon_y.saturating_sub(LIGHTHOUSE.len() as u16)
+    }
+
+    fn lamp(&self) -> (u16, u16) {
+        (self.lighthouse_x + LAMP.0, self.lighthouse_y() + LAMP.1)
+    }
+
+    fn render_sea(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let beaufort = frame.wind.beaufort() as f32;
+        let swell = 1.0 + beaufort * 0.25;
+        let (water, crest) = if frame.is_day {
+            (Color::Blue, Color::Cyan)
+        } else {
+            (Color::DarkBlue, Color::DarkCyan)
+        };
+
+        for row in 0..SEA_ROWS {
+            let y = self.horizon_y.saturating_sub(SEA_ROWS - row);
+            for x in 0..self.width {
+                let phase = x as f32 * 0.35 + row as f32 * 1.7 - frame.elapsed * swell;
+                let wave = phase.sin() + (x as f32 * 0.13 + frame.elapsed * 0.4).sin() * 0.5;
+                let (ch, color) = if beaufort >= 5.0 && row + 1 == SEA_ROWS && wave > 1.2 {
+                    ('^', SnowCover::color(frame.is_day))
+                } else if wave > 0.8 {
+                    ('~', crest)
+                } else if wave > 0.0 {
+                    ('-', water)
+                } else {
+                    (' ', water)
+                };
+                renderer.render_char(x, y, ch, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The beam turns around the lamp; seen from the side it swings out to
+    /// one side, shortens as it points at the viewer and flashes.
+    fn render_beam(&self, renderer: &mut TerminalRenderer, elapsed: f32) -> io::Result<()> {
+        let (lamp_x, lamp_y) = self.lamp();
+        let angle = elapsed * 0.9;
+        let reach = angle.cos() * BEAM_LENGTH;
+        let facing_viewer = angle.sin() > 0.9;
+
+        let lamp = if facing_viewer {
+            Color::White
+        } else {
+            Color::Yellow
+        };
+        renderer.render_char(lamp_x, lamp_y, '*', lamp)?;
+
+        let step = reach.signum() as i32;
+        for i in 3..=reach.abs() as i32 {
+            let x = lamp_x as i32 + i * step;
+            if x < 0 {
+                break;
+            }
+            let fade = i as f32 / reach.abs();
+            let (ch, color) = if fade < 0.5 {
+                ('=', Color::Yellow)
+            } else {
+                ('-', Color::DarkYellow)
+            };
+            renderer.render_char(x as u16, lamp_y, ch, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Coast {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Coast {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        self.width = width;
+        self.horizon_y = horizon_y;
+        self.lighthouse_x = width / 4;
+        let hut_x = (width * 2 / 3).saturating_sub(self.hut.width() / 2);
+        self.hut.place(hut_x, horizon_y);
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        self.render_sea(renderer, frame)?;
+
+        let white = if frame.is_day {
+            Color::White
+        } else {
+            Color::Grey
+        };
+        let red = if frame.is_day {
+            Color::Red
+        } else {
+            Color::DarkRed
+        };
+        let rock = Color::DarkGrey;
+        draw_art(
+            renderer,
+            self.lighthouse_x as i32,
+            self.lighthouse_y(),
+            &LIGHTHOUSE,
+            |row, _, ch| match ch {
+                '#' => (ch, red),
+                '*' => (ch, Color::DarkYellow),
+                _ if row < 3 => (ch, red),
+                _ if row + 1 == LIGHTHOUSE.len() => (ch, rock),
+                _ => (ch, white),
+            },
+        )?;
+        if !frame.is_day {
+            self.render_beam(renderer, frame.elapsed)?;
+        }
+
+        self.hut.render(renderer, frame)
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, _season: Season) {
+        let lighthouse_y = self.lighthouse_y();
+        add_art_surface(
+            geometry.surface_mut(),
+            self.lighthouse_x,
+            lighthouse_y,
+            &LIGHTHOUSE,
+        );
+        geometry
+            .lightning_targets
+            .push((self.lighthouse_x + 4, lighthouse_y));
+        // Either end of the gallery
+        geometry.perches.extend([
+            (self.lighthouse_x + 1, lighthouse_y + 2),
+            (self.lighthouse_x + 7, lighthouse_y + 2),
+        ]);
+        self.hut.add_geometry(geometry);
+    }
+
+    fn chimney(&self) -> Option<(u16, u16)> {
+        self.hut.chimney()
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        let mut lights = self.hut.windows();
+        lights.push(self.lamp());
+        lights
+    }
+
+    fn door_x(&self) -> u16 {
+        self.hut.door_x()
+    }
+
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        vec![self.hut.light_string()]
+    }
+}
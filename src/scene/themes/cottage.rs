@@ -0,0 +1,163 @@
+use super::{Scene, SceneFrame};
+use crate::render::TerminalRenderer;
+use crate::scene::decorations::{DecorationRenderConfig, Decorations};
+use crate::scene::house::{House, Interior};
+use crate::scene::{SceneGeometry, Season};
+use std::io;
+
+/// The original scene: a house in the middle of its garden, with a tree
+/// and mailbox to the left and a fence, wind sock and pine to the right.
+pub struct Cottage {
+    house: House,
+    decorations: Decorations,
+    width: u16,
+    horizon_y: u16,
+}
+
+impl Cottage {
+    pub fn new() -> Self {
+        Self {
+            house: House,
+            decorations: Decorations::new(),
+            width: 0,
+            horizon_y: 0,
+        }
+    }
+
+    /// Top-left corner of the house, centered on the horizon.
+    fn house_origin(&self) -> (u16, u16) {
+        let house_x = (self.width / 2).saturating_sub(self.house.width() / 2);
+        let house_y = self.horizon_y.saturating_sub(self.house.height());
+        (house_x, house_y)
+    }
+
+    fn decoration_config(&self, frame: &SceneFrame) -> DecorationRenderConfig {
+        let (house_x, _) = self.house_origin();
+        DecorationRenderConfig {
+            horizon_y: self.horizon_y,
+            house_x,
+            house_width: self.house.width(),
+            width: self.width,
+            is_day: frame.is_day,
+            sway: frame.sway,
+            wind: frame.wind,
+            elapsed: frame.elapsed,
+            snow_level: frame.snow_level,
+            season: frame.season,
+        }
+    }
+
+    fn offset(&self, points: &[(u16, u16)]) -> Vec<(u16, u16)> {
+        let (house_x, house_y) = self.house_origin();
+        points
+            .iter()
+            .map(|&(dx, dy)| (house_x + dx, house_y + dy))
+            .filter(|&(x, _)| x < self.width)
+            .collect()
+    }
+}
+
+impl Default for Cottage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Cottage {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        self.width = width;
+        self.horizon_y = horizon_y;
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let (house_x, house_y) = self.house_origin();
+
+        let interior = Interior::new(
+            frame.local_time,
+            frame.is_day,
+            frame.fireplace_lit,
+            frame.elapsed,
+        );
+        self.house.render(
+            renderer,
+            house_x,
+            house_y,
+            frame.is_day,
+            frame.snow_level,
+            &interior,
+        )?;
+        self.house.render_weather_vane(
+            renderer,
+            house_x,
+            house_y,
+            frame.wind,
+            frame.elapsed,
+            frame.is_day,
+        )?;
+        if frame.snow_level == 0 {
+            self.house.render_ice(
+                renderer,
+                house_x,
+                house_y,
+                frame.icicle_length,
+                frame.is_day,
+            )?;
+        }
+
+        self.decorations
+            .render(renderer, &self.decoration_config(frame))
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, season: Season) {
+        let (house_x, house_y) = self.house_origin();
+        self.house
+            .add_surfaces(house_x, house_y, geometry.surface_mut());
+
+        // Only the layout matters here, which is the same by day and night
+        let decoration_config = DecorationRenderConfig {
+            horizon_y: self.horizon_y,
+            house_x,
+            house_width: self.house.width(),
+            width: self.width,
+            is_day: true,
+            sway: 0.0,
+            wind: Default::default(),
+            elapsed: 0.0,
+            snow_level: 0,
+            season,
+        };
+        self.decorations
+            .add_surfaces(&decoration_config, geometry.surface_mut());
+        geometry
+            .lightning_targets
+            .extend(self.decorations.tree_tops(&decoration_config));
+        geometry
+            .perches
+            .extend(self.decorations.perches(&decoration_config));
+        geometry.perches.extend(self.offset(&House::PERCHES));
+        geometry.eaves.extend(self.offset(&House::EAVES));
+    }
+
+    fn chimney(&self) -> Option<(u16, u16)> {
+        let (house_x, house_y) = self.house_origin();
+        Some((house_x + House::CHIMNEY_X_OFFSET, house_y))
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        let windows = House::WINDOWS.map(|(x, y, _)| (x, y));
+        self.offset(&windows)
+    }
+
+    fn door_x(&self) -> u16 {
+        let (house_x, _) = self.house_origin();
+        house_x + House::DOOR_X_OFFSET
+    }
+
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        let (house_x, house_y) = self.house_origin();
+        House::LIGHT_STRINGS
+            .iter()
+            .map(|&(first, last, row)| (house_x + first, house_x + last, house_y + row))
+            .collect()
+    }
+}
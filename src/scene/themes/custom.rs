@@ -0,0 +1,561 @@
+//! Landscapes described in a scene file: layers of ASCII art, each with an
+//! optional color mask, and anchor points telling the animations where the
+//! chimney, door, windows and perches are.
+//!
+//! ```toml
+//! ground_height = 7
+//! align = "center"
+//!
+//! [colors]
+//! b = { day = "#8c5a3c", night = "#46281e" }
+//! r = { day = "dark_red" }
+//!
+//! [[layers]]
+//! name = "office"
+//! art = '''
+//!  _________
+//! |  [] []  |
+//! |___| |___|
+//! '''
+//! mask = '''
+//!  rrrrrrrrr
+//! b         b
+//! bbbbbbbbbbb
+//! '''
+//!
+//! [anchors]
+//! door = 4
+//! windows = [[3, 1, 2], [6, 1, 2]]
+//! perches = [[1, 0], [9, 0]]
+//! ```
+//!
+//! Art shares one canvas whose bottom row stands on the horizon; anchors
+//! are (column, row) from its top-left corner.
+
+use super::{Scene, SceneFrame};
+use crate::error::ConfigError;
+use crate::render::TerminalRenderer;
+use crate::render::art::{ColorEntry, MaskedArt, Palette};
+use crate::scene::house::WindowLight;
+use crate::scene::snow_cover::SnowCover;
+use crate::scene::{SceneGeometry, Season, WorldScene};
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Deepest ground band a scene may ask for.
+const MAX_GROUND_HEIGHT: u16 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Align {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+/// When a layer is drawn, so signs can light up at night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Show {
+    #[default]
+    Always,
+    Day,
+    Night,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default = "default_ground_height")]
+    ground_height: u16,
+    #[serde(default)]
+    align: Align,
+    /// Columns to shift the canvas right of its alignment.
+    #[serde(default)]
+    offset: i32,
+    #[serde(default)]
+    colors: HashMap<String, ColorEntry>,
+    #[serde(default)]
+    layers: Vec<LayerFile>,
+    #[serde(default)]
+    anchors: Anchors,
+}
+
+fn default_ground_height() -> u16 {
+    WorldScene::GROUND_HEIGHT
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayerFile {
+    name: Option<String>,
+    art: String,
+    mask: Option<String>,
+    /// Whether rain and snow land on it. Layers shown only by day or by
+    /// night never do, so the ground doesn't shift when they appear.
+    #[serde(default = "default_solid")]
+    solid: bool,
+    #[serde(default)]
+    show: Show,
+}
+
+fn default_solid() -> bool {
+    true
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Anchors {
+    /// Top of the chimney, where smoke rises from.
+    chimney: Option<[u16; 2]>,
+    /// Column of the front door.
+    door: Option<u16>,
+    /// As (column of the left edge, row, width); the first is by the hearth.
+    #[serde(default)]
+    windows: Vec<[u16; 3]>,
+    #[serde(default)]
+    perches: Vec<[u16; 2]>,
+    /// Roof edges that drip in the rain.
+    #[serde(default)]
+    eaves: Vec<[u16; 2]>,
+    /// Lamps that glow in fog, besides the windows.
+    #[serde(default)]
+    lights: Vec<[u16; 2]>,
+    /// As (first column, last column, row).
+    #[serde(default)]
+    light_strings: Vec<[u16; 3]>,
+    /// Tall points lightning favours; defaults to the highest solid cell.
+    #[serde(default)]
+    lightning: Vec<[u16; 2]>,
+}
+
+struct Layer {
+    art: MaskedArt,
+    solid: bool,
+    show: Show,
+}
+
+/// A scene loaded from a file in the config directory.
+pub struct CustomScene {
+    layers: Vec<Layer>,
+    palette: Palette,
+    anchors: Anchors,
+    ground_height: u16,
+    align: Align,
+    offset: i32,
+    canvas_width: u16,
+    canvas_height: u16,
+    /// Top-left corner of the canvas on screen, which may be off screen.
+    origin: (i32, i32),
+}
+
+impl CustomScene {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let source = fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::parse(&source).map_err(|reason| ConfigError::InvalidScene {
+            path: path.display().to_string(),
+            reason,
+        })
+    }
+
+    /// Parses and validates a scene file, describing the first problem
+    /// found.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let file: SceneFile = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        if file.ground_height == 0 || file.ground_height > MAX_GROUND_HEIGHT {
+            return Err(format!(
+                "ground_height must be between 1 and {MAX_GROUND_HEIGHT}, got {}",
+                file.ground_height
+            ));
+        }
+
+        let palette = Palette::parse(&file.colors)?;
+
+        if file.layers.is_empty() {
+            return Err("no layers; add at least one [[layers]] table with art".to_string());
+        }
+        let mut layers = Vec::new();
+        for (i, layer) in file.layers.iter().enumerate() {
+            let name = match &layer.name {
+                Some(name) => format!("layer \"{name}\""),
+                None => format!("layer {}", i + 1),
+            };
+            layers.push(Layer {
+                art: MaskedArt::parse(&name, &layer.art, layer.mask.as_deref(), &palette)?,
+                solid: layer.solid,
+                show: layer.show,
+            });
+        }
+
+        let canvas_width = layers.iter().map(|l| l.art.width()).max().unwrap_or(0);
+        let canvas_height = layers.iter().map(|l| l.art.height()).max().unwrap_or(0);
+
+        let scene = Self {
+            layers,
+            palette,
+            anchors: file.anchors,
+            ground_height: file.ground_height,
+            align: file.align,
+            offset: file.offset,
+            canvas_width,
+            canvas_height,
+            origin: (0, 0),
+        };
+        scene.check_anchors()?;
+        Ok(scene)
+    }
+
+    fn check_anchors(&self) -> Result<(), String> {
+        let anchors = &self.anchors;
+        let mut points = Vec::new();
+        points.extend(anchors.chimney.map(|[x, y]| ("chimney", x, y)));
+        points.extend(anchors.door.map(|x| ("door", x, 0)));
+        for &[x, y, width] in &anchors.windows {
+            let Some(last) = width.checked_sub(1) else {
+                return Err(format!("window at ({x}, {y}) has no width"));
+            };
+            let Some(right) = x.checked_add(last) else {
+                return Err(format!(
+                    "window at ({x}, {y}) is {width} columns wide, past any art"
+                ));
+            };
+            points.push(("window", x, y));
+            points.push(("window", right, y));
+        }
+        for &[first, last, y] in &anchors.light_strings {
+            if last < first {
+                return Err(format!(
+                    "light string on row {y} ends (column {last}) before it starts (column {first})"
+                ));
+            }
+            points.push(("light string", first, y));
+            points.push(("light string", last, y));
+        }
+        points.extend(anchors.perches.iter().map(|&[x, y]| ("perch", x, y)));
+        points.extend(anchors.eaves.iter().map(|&[x, y]| ("eave", x, y)));
+        points.extend(anchors.lights.iter().map(|&[x, y]| ("light", x, y)));
+        points.extend(anchors.lightning.iter().map(|&[x, y]| ("lightning", x, y)));
+
+        match points
+            .iter()
+            .find(|&&(_, x, y)| x >= self.canvas_width || y >= self.canvas_height)
+        {
+            Some((what, x, y)) => Err(format!(
+                "{what} at ({x}, {y}) is outside the {}x{} art",
+                self.canvas_width, self.canvas_height
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn to_screen(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let x = u16::try_from(self.origin.0 + x as i32).ok()?;
+        let y = u16::try_from(self.origin.1 + y as i32).ok()?;
+        Some((x, y))
+    }
+
+    fn points(&self, points: &[[u16; 2]]) -> Vec<(u16, u16)> {
+        points
+            .iter()
+            .filter_map(|&[x, y]| self.to_screen(x, y))
+            .collect()
+    }
+
+    fn window_at(&self, col: u16, row: u16) -> Option<usize> {
+        self.anchors.windows.iter().position(|&[x, y, width]| {
+            y == row
+                && x.checked_add(width)
+                    .is_some_and(|end| (x..end).contains(&col))
+        })
+    }
+
+    /// Topmost solid cell per canvas column, where snow settles. Only
+    /// layers that are always shown count.
+    fn roofline(&self) -> Vec<Option<u16>> {
+        (0..self.canvas_width as usize)
+            .map(|col| {
+                self.layers
+                    .iter()
+                    .filter(|layer| layer.solid && layer.show == Show::Always)
+                    .filter_map(|layer| {
+                        layer
+                            .art
+                            .lines
+                            .iter()
+                            .position(|line| line.get(col).is_some_and(|&ch| ch != ' '))
+                    })
+                    .min()
+                    .map(|row| row as u16)
+            })
+            .collect()
+    }
+}
+
+impl Scene for CustomScene {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        let canvas_width = self.canvas_width as i32;
+        let x = match self.align {
+            Align::Left => 0,
+            Align::Center => (width as i32 - canvas_width) / 2,
+            Align::Right => width as i32 - canvas_width,
+        };
+        self.origin = (
+            x + self.offset,
+            horizon_y as i32 - self.canvas_height as i32,
+        );
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let roofline = self.roofline();
+        let default_color = if frame.is_day {
+            Color::White
+        } else {
+            Color::Grey
+        };
+
+        for layer in &self.layers {
+            let visible = match layer.show {
+                Show::Always => true,
+                Show::Day => frame.is_day,
+                Show::Night => !frame.is_day,
+            };
+            if !visible {
+                continue;
+            }
+
+            for (row, line) in layer.art.lines.iter().enumerate() {
+                for (col, &ch) in line.iter().enumerate() {
+                    let (col, row) = (col as u16, row as u16);
+                    let Some((x, y)) = self.to_screen(col, row) else {
+                        continue;
+                    };
+                    if ch == ' ' {
+                        continue;
+                    }
+
+                    let color = if let Some(room) = self.window_at(col, row) {
+                        let light = match WindowLight::at(room, frame.local_time, frame.is_day) {
+                            WindowLight::Dark => WindowLight::Dark,
+                            _ if room == 0 && frame.fireplace_lit => WindowLight::Firelight,
+                            light => light,
+                        };
+                        light.color(frame.elapsed)
+                    } else if layer.solid
+                        && frame.snow_level > 0
+                        && roofline[col as usize] == Some(row)
+                    {
+                        SnowCover::color(frame.is_day)
+                    } else {
+                        layer
+                            .art
+                            .color(row as usize, col as usize, &self.palette, frame.is_day)
+                            .unwrap_or(default_color)
+                    };
+                    renderer.render_char(x, y, ch, color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, _season: Season) {
+        let roofline = self.roofline();
+        for (col, top) in roofline.iter().enumerate() {
+            if let Some((x, y)) = top.and_then(|top| self.to_screen(col as u16, top)) {
+                let surface = geometry.surface_mut();
+                if let Some(cell) = surface.get_mut(x as usize) {
+                    *cell = (*cell).min(y);
+                }
+            }
+        }
+
+        geometry.eaves.extend(self.points(&self.anchors.eaves));
+        geometry.perches.extend(self.points(&self.anchors.perches));
+        if self.anchors.lightning.is_empty() {
+            let highest = roofline
+                .iter()
+                .enumerate()
+                .filter_map(|(col, top)| Some((col as u16, (*top)?)))
+                .min_by_key(|&(_, top)| top);
+            geometry
+                .lightning_targets
+                .extend(highest.and_then(|(col, top)| self.to_screen(col, top)));
+        } else {
+            geometry
+                .lightning_targets
+                .extend(self.points(&self.anchors.lightning));
+        }
+    }
+
+    fn chimney(&self) -> Option<(u16, u16)> {
+        let [x, y] = self.anchors.chimney?;
+        self.to_screen(x, y)
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        let mut lights = self.points(&self.anchors.lights);
+        lights.extend(
+            self.anchors
+                .windows
+                .iter()
+                .filter_map(|&[x, y, _]| self.to_screen(x, y)),
+        );
+        lights
+    }
+
+    fn door_x(&self) -> u16 {
+        let door = self.anchors.door.unwrap_or(self.canvas_width / 2);
+        self.to_screen(door, 0).map_or(0, |(x, _)| x)
+    }
+
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        self.anchors
+            .light_strings
+            .iter()
+            .filter_map(|&[first, last, row]| {
+                let (first, y) = self.to_screen(first, row)?;
+                let (last, _) = self.to_screen(last, row)?;
+                Some((first, last, y))
+            })
+            .collect()
+    }
+
+    fn ground_height(&self) -> u16 {
+        self.ground_height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFICE: &str = r##"
+ground_height = 5
+
+[colors]
+b = { day = "#8c5a3c", night = "#46281e" }
+r = { day = "dark_red" }
+
+[[layers]]
+name = "office"
+art = '''
+ _________
+|  [] []  |
+|___| |___|
+'''
+mask = '''
+ rrrrrrrrr
+b         b
+bbbbbbbbbbb
+'''
+
+[[layers]]
+name = "sign"
+solid = false
+show = "night"
+art = '''
+    ~~~
+'''
+
+[anchors]
+chimney = [2, 0]
+door = 4
+windows = [[3, 1, 2], [6, 1, 2]]
+perches = [[1, 0], [9, 0]]
+"##;
+
+    fn error(source: &str) -> String {
+        CustomScene::parse(source)
+            .err()
+            .expect("scene should be rejected")
+    }
+
+    #[test]
+    fn test_custom_scene_stands_on_the_horizon() {
+        let mut scene = CustomScene::parse(OFFICE).unwrap();
+        scene.layout(41, 30);
+
+        assert_eq!(scene.ground_height(), 5);
+        assert_eq!((scene.canvas_width, scene.canvas_height), (11, 3));
+        assert_eq!(scene.origin, (15, 27));
+        assert_eq!(scene.chimney(), Some((17, 27)));
+        assert_eq!(scene.door_x(), 19);
+        assert_eq!(scene.lights(), [(18, 28), (21, 28)]);
+
+        let mut geometry = SceneGeometry::new(41, 30, 5);
+        scene.add_geometry(&mut geometry, Season::Summer);
+        assert_eq!(geometry.surface_at(15), 28);
+        assert_eq!(geometry.surface_at(16), 27);
+        assert!(geometry.is_open_ground(14));
+        assert_eq!(geometry.perches, [(16, 27), (24, 27)]);
+        assert_eq!(geometry.lightning_targets, [(16, 27)]);
+    }
+
+    #[test]
+    fn test_custom_scene_part_time_layers_are_not_solid() {
+        let source = r#"
+[[layers]]
+name = "wall"
+art = '''
+
+==
+'''
+
+[[layers]]
+name = "lantern"
+show = "night"
+art = '''
+##
+'''
+"#;
+        let scene = CustomScene::parse(source).unwrap();
+        assert_eq!(scene.roofline(), [Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn test_custom_scene_errors_say_what_is_wrong() {
+        assert!(error("").contains("no layers"));
+        assert!(error("ground_height = 0\n[[layers]]\nart = 'x'").contains("ground_height"));
+        assert!(error("[[layers]]\nart = 'x'\nmask = 'q'").contains("'q'"));
+        assert!(error("[[layers]]\nart = 'x'\nmask = 'qq'").contains("longer than the art"));
+        assert!(error("[[layers]]\nart = 'x'\nmask = '''\nq\nq\n'''").contains("2 lines"));
+        assert!(error("[[layers]]\nart = \"\\tx\"").contains("tabs"));
+
+        let bad_color = "[colors]\nq = { day = \"blurple\" }\n[[layers]]\nart = 'x'";
+        assert!(error(bad_color).contains("blurple"));
+
+        let outside = "[[layers]]\nart = 'xx'\n[anchors]\nchimney = [5, 0]";
+        assert!(error(outside).contains("chimney at (5, 0) is outside the 2x1 art"));
+
+        let typo = "[[layers]]\nart = 'x'\n[anchors]\nchimny = [0, 0]";
+        assert!(error(typo).contains("chimny"));
+    }
+
+    #[test]
+    fn test_custom_scene_rejects_windows_off_the_art() {
+        let window = |anchor: &str| {
+            error(&format!(
+                "[[layers]]\nart = 'xx'\n[anchors]\nwindows = [{anchor}]"
+            ))
+        };
+
+        assert!(window("[0, 0, 0]").contains("window at (0, 0) has no width"));
+        assert!(window("[1, 0, 2]").contains("window at (2, 0) is outside the 2x1 art"));
+        assert!(window("[65535, 0, 2]").contains("is 2 columns wide, past any art"));
+        assert!(window("[2, 0, 65535]").contains("past any art"));
+
+        let scene =
+            CustomScene::parse("[[layers]]\nart = 'xx'\n[anchors]\nwindows = [[0, 0, 2]]").unwrap();
+        assert_eq!(scene.window_at(1, 0), Some(0));
+        assert_eq!(scene.window_at(2, 0), None);
+    }
+}
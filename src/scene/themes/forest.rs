@@ -0,0 +1,153 @@
+use super::cabin::Cabin;
+use super::pine::Pine;
+use super::{Scene, SceneFrame, scramble};
+use crate::render::TerminalRenderer;
+use crate::scene::{SceneGeometry, Season};
+use crossterm::style::Color;
+use std::io;
+
+const CABIN: [&str; 7] = [
+    "     |=|              ",
+    "   __|_|___________   ",
+    "  /                \\  ",
+    " /__________________\\ ",
+    "  |=[]=| |  | |=[]=|  ",
+    "  |====| | o| |====|  ",
+    "  |====|_|__|_|====|  ",
+];
+const CABIN_DOOR: u16 = 9;
+/// Gap kept clear of trees either side of the cabin.
+const CLEARING: u16 = 4;
+
+/// A log cabin in a clearing among the pines, with a lantern by the door.
+pub struct Forest {
+    cabin: Cabin,
+    pines: Vec<Pine>,
+    width: u16,
+    horizon_y: u16,
+}
+
+impl Forest {
+    pub fn new() -> Self {
+        Self {
+            cabin: Cabin::new(
+                &CABIN,
+                3,
+                CABIN_DOOR,
+                Color::Rgb {
+                    r: 140,
+                    g: 90,
+                    b: 50,
+                },
+                Color::Rgb {
+                    r: 90,
+                    g: 70,
+                    b: 50,
+                },
+            ),
+            pines: Vec::new(),
+            width: 0,
+            horizon_y: 0,
+        }
+    }
+
+    fn lantern(&self) -> (u16, u16) {
+        (self.cabin.door_x() - 1, self.horizon_y.saturating_sub(3))
+    }
+}
+
+impl Default for Forest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Forest {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        self.width = width;
+        self.horizon_y = horizon_y;
+
+        let cabin_x = (width / 2).saturating_sub(self.cabin.width() / 2);
+        self.cabin.place(cabin_x, horizon_y);
+
+        // Trees of mixed heights packed close together, kept out of the
+        // clearing and no taller than the sky allows
+        let clearing = cabin_x.saturating_sub(CLEARING)..cabin_x + self.cabin.width() + CLEARING;
+        let tallest = (horizon_y / 2).clamp(2, 9) as u32;
+        self.pines.clear();
+        let mut x = 1;
+        for i in 0u32.. {
+            let tiers = 2 + (scramble(i) % (tallest - 1)) as u16;
+            let pine = Pine::new(x, tiers);
+            if x + pine.width() >= width {
+                break;
+            }
+            if clearing.contains(&x) || clearing.contains(&(x + pine.width())) {
+                x += 1;
+                continue;
+            }
+            self.pines.push(pine);
+            x += pine.width() + (scramble(i + 101) % 3) as u16;
+        }
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let shades = if frame.is_day {
+            [
+                Color::DarkGreen,
+                Color::Rgb {
+                    r: 0,
+                    g: 100,
+                    b: 40,
+                },
+            ]
+        } else {
+            [
+                Color::Rgb { r: 0, g: 50, b: 0 },
+                Color::Rgb { r: 0, g: 40, b: 20 },
+            ]
+        };
+        for (i, pine) in self.pines.iter().enumerate() {
+            pine.render(renderer, self.horizon_y, frame, shades[i % 2])?;
+        }
+        self.cabin.render(renderer, frame)?;
+
+        let (x, y) = self.lantern();
+        let lantern = if frame.is_day {
+            Color::DarkGrey
+        } else if (frame.elapsed * 5.0).sin() > -0.8 {
+            Color::Yellow
+        } else {
+            Color::DarkYellow
+        };
+        renderer.render_char(x, y, 'o', lantern)
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, _season: Season) {
+        self.cabin.add_geometry(geometry);
+        for pine in &self.pines {
+            pine.add_surface(geometry.surface_mut(), self.horizon_y);
+            let (x, y) = pine.top(self.horizon_y);
+            geometry.lightning_targets.push((x, y));
+            geometry.perches.push((x, y.saturating_sub(1)));
+        }
+    }
+
+    fn chimney(&self) -> Option<(u16, u16)> {
+        self.cabin.chimney()
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        let mut lights = self.cabin.windows();
+        lights.push(self.lantern());
+        lights
+    }
+
+    fn door_x(&self) -> u16 {
+        self.cabin.door_x()
+    }
+
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        vec![self.cabin.light_string()]
+    }
+}
@@ -0,0 +1,212 @@
+//! Landscapes the weather plays over. Each theme stands its landmarks on the
+//! horizon and tells the animations where they are: what is solid, where
+//! smoke rises and which lights glow after dark.
+
+mod cabin;
+pub mod city;
+pub mod coast;
+pub mod cottage;
+pub mod custom;
+pub mod forest;
+pub mod mountains;
+mod pine;
+
+use crate::animation::Wind;
+use crate::render::TerminalRenderer;
+use crate::scene::{SceneGeometry, Season, WorldScene};
+use chrono::NaiveDateTime;
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SceneTheme {
+    /// The house with its garden, tree and fence.
+    #[default]
+    Cottage,
+    /// Office towers with their windows lit into the evening.
+    City,
+    /// A chalet in an alpine valley below a seasonal snow line.
+    Mountains,
+    /// A lighthouse and a fisherman's hut by the sea.
+    Coast,
+    /// A log cabin among the pines.
+    Forest,
+}
+
+impl SceneTheme {
+    pub fn build(self) -> Box<dyn Scene> {
+        match self {
+            SceneTheme::Cottage => Box::new(cottage::Cottage::new()),
+            SceneTheme::City => Box::new(city::City::new()),
+            SceneTheme::Mountains => Box::new(mountains::Mountains::new()),
+            SceneTheme::Coast => Box::new(coast::Coast::new()),
+            SceneTheme::Forest => Box::new(forest::Forest::new()),
+        }
+    }
+}
+
+/// What a landscape needs to know to draw one frame.
+pub struct SceneFrame {
+    pub is_day: bool,
+    /// Rows of snow lying on roofs and branches.
+    pub snow_level: u8,
+    pub season: Season,
+    pub wind: Wind,
+    /// Horizontal offset of tree crowns in cells.
+    pub sway: f32,
+    /// Wall-clock time at the location, for lit windows.
+    pub local_time: NaiveDateTime,
+    pub fireplace_lit: bool,
+    /// Seconds since the scene started, for flicker and motion.
+    pub elapsed: f32,
+    /// Rows of icicles hanging from the eaves.
+    pub icicle_length: u8,
+}
+
+/// A landscape standing on the ground band. Coordinates are absolute
+/// terminal cells, fixed by the last call to [`Scene::layout`].
+pub trait Scene {
+    /// Places the landmarks for a terminal `width` cells wide whose ground
+    /// starts at row `horizon_y`.
+    fn layout(&mut self, width: u16, horizon_y: u16);
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()>;
+
+    /// Records solid cells, eaves, lightning targets and perches.
+    /// Deciduous trees lose their leaves with the `season`.
+    fn add_geometry(&self, geometry: &mut SceneGeometry, season: Season);
+
+    /// Top of the chimney smoke rises from, if there is a fireplace.
+    fn chimney(&self) -> Option<(u16, u16)> {
+        None
+    }
+
+    /// Lamps and windows that glow through fog after dark.
+    fn lights(&self) -> Vec<(u16, u16)> {
+        Vec::new()
+    }
+
+    /// Column of the front door; seasonal decorations stand either side.
+    fn door_x(&self) -> u16;
+
+    /// Runs holiday lights hang along, as (first column, last column, row).
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        Vec::new()
+    }
+
+    /// Rows of ground below the horizon.
+    fn ground_height(&self) -> u16 {
+        WorldScene::GROUND_HEIGHT
+    }
+}
+
+/// Draws `lines` with their top-left corner at (`x`, `y`). Spaces are
+/// transparent; `paint` picks the character and color of every other cell
+/// from its row, column and character.
+fn draw_art<S: AsRef<str>>(
+    renderer: &mut TerminalRenderer,
+    x: i32,
+    y: u16,
+    lines: &[S],
+    mut paint: impl FnMut(usize, usize, char) -> (char, Color),
+) -> io::Result<()> {
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.as_ref().chars().enumerate() {
+            let cx = x + col as i32;
+            if ch == ' ' || cx < 0 {
+                continue;
+            }
+            let (ch, color) = paint(row, col, ch);
+            renderer.render_char(cx as u16, y + row as u16, ch, color)?;
+        }
+    }
+    Ok(())
+}
+
+/// Lowers `surface` (topmost solid row per column) to the outline of
+/// `lines` drawn at (`x`, `y`).
+fn add_art_surface<S: AsRef<str>>(surface: &mut [u16], x: u16, y: u16, lines: &[S]) {
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.as_ref().chars().enumerate() {
+            let col = (x + col as u16) as usize;
+            if ch != ' ' && col < surface.len() {
+                surface[col] = surface[col].min(y + row as u16);
+            }
+        }
+    }
+}
+
+/// Dims a daytime color for the night.
+fn night_shade(color: Color, is_day: bool) -> Color {
+    match color {
+        Color::Rgb { r, g, b } if !is_day => Color::Rgb {
+            r: r / 2,
+            g: g / 2,
+            b: b / 2,
+        },
+        color => color,
+    }
+}
+
+/// Cheap deterministic scramble, so layouts and lit windows stay put from
+/// frame to frame.
+fn scramble(n: u32) -> u32 {
+    let mut n = n.wrapping_mul(0x9E37_79B1) ^ 0x5bd1_e995;
+    n ^= n >> 15;
+    n = n.wrapping_mul(0x2c1b_3c6d);
+    n ^ (n >> 13)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry_for(theme: SceneTheme, width: u16, height: u16) -> SceneGeometry {
+        let horizon_y = height - 7;
+        let mut scene = theme.build();
+        scene.layout(width, horizon_y);
+        let mut geometry = SceneGeometry::new(width, horizon_y, 7);
+        scene.add_geometry(&mut geometry, Season::Summer);
+        geometry
+    }
+
+    #[test]
+    fn test_every_theme_stands_on_the_horizon() {
+        for theme in [
+            SceneTheme::Cottage,
+            SceneTheme::City,
+            SceneTheme::Mountains,
+            SceneTheme::Coast,
+            SceneTheme::Forest,
+        ] {
+            let geometry = geometry_for(theme, 160, 45);
+            let solid = (0..160).filter(|&x| !geometry.is_open_ground(x)).count();
+            assert!(solid > 10, "{theme:?} has nothing solid");
+            assert!(!geometry.lightning_targets.is_empty(), "{theme:?}");
+            assert!(!geometry.perches.is_empty(), "{theme:?}");
+            assert!(
+                geometry.perches.iter().all(|&(x, y)| x < 160 && y < 38),
+                "{theme:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_themes_fit_narrow_terminals() {
+        for theme in [
+            SceneTheme::Cottage,
+            SceneTheme::City,
+            SceneTheme::Mountains,
+            SceneTheme::Coast,
+            SceneTheme::Forest,
+        ] {
+            let mut scene = theme.build();
+            scene.layout(20, 5);
+            let mut geometry = SceneGeometry::new(20, 5, 7);
+            scene.add_geometry(&mut geometry, Season::Winter);
+            assert!(scene.door_x() < 40, "{theme:?}");
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use super::cabin::Cabin;
+use super::pine::Pine;
+use super::{Scene, SceneFrame, night_shade, scramble};
+use crate::render::TerminalRenderer;
+use crate::scene::snow_cover::SnowCover;
+use crate::scene::{SceneGeometry, Season};
+use crossterm::style::Color;
+use std::cmp::Ordering;
+use std::io;
+use std::ops::Range;
+
+const CHALET: [&str; 6] = [
+    "      |=|       ",
+    "  ____|_|_____  ",
+    " /            \\ ",
+    "/______________\\",
+    " |[] |    | []| ",
+    " |___|____|___| ",
+];
+const CHALET_DOOR: u16 = 6;
+
+/// A chalet in a valley below a range of peaks. The snow line creeps down
+/// the slopes in winter and retreats to the summits by summer.
+pub struct Mountains {
+    chalet: Cabin,
+    pines: Vec<Pine>,
+    /// Peak heights above the horizon per column, far range first.
+    ranges: [Vec<u16>; 2],
+    width: u16,
+    horizon_y: u16,
+}
+
+impl Mountains {
+    pub fn new() -> Self {
+        Self {
+            chalet: Cabin::new(
+                &CHALET,
+                3,
+                CHALET_DOOR,
+                Color::Rgb {
+                    r: 150,
+                    g: 100,
+                    b: 60,
+                },
+                Color::Rgb {
+                    r: 120,
+                    g: 60,
+                    b: 40,
+                },
+            ),
+            pines: Vec::new(),
+            ranges: [Vec::new(), Vec::new()],
+            width: 0,
+            horizon_y: 0,
+        }
+    }
+
+    /// Row above which the far range is white.
+    fn snow_line(&self, season: Season, snow_level: u8) -> u16 {
+        let share = match season {
+            _ if snow_level > 0 => 0.0,
+            Season::Winter => 0.15,
+            Season::Spring => 0.5,
+            Season::Summer => 0.8,
+            Season::Autumn => 0.6,
+        };
+        let peak = self.ranges[0].iter().copied().max().unwrap_or(0);
+        self.horizon_y.saturating_sub((peak as f32 * share) as u16)
+    }
+
+    fn render_range(
+        &self,
+        renderer: &mut TerminalRenderer,
+        heights: &[u16],
+        rock: Color,
+        snow_line: u16,
+        snow: Color,
+    ) -> io::Result<()> {
+        for x in 0..heights.len() {
+            let left = heights[x.saturating_sub(1)];
+            let right = heights[(x + 1).min(heights.len() - 1)];
+            // Twin summit columns make a peak; longer flats are shoulders
+            let summit = |other: Option<&u16>| other.is_some_and(|&h| h < heights[x]);
+            let ch = match (left.cmp(&heights[x]), right.cmp(&heights[x])) {
+                (Ordering::Less, Ordering::Less) => '^',
+                (_, Ordering::Greater) => '/',
+                (Ordering::Greater, _) => '\\',
+                (Ordering::Less, Ordering::Equal) if summit(heights.get(x + 2)) => '/',
+                (Ordering::Equal, Ordering::Less)
+                    if summit(x.checked_sub(2).map(|i| &heights[i])) =>
+                {
+                    '\\'
+                }
+                _ => '_',
+            };
+            if heights[x] == 0 {
+                continue;
+            }
+
+            // Steep slopes run down to the lower neighbour's ridge
+            let ridge = self.horizon_y.saturating_sub(heights[x]);
+            let foot = self
+                .horizon_y
+                .saturating_sub(left.min(right))
+                .max(ridge + 1);
+            for y in ridge..self.horizon_y {
+                let color = if y < snow_line { snow } else { rock };
+                // The body is drawn solid to hide the sky behind it
+                let ch = if y < foot { ch } else { ' ' };
+                renderer.render_char(x as u16, y, ch, color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Summits as (column, height) spread across the width, with the given
+/// ranges of height and spacing.
+fn peaks(width: u16, heights: Range<u16>, spacing: Range<u16>, seed: u32) -> Vec<(u16, u16)> {
+    let mut peaks = Vec::new();
+    let mut x = (scramble(seed) % spacing.start as u32) as u16;
+    for i in 0u32.. {
+        if x >= width + spacing.end {
+            break;
+        }
+        let roll = scramble(seed * 1000 + i);
+        let height = heights.start + (roll % heights.len().max(1) as u32) as u16;
+        peaks.push((x, height));
+        x += spacing.start + (roll >> 16) as u16 % spacing.len().max(1) as u16;
+    }
+    peaks
+}
+
+/// Height of the skyline in each column. Slopes fall one row every `run`
+/// columns from twin summit columns, so peaks come out as `/\`.
+fn ridge(width: u16, peaks: &[(u16, u16)], run: u16) -> Vec<u16> {
+    (0..width)
+        .map(|x| {
+            peaks
+                .iter()
+                .map(|&(peak_x, height)| {
+                    let distance = if x <= peak_x {
+                        peak_x - x
+                    } else {
+                        x - peak_x - 1
+                    };
+                    height.saturating_sub(distance / run)
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+impl Default for Mountains {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Mountains {
+    fn layout(&mut self, width: u16, horizon_y: u16) {
+        self.width = width;
+        self.horizon_y = horizon_y;
+
+        // Steep peaks behind rolling foothills
+        let tallest = horizon_y * 3 / 5;
+        self.ranges = [
+            ridge(width, &peaks(width, tallest / 2..tallest, 12..28, 1), 1),
+            ridge(width, &peaks(width, 2..tallest / 4 + 3, 20..40, 2), 3),
+        ];
+
+        let chalet_x = (width * 3 / 5).saturating_sub(self.chalet.width() / 2);
+        self.chalet.place(chalet_x, horizon_y);
+
+        let chalet = chalet_x..chalet_x + self.chalet.width();
+        self.pines = [8u32, 20, 27, 70, 84, 91, 118, 126, 140]
+            .iter()
+            .enumerate()
+            .map(|(i, &share)| {
+                let x = (width as u32 * share / 150) as u16;
+                Pine::new(x, 3 + i as u16 % 3)
+            })
+            .filter(|pine| {
+                pine.x + pine.width() < width
+                    && !chalet.contains(&pine.x)
+                    && !chalet.contains(&(pine.x + pine.width()))
+            })
+            .collect();
+    }
+
+    fn render(&self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let snow = SnowCover::color(frame.is_day);
+        let far = if frame.is_day {
+            Color::Rgb {
+                r: 120,
+                g: 120,
+                b: 140,
+            }
+        } else {
+            Color::Rgb {
+                r: 60,
+                g: 60,
+                b: 80,
+            }
+        };
+        let near = night_shade(
+            Color::Rgb {
+                r: 70,
+                g: 110,
+                b: 60,
+            },
+            frame.is_day,
+        );
+        // The foothills only turn white when snow lies in the valley too
+        let near_snow_line = if frame.snow_level > 0 {
+            self.horizon_y
+        } else {
+            0
+        };
+
+        let snow_line = self.snow_line(frame.season, frame.snow_level);
+        self.render_range(renderer, &self.ranges[0], far, snow_line, snow)?;
+        self.render_range(renderer, &self.ranges[1], near, near_snow_line, snow)?;
+
+        let pine_color = if frame.is_day {
+            Color::DarkGreen
+        } else {
+            Color::Rgb { r: 0, g: 50, b: 0 }
+        };
+        for pine in &self.pines {
+            pine.render(renderer, self.horizon_y, frame, pine_color)?;
+        }
+        self.chalet.render(renderer, frame)
+    }
+
+    fn add_geometry(&self, geometry: &mut SceneGeometry, _season: Season) {
+        self.chalet.add_geometry(geometry);
+        for pine in &self.pines {
+            pine.add_surface(geometry.surface_mut(), self.horizon_y);
+            let (x, y) = pine.top(self.horizon_y);
+            geometry.lightning_targets.push((x, y));
+            geometry.perches.push((x, y.saturating_sub(1)));
+        }
+    }
+
+    fn chimney(&self) -> Option<(u16, u16)> {
+        self.chalet.chimney()
+    }
+
+    fn lights(&self) -> Vec<(u16, u16)> {
+        self.chalet.windows()
+    }
+
+    fn door_x(&self) -> u16 {
+        self.chalet.door_x()
+    }
+
+    fn light_strings(&self) -> Vec<(u16, u16, u16)> {
+        vec![self.chalet.light_string()]
+    }
+}
@@ -0,0 +1,120 @@
+use super::{SceneFrame, add_art_surface};
+use crate::render::TerminalRenderer;
+use crate::scene::snow_cover::SnowCover;
+use crossterm::style::Color;
+use std::io;
+
+/// A conifer standing on the horizon, evergreen through the seasons.
+#[derive(Debug, Clone, Copy)]
+pub struct Pine {
+    /// Left edge of the lowest branches.
+    pub x: u16,
+    /// Rows of crown above a one-row trunk.
+    pub tiers: u16,
+}
+
+impl Pine {
+    pub fn new(x: u16, tiers: u16) -> Self {
+        Self {
+            x,
+            tiers: tiers.max(2),
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.tiers * 2
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let tiers = self.tiers as usize;
+        let mut lines = (0..tiers)
+            .map(|i| {
+                let fill = if i + 1 == tiers { "_" } else { " " };
+                format!(
+                    "{}/{}\\{}",
+                    " ".repeat(tiers - 1 - i),
+                    fill.repeat(i * 2),
+                    " ".repeat(tiers - 1 - i)
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("{}||", " ".repeat(tiers - 1)));
+        lines
+    }
+
+    /// The tip, as (column, row).
+    pub fn top(&self, horizon_y: u16) -> (u16, u16) {
+        (
+            self.x + self.tiers - 1,
+            horizon_y.saturating_sub(self.tiers + 1),
+        )
+    }
+
+    pub fn add_surface(&self, surface: &mut [u16], horizon_y: u16) {
+        let (_, top) = self.top(horizon_y);
+        add_art_surface(surface, self.x, top, &self.lines());
+    }
+
+    /// Draws the pine with its crown leaning by the frame's sway, the tip the
+    /// most, and snow on the upper branches.
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        horizon_y: u16,
+        frame: &SceneFrame,
+        color: Color,
+    ) -> io::Result<()> {
+        let (_, top) = self.top(horizon_y);
+        let tiers = self.tiers as usize;
+        let trunk_color = if frame.is_day {
+            Color::Rgb {
+                r: 101,
+                g: 67,
+                b: 33,
+            }
+        } else {
+            Color::Rgb {
+                r: 60,
+                g: 40,
+                b: 20,
+            }
+        };
+
+        for (row, line) in self.lines().iter().enumerate() {
+            let bend = tiers.saturating_sub(row) as f32 / tiers as f32;
+            let offset = (frame.sway * bend).round() as i32;
+            let snowy = row < tiers && row < frame.snow_level as usize * 2;
+            let color = if row == tiers {
+                trunk_color
+            } else if snowy {
+                SnowCover::color(frame.is_day)
+            } else {
+                color
+            };
+
+            for (col, ch) in line.chars().enumerate() {
+                let cx = self.x as i32 + col as i32 + offset;
+                if ch != ' ' && cx >= 0 {
+                    renderer.render_char(cx as u16, top + row as u16, ch, color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pine_shape() {
+        let pine = Pine::new(10, 3);
+        assert_eq!(pine.lines(), ["  /\\  ", " /  \\ ", "/____\\", "  ||"]);
+        assert_eq!(pine.top(20), (12, 16));
+
+        let mut surface = vec![20; 20];
+        pine.add_surface(&mut surface, 20);
+        assert_eq!(&surface[10..16], &[18, 17, 16, 16, 17, 18]);
+    }
+}
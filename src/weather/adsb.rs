@@ -0,0 +1,222 @@
+use crate::error::{NetworkError, WeatherError};
+use crate::weather::feed::read_feed;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+/// Positions older than this are dropped; the aircraft has likely left.
+const MAX_POSITION_AGE_SECS: f64 = 60.0;
+
+/// One aircraft reported by a local ADS-B receiver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aircraft {
+    pub hex: String,
+    pub callsign: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_ft: f64,
+    /// Direction of travel over the ground, degrees clockwise from north.
+    pub track: Option<f64>,
+    pub ground_speed_kt: Option<f64>,
+}
+
+/// Where an aircraft appears from the observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SightLine {
+    pub distance_km: f64,
+    /// Degrees clockwise from north.
+    pub bearing: f64,
+    /// Degrees above the horizon, allowing for the curve of the Earth.
+    pub elevation: f64,
+}
+
+impl Aircraft {
+    pub fn sight_line(&self, latitude: f64, longitude: f64) -> SightLine {
+        let (lat1, lat2) = (latitude.to_radians(), self.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (self.longitude - longitude).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        let distance_km = 2.0 * EARTH_RADIUS_KM * a.sqrt().asin();
+
+        let bearing = (dlon.sin() * lat2.cos())
+            .atan2(lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos())
+            .to_degrees()
+            .rem_euclid(360.0);
+
+        let height_km =
+            self.altitude_ft * 0.0003048 - distance_km.powi(2) / (2.0 * EARTH_RADIUS_KM);
+        let elevation = height_km.atan2(distance_km).to_degrees();
+
+        SightLine {
+            distance_km,
+            bearing,
+            elevation,
+        }
+    }
+
+    /// Dead-reckons the position forward along the track.
+    pub fn advanced(&self, seconds: f64) -> Aircraft {
+        let (Some(track), Some(speed)) = (self.track, self.ground_speed_kt) else {
+            return self.clone();
+        };
+        let km = speed * 1.852 * seconds / 3600.0;
+        let track = track.to_radians();
+        let dlat = km * track.cos() / 111.32;
+        let dlon = km * track.sin() / (111.32 * self.latitude.to_radians().cos().max(0.01));
+
+        Aircraft {
+            latitude: self.latitude + dlat,
+            longitude: self.longitude + dlon,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AircraftFeed {
+    #[serde(default)]
+    aircraft: Vec<FeedAircraft>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedAircraft {
+    hex: String,
+    #[serde(default)]
+    flight: Option<String>,
+    /// readsb and dump1090-fa; older dump1090 calls it `altitude`.
+    #[serde(default, alias = "altitude")]
+    alt_baro: Option<Value>,
+    #[serde(default)]
+    lat: Option<f64>,
+    #[serde(default)]
+    lon: Option<f64>,
+    #[serde(default)]
+    track: Option<f64>,
+    #[serde(default, alias = "speed")]
+    gs: Option<f64>,
+    #[serde(default)]
+    seen_pos: Option<f64>,
+}
+
+/// Airborne aircraft with a recent position from a dump1090 or readsb
+/// `aircraft.json`.
+pub fn parse_aircraft(body: &str) -> Result<Vec<Aircraft>, String> {
+    let feed: AircraftFeed =
+        serde_json::from_str(body).map_err(|e| format!("not an aircraft.json: {}", e))?;
+
+    Ok(feed
+        .aircraft
+        .into_iter()
+        .filter(|a| a.seen_pos.is_none_or(|age| age <= MAX_POSITION_AGE_SECS))
+        .filter_map(|a| {
+            // Feet, or "ground" for taxiing aircraft
+            let altitude_ft = a.alt_baro?.as_f64()?;
+            Some(Aircraft {
+                callsign: a
+                    .flight
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty()),
+                hex: a.hex,
+                latitude: a.lat?,
+                longitude: a.lon?,
+                altitude_ft,
+                track: a.track,
+                ground_speed_kt: a.gs,
+            })
+        })
+        .collect())
+}
+
+pub struct AdsbProvider {
+    client: reqwest::Client,
+    source: String,
+}
+
+impl AdsbProvider {
+    /// `source` is the receiver's `aircraft.json`, by URL or local path.
+    pub fn new(source: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(3))
+            .build()
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "Warning: Failed to create custom HTTP client for ADS-B: {}",
+                    e
+                );
+                reqwest::Client::new()
+            });
+
+        Self {
+            client,
+            source: source.into(),
+        }
+    }
+
+    pub async fn get_aircraft(&self) -> Result<Vec<Aircraft>, WeatherError> {
+        let body = read_feed(&self.client, &self.source, 5).await?;
+
+        parse_aircraft(&body).map_err(|reason| {
+            WeatherError::Network(NetworkError::InvalidData {
+                url: self.source.clone(),
+                reason,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aircraft_json() {
+        let body = r#"{
+            "now": 1718000000.0,
+            "messages": 1200,
+            "aircraft": [
+                {"hex": "3c6444", "flight": "DLH9U   ", "alt_baro": 36000, "gs": 450.1,
+                 "track": 90.2, "lat": 52.6, "lon": 13.5, "seen_pos": 1.2},
+                {"hex": "4ca7b5", "alt_baro": "ground", "lat": 52.36, "lon": 13.51},
+                {"hex": "471f8d", "alt_baro": 12000},
+                {"hex": "a1b2c3", "altitude": 8000, "lat": 52.4, "lon": 13.2, "speed": 250},
+                {"hex": "e49406", "alt_baro": 3000, "lat": 52.4, "lon": 13.2, "seen_pos": 300}
+            ]
+        }"#;
+
+        let aircraft = parse_aircraft(body).unwrap();
+        assert_eq!(aircraft.len(), 2);
+        assert_eq!(aircraft[0].callsign.as_deref(), Some("DLH9U"));
+        assert_eq!(aircraft[0].altitude_ft, 36000.0);
+        assert_eq!(aircraft[1].callsign, None);
+        assert_eq!(aircraft[1].ground_speed_kt, Some(250.0));
+
+        assert!(parse_aircraft("not json").is_err());
+    }
+
+    #[test]
+    fn test_sight_line() {
+        let aircraft = Aircraft {
+            hex: "3c6444".to_string(),
+            callsign: None,
+            latitude: 52.7,
+            longitude: 13.41,
+            altitude_ft: 30000.0,
+            track: Some(90.0),
+            ground_speed_kt: Some(450.0),
+        };
+
+        // 20 km due north at about 9 km up
+        let sight = aircraft.sight_line(52.52, 13.41);
+        assert!((sight.distance_km - 20.0).abs() < 0.5, "{sight:?}");
+        assert!(!(1.0..=359.0).contains(&sight.bearing), "{sight:?}");
+        assert!((sight.elevation - 24.5).abs() < 1.0, "{sight:?}");
+
+        // A minute east at 450 knots is about 14 km
+        let moved = aircraft.advanced(60.0);
+        assert!(moved.longitude > aircraft.longitude);
+        assert!((moved.latitude - aircraft.latitude).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::f64::consts::PI;
+
+/// Days in one cycle of lunar phases.
+const SYNODIC_MONTH: f64 = 29.530588853;
+/// Tilt of Earth's axis against the ecliptic at J2000, in degrees.
+const OBLIQUITY: f64 = 23.43928;
+
+/// Where a body sits in the sky, in degrees. Azimuth runs clockwise from
+/// north.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyPosition {
+    pub elevation: f64,
+    pub azimuth: f64,
+}
+
+/// Solar position from the NOAA fractional-year approximation, good to
+/// about a degree.
+pub fn sun_position(latitude: f64, longitude: f64, time: DateTime<Utc>) -> SkyPosition {
+    let hour = time.hour() as f64 + time.minute() as f64 / 60.0 + time.second() as f64 / 3600.0;
+    let gamma = 2.0 * PI / 365.0 * (time.ordinal0() as f64 + (hour - 12.0) / 24.0);
+
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let solar_minutes = hour * 60.0 + eqtime + 4.0 * longitude;
+    let hour_angle = (solar_minutes / 4.0 - 180.0).to_radians();
+    let lat = latitude.to_radians();
+
+    let cos_zenith =
+        lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    let elevation = 90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees();
+
+    let azimuth = hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * lat.sin() - declination.tan() * lat.cos())
+        .to_degrees()
+        + 180.0;
+
+    SkyPosition {
+        elevation,
+        azimuth: azimuth.rem_euclid(360.0),
+    }
+}
+
+/// Position on the celestial sphere, both in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    pub right_ascension: f64,
+    pub declination: f64,
+}
+
+/// Days since the J2000 epoch, 2000-01-01 12:00 UTC.
+fn days_since_j2000(time: DateTime<Utc>) -> f64 {
+    time.timestamp() as f64 / 86400.0 + 2440587.5 - 2451545.0
+}
+
+/// Local mean sidereal time in degrees: the right ascension crossing the
+/// meridian at this longitude.
+pub fn local_sidereal_time(longitude: f64, time: DateTime<Utc>) -> f64 {
+    (280.46061837 + 360.98564736629 * days_since_j2000(time) + longitude).rem_euclid(360.0)
+}
+
+/// Where a point on the celestial sphere appears for an observer.
+pub fn to_horizontal(position: Equatorial, latitude: f64, sidereal_time: f64) -> SkyPosition {
+    let hour_angle = (sidereal_time - position.right_ascension).to_radians();
+    let declination = position.declination.to_radians();
+    let lat = latitude.to_radians();
+
+    let sin_altitude =
+        lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    let elevation = sin_altitude.clamp(-1.0, 1.0).asin().to_degrees();
+
+    let azimuth = hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * lat.sin() - declination.tan() * lat.cos())
+        .to_degrees()
+        + 180.0;
+
+    SkyPosition {
+        elevation,
+        azimuth: azimuth.rem_euclid(360.0),
+    }
+}
+
+/// Fraction of the lunar cycle: 0 new, 0.25 first quarter, 0.5 full.
+pub fn moon_phase(time: DateTime<Utc>) -> f64 {
+    // New moon of 2000-01-06 18:14 UTC
+    let since_new = days_since_j2000(time) - 5.2597;
+    (since_new / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+/// Lit fraction of the moon's disc for a phase.
+pub fn moon_illumination(phase: f64) -> f64 {
+    (1.0 - (phase * 2.0 * PI).cos()) / 2.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+    Saturn,
+}
+
+/// Keplerian elements at J2000 and their change per century: semi-major
+/// axis (AU), eccentricity, inclination, mean longitude, longitude of
+/// perihelion and of the ascending node (degrees).
+struct OrbitalElements {
+    at_epoch: [f64; 6],
+    per_century: [f64; 6],
+}
+
+impl Planet {
+    pub const ALL: [Planet; 5] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+    ];
+
+    fn elements(self) -> &'static OrbitalElements {
+        match self {
+            Planet::Mercury => &MERCURY,
+            Planet::Venus => &VENUS,
+            Planet::Mars => &MARS,
+            Planet::Jupiter => &JUPITER,
+            Planet::Saturn => &SATURN,
+        }
+    }
+}
+
+// Elements from JPL's approximate positions of the major planets, valid
+// 1800-2050 to well under a degree.
+#[rustfmt::skip]
+const MERCURY: OrbitalElements = OrbitalElements {
+    at_epoch: [0.38709927, 0.20563593, 7.00497902, 252.25032350, 77.45779628, 48.33076593],
+    per_century: [0.00000037, 0.00001906, -0.00594749, 149472.67411175, 0.16047689, -0.12534081],
+};
+#[rustfmt::skip]
+const VENUS: OrbitalElements = OrbitalElements {
+    at_epoch: [0.72333566, 0.00677672, 3.39467605, 181.97909950, 131.60246718, 76.67984255],
+    per_century: [0.00000390, -0.00004107, -0.00078890, 58517.81538729, 0.00268329, -0.27769418],
+};
+#[rustfmt::skip]
+const MARS: OrbitalElements = OrbitalElements {
+    at_epoch: [1.52371034, 0.09339410, 1.84969142, -4.55343205, -23.94362959, 49.55953891],
+    per_century: [0.00001847, 0.00007882, -0.00813131, 19140.30268499, 0.44441088, -0.29257343],
+};
+#[rustfmt::skip]
+const JUPITER: OrbitalElements = OrbitalElements {
+    at_epoch: [5.20288700, 0.04838624, 1.30439695, 34.39644051, 14.72847983, 100.47390909],
+    per_century: [-0.00011607, -0.00013253, -0.00183714, 3034.74612775, 0.21252668, 0.20469106],
+};
+#[rustfmt::skip]
+const SATURN: OrbitalElements = OrbitalElements {
+    at_epoch: [9.53667594, 0.05386179, 2.48599187, 49.95424423, 92.59887831, 113.66242448],
+    per_century: [-0.00125060, -0.00050991, 0.00193609, 1222.49362201, -0.41897216, -0.28867794],
+};
+
+/// The Earth-Moon barycentre, close enough to Earth at this precision.
+#[rustfmt::skip]
+const EARTH: OrbitalElements = OrbitalElements {
+    at_epoch: [1.00000261, 0.01671123, -0.00001531, 100.46457166, 102.93768193, 0.0],
+    per_century: [0.00000562, -0.00004392, -0.01294668, 35999.37244981, 0.32327364, 0.0],
+};
+
+/// Heliocentric ecliptic coordinates in AU.
+fn heliocentric(elements: &OrbitalElements, centuries: f64) -> [f64; 3] {
+    let [a, e, inclination, mean_longitude, perihelion, node]: [f64; 6] =
+        std::array::from_fn(|i| elements.at_epoch[i] + elements.per_century[i] * centuries);
+
+    let argument = (perihelion - node).to_radians();
+    let mean_anomaly = (mean_longitude - perihelion).to_radians();
+    let mut eccentric = mean_anomaly + e * mean_anomaly.sin();
+    for _ in 0..6 {
+        eccentric -= (eccentric - e * eccentric.sin() - mean_anomaly) / (1.0 - e * eccentric.cos());
+    }
+
+    let px = a * (eccentric.cos() - e);
+    let py = a * (1.0 - e * e).sqrt() * eccentric.sin();
+    let (sin_w, cos_w) = argument.sin_cos();
+    let (sin_n, cos_n) = node.to_radians().sin_cos();
+    let (sin_i, cos_i) = inclination.to_radians().sin_cos();
+
+    [
+        (cos_w * cos_n - sin_w * sin_n * cos_i) * px
+            + (-sin_w * cos_n - cos_w * sin_n * cos_i) * py,
+        (cos_w * sin_n + sin_w * cos_n * cos_i) * px
+            + (-sin_w * sin_n + cos_w * cos_n * cos_i) * py,
+        sin_w * sin_i * px + cos_w * sin_i * py,
+    ]
+}
+
+fn ecliptic_to_equatorial([x, y, z]: [f64; 3]) -> Equatorial {
+    let (sin_e, cos_e) = OBLIQUITY.to_radians().sin_cos();
+    let ye = y * cos_e - z * sin_e;
+    let ze = y * sin_e + z * cos_e;
+    Equatorial {
+        right_ascension: ye.atan2(x).to_degrees().rem_euclid(360.0),
+        declination: ze.atan2(x.hypot(ye)).to_degrees(),
+    }
+}
+
+/// Where a planet appears from Earth.
+pub fn planet_position(planet: Planet, time: DateTime<Utc>) -> Equatorial {
+    let centuries = days_since_j2000(time) / 36525.0;
+    let earth = heliocentric(&EARTH, centuries);
+    let body = heliocentric(planet.elements(), centuries);
+    ecliptic_to_equatorial(std::array::from_fn(|i| body[i] - earth[i]))
+}
+
+#[cfg(test)]
+fn sun_equatorial(time: DateTime<Utc>) -> Equatorial {
+    let earth = heliocentric(&EARTH, days_since_j2000(time) / 36525.0);
+    ecliptic_to_equatorial(earth.map(|c| -c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sun_position() {
+        // London, summer solstice
+        let noon = Utc.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap();
+        let sun = sun_position(51.5, 0.0, noon);
+        assert!((sun.elevation - 62.0).abs() < 1.0, "{sun:?}");
+        assert!((sun.azimuth - 180.0).abs() < 3.0, "{sun:?}");
+
+        let evening = Utc.with_ymd_and_hms(2024, 6, 21, 19, 0, 0).unwrap();
+        let sun = sun_position(51.5, 0.0, evening);
+        assert!((sun.elevation - 10.0).abs() < 1.5, "{sun:?}");
+        assert!(sun.azimuth > 280.0 && sun.azimuth < 300.0, "{sun:?}");
+
+        let midnight = Utc.with_ymd_and_hms(2024, 6, 21, 0, 0, 0).unwrap();
+        assert!(sun_position(51.5, 0.0, midnight).elevation < 0.0);
+    }
+
+    fn separation(a: Equatorial, b: Equatorial) -> f64 {
+        let (ra1, dec1) = (a.right_ascension.to_radians(), a.declination.to_radians());
+        let (ra2, dec2) = (b.right_ascension.to_radians(), b.declination.to_radians());
+        (dec1.sin() * dec2.sin() + dec1.cos() * dec2.cos() * (ra1 - ra2).cos())
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+    }
+
+    #[test]
+    fn test_sidereal_time_and_pole_star() {
+        let epoch = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        assert!((local_sidereal_time(0.0, epoch) - 280.46).abs() < 0.01);
+
+        // Polaris stays about as high as the observer's latitude
+        let polaris = Equatorial {
+            right_ascension: 37.95,
+            declination: 89.26,
+        };
+        for hour in [0, 6, 12, 18] {
+            let time = Utc.with_ymd_and_hms(2025, 3, 1, hour, 0, 0).unwrap();
+            let position = to_horizontal(polaris, 52.5, local_sidereal_time(13.4, time));
+            assert!((position.elevation - 52.5).abs() < 1.0, "{position:?}");
+        }
+    }
+
+    #[test]
+    fn test_planet_positions() {
+        // Mars at opposition, opposite the sun
+        let opposition = Utc.with_ymd_and_hms(2020, 10, 13, 23, 0, 0).unwrap();
+        let mars = planet_position(Planet::Mars, opposition);
+        assert!(separation(mars, sun_equatorial(opposition)) > 170.0);
+
+        // Inner planets never stray far from the sun
+        for month in 1..=12 {
+            let time = Utc.with_ymd_and_hms(2025, month, 15, 0, 0, 0).unwrap();
+            let sun = sun_equatorial(time);
+            assert!(separation(planet_position(Planet::Venus, time), sun) < 48.0);
+            assert!(separation(planet_position(Planet::Mercury, time), sun) < 28.5);
+        }
+    }
+
+    #[test]
+    fn test_moon_phase() {
+        let full = Utc.with_ymd_and_hms(2024, 12, 15, 9, 0, 0).unwrap();
+        assert!((moon_phase(full) - 0.5).abs() < 0.03);
+        assert!(moon_illumination(moon_phase(full)) > 0.98);
+
+        let new = Utc.with_ymd_and_hms(2025, 1, 29, 12, 0, 0).unwrap();
+        let phase = moon_phase(new);
+        assert!(!(0.03..=0.97).contains(&phase), "{phase}");
+    }
+}
@@ -0,0 +1,38 @@
+use crate::error::{ConfigError, NetworkError, WeatherError};
+
+/// Reads a feed from an http(s) URL, or from a `file://` URL or plain path
+/// to a local copy.
+pub async fn read_feed(
+    client: &reqwest::Client,
+    source: &str,
+    timeout_secs: u64,
+) -> Result<String, WeatherError> {
+    let local_path = if let Some(path) = source.strip_prefix("file://") {
+        Some(path)
+    } else if source.starts_with("http://") || source.starts_with("https://") {
+        None
+    } else {
+        Some(source)
+    };
+
+    match local_path {
+        Some(path) => tokio::fs::read_to_string(path).await.map_err(|e| {
+            WeatherError::Config(ConfigError::ReadError {
+                path: path.to_string(),
+                source: e,
+            })
+        }),
+        None => {
+            let response = client
+                .get(source)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| NetworkError::from_reqwest(e, source, timeout_secs))?;
+            Ok(response
+                .text()
+                .await
+                .map_err(|e| NetworkError::from_reqwest(e, source, timeout_secs))?)
+        }
+    }
+}
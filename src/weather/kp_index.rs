@@ -0,0 +1,104 @@
+use crate::error::{NetworkError, WeatherError};
+use crate::weather::feed::read_feed;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Planetary K-index for the last week in 3-hour steps, from NOAA's Space
+/// Weather Prediction Center.
+pub const SWPC_KP_URL: &str = "https://services.swpc.noaa.gov/products/noaa-planetary-k-index.json";
+
+pub struct SwpcKpProvider {
+    client: reqwest::Client,
+    source: String,
+}
+
+impl SwpcKpProvider {
+    /// `source` is an http(s) URL, or a `file://` URL or plain path to a
+    /// local copy of the feed.
+    pub fn new(source: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to create custom HTTP client for Kp: {}", e);
+                reqwest::Client::new()
+            });
+
+        Self {
+            client,
+            source: source.into(),
+        }
+    }
+
+    /// Latest Kp value in the feed, 0 (quiet) to 9 (extreme storm).
+    pub async fn get_current_kp(&self) -> Result<f64, WeatherError> {
+        let body = read_feed(&self.client, &self.source, 30).await?;
+
+        parse_kp(&body).map_err(|reason| {
+            WeatherError::Network(NetworkError::InvalidData {
+                url: self.source.clone(),
+                reason,
+            })
+        })
+    }
+}
+
+/// Reads the most recent Kp from either shape SWPC has published: rows of
+/// strings under a header row, or one object per reading.
+pub fn parse_kp(body: &str) -> Result<f64, String> {
+    let rows: Vec<Value> =
+        serde_json::from_str(body).map_err(|e| format!("not a JSON array: {}", e))?;
+
+    let header = rows.first().and_then(Value::as_array);
+    let column = header.and_then(|names| {
+        names
+            .iter()
+            .position(|name| name.as_str().is_some_and(|n| n.eq_ignore_ascii_case("kp")))
+    });
+
+    rows.iter()
+        .rev()
+        .find_map(|row| match row {
+            Value::Array(cells) => column.and_then(|i| number(cells.get(i)?)),
+            Value::Object(fields) => ["estimated_kp", "Kp", "kp_index", "kp"]
+                .iter()
+                .find_map(|key| number(fields.get(*key)?)),
+            _ => None,
+        })
+        .filter(|kp| (0.0..=9.0).contains(kp))
+        .ok_or_else(|| "no Kp reading found".to_string())
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kp_formats() {
+        let rows = r#"[
+            ["time_tag", "Kp", "a_running", "station_count"],
+            ["2025-05-10 18:00:00.000", "7.67", "207", "8"],
+            ["2025-05-10 21:00:00.000", "8.33", "300", "8"]
+        ]"#;
+        assert_eq!(parse_kp(rows), Ok(8.33));
+
+        let objects = r#"[
+            {"time_tag": "2025-05-10T18:00:00", "Kp": 4.0, "a_running": 27},
+            {"time_tag": "2025-05-10T21:00:00", "Kp": 5.33, "a_running": 56}
+        ]"#;
+        assert_eq!(parse_kp(objects), Ok(5.33));
+
+        assert!(parse_kp("[]").is_err());
+        assert!(parse_kp("<html>").is_err());
+        assert!(parse_kp(r#"[["time_tag", "Kp"], ["2025-05-10", "n/a"]]"#).is_err());
+    }
+}
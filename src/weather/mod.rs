@@ -1,5 +1,9 @@
+pub mod adsb;
 pub mod air_quality;
+pub mod astronomy;
 pub mod client;
+pub mod feed;
+pub mod kp_index;
 pub mod normalizer;
 pub mod open_meteo;
 pub mod provider;
@@ -9,8 +13,13 @@ pub mod units;
 pub use client::WeatherClient;
 pub use open_meteo::OpenMeteoProvider;
 pub use air_quality::OpenMeteoAqiProvider;
+pub use astronomy::{SkyPosition, sun_position};
+pub use adsb::{AdsbProvider, Aircraft};
+pub use kp_index::SwpcKpProvider;
 pub use types::{
     FogIntensity, RainIntensity, SnowIntensity, WeatherCondition, WeatherConditions, WeatherData,
     WeatherLocation, WeatherUnits, AirQualityData, AqiCategory
 };
-pub use units::{format_precipitation, format_temperature, format_wind_speed};
+pub use units::{
+    beaufort_scale, compass_direction, format_precipitation, format_temperature, format_wind_speed,
+};
@@ -51,8 +51,14 @@ impl WeatherNormalizer {
             wind_speed: response.wind_speed,
             wind_direction: response.wind_direction,
             cloud_cover: response.cloud_cover,
+            cloud_cover_low: response.cloud_cover_low,
+            cloud_cover_mid: response.cloud_cover_mid,
+            cloud_cover_high: response.cloud_cover_high,
             pressure: response.pressure,
             visibility: response.visibility,
+            snow_depth: response.snow_depth,
+            cape: response.cape,
+            utc_offset_seconds: response.utc_offset_seconds,
             is_day: response.is_day == 1,
             moon_phase: response.moon_phase,
             timestamp: response.timestamp,
@@ -141,8 +147,14 @@ mod tests {
             wind_speed: 15.0,
             wind_direction: 180.0,
             cloud_cover: 85.0,
+            cloud_cover_low: None,
+            cloud_cover_mid: None,
+            cloud_cover_high: None,
             pressure: 1013.0,
             visibility: Some(10000.0),
+            snow_depth: None,
+            cape: None,
+            utc_offset_seconds: None,
             is_day: 1,
             moon_phase: Some(0.5),
             timestamp: "2024-01-01T12:00".to_string(),
@@ -160,4 +172,40 @@ mod tests {
         assert!(data.is_day);
         assert_eq!(data.moon_phase, Some(0.5));
     }
+
+    #[test]
+    fn test_normalize_cloud_layers() {
+        let response = WeatherProviderResponse {
+            weather_code: 3,
+            temperature: 12.0,
+            apparent_temperature: 11.0,
+            humidity: 80.0,
+            precipitation: 0.0,
+            wind_speed: 4.0,
+            wind_direction: 270.0,
+            cloud_cover: 95.0,
+            cloud_cover_low: Some(20.0),
+            cloud_cover_mid: Some(90.0),
+            cloud_cover_high: Some(60.0),
+            pressure: 1008.0,
+            visibility: Some(10000.0),
+            snow_depth: None,
+            cape: None,
+            utc_offset_seconds: None,
+            is_day: 1,
+            moon_phase: None,
+            timestamp: "2024-01-01T12:00".to_string(),
+            hourly_times: None,
+            hourly_temperatures: None,
+            hourly_weather_codes: None,
+            hourly_precipitation_probabilities: None,
+            hourly_wind_speeds: None,
+        };
+
+        let data = WeatherNormalizer::normalize(response);
+
+        assert_eq!(data.cloud_cover_low, Some(20.0));
+        assert_eq!(data.cloud_cover_mid, Some(90.0));
+        assert_eq!(data.cloud_cover_high, Some(60.0));
+    }
 }
@@ -1,9 +1,12 @@
 use crate::error::{NetworkError, WeatherError};
+use crate::weather::astronomy;
 use crate::weather::provider::{WeatherProvider, WeatherProviderResponse};
 use crate::weather::types::{
     PrecipitationUnit, TemperatureUnit, WeatherLocation, WeatherUnits, WindSpeedUnit,
 };
-use crate::weather::units::{normalize_precipitation, normalize_temperature, normalize_wind_speed};
+use crate::weather::units::{
+    normalize_precipitation, normalize_snow_depth, normalize_temperature, normalize_wind_speed,
+};
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::time::Duration;
@@ -19,6 +22,8 @@ pub struct OpenMeteoProvider {
 struct OpenMeteoResponse {
     current: CurrentWeather,
     hourly: Option<HourlyWeather>,
+    #[serde(default)]
+    utc_offset_seconds: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -42,11 +47,21 @@ struct CurrentWeather {
     precipitation: f64,
     weather_code: i32,
     cloud_cover: f64,
+    #[serde(default)]
+    cloud_cover_low: Option<f64>,
+    #[serde(default)]
+    cloud_cover_mid: Option<f64>,
+    #[serde(default)]
+    cloud_cover_high: Option<f64>,
     surface_pressure: f64,
     wind_speed_10m: f64,
     wind_direction_10m: f64,
     #[serde(default)]
     visibility: Option<f64>,
+    #[serde(default)]
+    snow_depth: Option<f64>,
+    #[serde(default)]
+    cape: Option<f64>,
 }
 
 impl OpenMeteoProvider {
@@ -92,7 +107,7 @@ impl OpenMeteoProvider {
 
     fn build_url(&self, location: &WeatherLocation, units: &WeatherUnits) -> String {
         format!(
-            "{}?latitude={}&longitude={}&current=temperature_2m,relative_humidity_2m,apparent_temperature,is_day,precipitation,weather_code,cloud_cover,surface_pressure,wind_speed_10m,wind_direction_10m,visibility&hourly=temperature_2m,weather_code,precipitation_probability,wind_speed_10m&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}&timezone=auto",
+            "{}?latitude={}&longitude={}&current=temperature_2m,relative_humidity_2m,apparent_temperature,is_day,precipitation,weather_code,cloud_cover,cloud_cover_low,cloud_cover_mid,cloud_cover_high,surface_pressure,wind_speed_10m,wind_direction_10m,visibility,snow_depth,cape&hourly=temperature_2m,weather_code,precipitation_probability,wind_speed_10m&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}&timezone=auto",
             self.base_url,
             location.latitude,
             location.longitude,
@@ -129,7 +144,7 @@ impl WeatherProvider for OpenMeteoProvider {
             .await
             .map_err(|e| WeatherError::Network(NetworkError::from_reqwest(e, &url, 30)))?;
 
-        let moon_phase = Some(0.5);
+        let moon_phase = Some(astronomy::moon_phase(chrono::Utc::now()));
 
         let (hourly_times, hourly_temperatures, hourly_weather_codes, hourly_precipitation_probabilities, hourly_wind_speeds) = if let Some(hourly) = data.hourly {
             let temps = hourly.temperature_2m.into_iter().map(|t| normalize_temperature(t, units.temperature)).collect();
@@ -151,8 +166,17 @@ impl WeatherProvider for OpenMeteoProvider {
             wind_speed: normalize_wind_speed(data.current.wind_speed_10m, units.wind_speed),
             wind_direction: data.current.wind_direction_10m,
             cloud_cover: data.current.cloud_cover,
+            cloud_cover_low: data.current.cloud_cover_low,
+            cloud_cover_mid: data.current.cloud_cover_mid,
+            cloud_cover_high: data.current.cloud_cover_high,
             pressure: data.current.surface_pressure,
             visibility: data.current.visibility,
+            snow_depth: data
+                .current
+                .snow_depth
+                .map(|d| normalize_snow_depth(d, units.precipitation)),
+            cape: data.current.cape,
+            utc_offset_seconds: data.utc_offset_seconds,
             is_day: data.current.is_day,
             moon_phase,
             timestamp: data.current.time,
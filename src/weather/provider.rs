@@ -13,8 +13,18 @@ pub struct WeatherProviderResponse {
     pub wind_speed: f64,
     pub wind_direction: f64,
     pub cloud_cover: f64,
+    #[serde(default)]
+    pub cloud_cover_low: Option<f64>,
+    #[serde(default)]
+    pub cloud_cover_mid: Option<f64>,
+    #[serde(default)]
+    pub cloud_cover_high: Option<f64>,
     pub pressure: f64,
     pub visibility: Option<f64>,
+    pub snow_depth: Option<f64>,
+    pub cape: Option<f64>,
+    #[serde(default)]
+    pub utc_offset_seconds: Option<i32>,
     pub is_day: i32,
     pub moon_phase: Option<f64>,
     pub timestamp: String,
@@ -69,6 +69,20 @@ impl WeatherCondition {
         }
     }
 
+    /// Representative total cloud cover (%) for a condition, used when no
+    /// measured value is available (simulation).
+    pub fn typical_cloud_cover(&self) -> f64 {
+        match self {
+            Self::Clear => 10.0,
+            Self::PartlyCloudy => 40.0,
+            Self::Cloudy => 75.0,
+            Self::Overcast | Self::Fog => 100.0,
+            Self::Drizzle | Self::SnowGrains => 90.0,
+            Self::RainShowers | Self::SnowShowers => 70.0,
+            _ => 100.0,
+        }
+    }
+
     pub fn is_raining(&self) -> bool {
         matches!(
             self,
@@ -89,6 +103,14 @@ impl WeatherCondition {
         matches!(self, Self::Thunderstorm | Self::ThunderstormHail)
     }
 
+    pub fn is_hail(&self) -> bool {
+        matches!(self, Self::ThunderstormHail)
+    }
+
+    pub fn is_freezing_rain(&self) -> bool {
+        matches!(self, Self::FreezingRain)
+    }
+
     pub fn is_cloudy(&self) -> bool {
         matches!(self, Self::PartlyCloudy | Self::Cloudy | Self::Overcast)
     }
@@ -169,8 +191,23 @@ pub struct WeatherData {
     pub wind_speed: f64,
     pub wind_direction: f64,
     pub cloud_cover: f64,
+    #[serde(default)]
+    pub cloud_cover_low: Option<f64>,
+    #[serde(default)]
+    pub cloud_cover_mid: Option<f64>,
+    #[serde(default)]
+    pub cloud_cover_high: Option<f64>,
     pub pressure: f64,
     pub visibility: Option<f64>,
+    /// Snow on the ground in metres.
+    #[serde(default)]
+    pub snow_depth: Option<f64>,
+    /// Convective available potential energy in J/kg.
+    #[serde(default)]
+    pub cape: Option<f64>,
+    /// Offset of the location's local time from UTC, in seconds.
+    #[serde(default)]
+    pub utc_offset_seconds: Option<i32>,
     pub is_day: bool,
     pub moon_phase: Option<f64>,
     pub timestamp: String,
@@ -204,7 +241,7 @@ impl AqiCategory {
         }
     }
 
-    pub fn to_string(&self) -> &'static str {
+    pub fn to_string(self) -> &'static str {
         match self {
             Self::Good => "Good",
             Self::Fair => "Fair",
@@ -271,6 +308,8 @@ pub struct WeatherConditions {
     pub is_raining: bool,
     pub is_snowing: bool,
     pub is_thunderstorm: bool,
+    pub is_hail: bool,
+    pub is_freezing_rain: bool,
     pub is_cloudy: bool,
     pub is_foggy: bool,
     pub is_day: bool,
@@ -282,6 +321,8 @@ impl Default for WeatherConditions {
             is_raining: false,
             is_snowing: false,
             is_thunderstorm: false,
+            is_hail: false,
+            is_freezing_rain: false,
             is_cloudy: false,
             is_foggy: false,
             is_day: true,
@@ -40,6 +40,24 @@ pub fn inch_to_mm(inch: f64) -> f64 {
     inch * 25.4
 }
 
+/// Beaufort force (0-12) for a wind speed in m/s.
+pub fn beaufort_scale(ms: f64) -> u8 {
+    const UPPER_BOUNDS: [f64; 12] = [
+        0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7,
+    ];
+    UPPER_BOUNDS
+        .iter()
+        .position(|&bound| ms < bound)
+        .unwrap_or(12) as u8
+}
+
+/// Eight-point compass name for a meteorological wind direction in degrees.
+pub fn compass_direction(degrees: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let index = (degrees.rem_euclid(360.0) / 45.0).round() as usize % POINTS.len();
+    POINTS[index]
+}
+
 pub fn format_temperature(celsius: f64, unit: TemperatureUnit) -> (f64, &'static str) {
     match unit {
         TemperatureUnit::Celsius => (celsius, "°C"),
@@ -79,9 +97,39 @@ pub fn normalize_wind_speed(value: f64, unit: WindSpeedUnit) -> f64 {
     }
 }
 
+/// Open-Meteo reports snow depth in metres, or in feet with imperial units.
+pub fn normalize_snow_depth(value: f64, unit: PrecipitationUnit) -> f64 {
+    match unit {
+        PrecipitationUnit::Mm => value,
+        PrecipitationUnit::Inch => value * 0.3048,
+    }
+}
+
 pub fn normalize_precipitation(value: f64, unit: PrecipitationUnit) -> f64 {
     match unit {
         PrecipitationUnit::Mm => value,
         PrecipitationUnit::Inch => inch_to_mm(value),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beaufort_scale() {
+        assert_eq!(beaufort_scale(0.0), 0);
+        assert_eq!(beaufort_scale(3.0), 2);
+        assert_eq!(beaufort_scale(5.5), 4);
+        assert_eq!(beaufort_scale(12.5), 6);
+        assert_eq!(beaufort_scale(40.0), 12);
+    }
+
+    #[test]
+    fn test_compass_direction() {
+        assert_eq!(compass_direction(0.0), "N");
+        assert_eq!(compass_direction(225.0), "SW");
+        assert_eq!(compass_direction(350.0), "N");
+        assert_eq!(compass_direction(-90.0), "W");
+    }
+}
@@ -1,6 +1,7 @@
 use std::fs;
 use std::io::Write;
 use weathr::config::Config;
+use weathr::scene::WorldScene;
 
 #[test]
 fn test_config_integration_load_valid_file() {
@@ -115,3 +116,61 @@ fn test_config_integration_extra_whitespace() {
 
     fs::remove_file(test_config_path).ok();
 }
+
+#[test]
+fn test_config_integration_custom_scene_file() {
+    let temp_dir = std::env::temp_dir();
+    let scene_path = temp_dir.join("weathr_scene_office.toml");
+
+    let mut file = fs::File::create(&scene_path).unwrap();
+    writeln!(file, "ground_height = 5").unwrap();
+    writeln!(file, "[[layers]]").unwrap();
+    writeln!(file, "art = '''").unwrap();
+    writeln!(file, " _=_ ").unwrap();
+    writeln!(file, "|[] |").unwrap();
+    writeln!(file, "|_|_|").unwrap();
+    writeln!(file, "'''").unwrap();
+    writeln!(file, "[anchors]").unwrap();
+    writeln!(file, "chimney = [2, 0]").unwrap();
+    writeln!(file, "windows = [[1, 1, 2]]").unwrap();
+    drop(file);
+
+    let mut scene = WorldScene::new(41, 30);
+    scene
+        .load_landscape(&scene_path)
+        .expect("Failed to load scene");
+
+    let geometry = scene.geometry();
+    assert_eq!(geometry.ground_height, 5);
+    assert_eq!(geometry.horizon_y, 25);
+    assert_eq!(geometry.chimney, Some((20, 22)));
+    assert_eq!(geometry.lights, vec![(19, 23)]);
+
+    fs::remove_file(scene_path).ok();
+}
+
+#[test]
+fn test_config_integration_invalid_scene_file() {
+    let temp_dir = std::env::temp_dir();
+    let scene_path = temp_dir.join("weathr_scene_invalid.toml");
+
+    let mut file = fs::File::create(&scene_path).unwrap();
+    writeln!(file, "[[layers]]").unwrap();
+    writeln!(file, "art = 'xx'").unwrap();
+    writeln!(file, "mask = 'xq'").unwrap();
+    writeln!(file, "[colors]").unwrap();
+    writeln!(file, "x = {{ day = \"dark_red\" }}").unwrap();
+    drop(file);
+
+    let mut scene = WorldScene::new(41, 30);
+    let err = scene.load_landscape(&scene_path).unwrap_err();
+
+    assert_eq!(err.kind(), "InvalidScene");
+    assert!(err.to_string().contains("weathr_scene_invalid.toml"));
+    assert!(err.to_string().contains("'q'"));
+
+    let missing = scene.load_landscape(&temp_dir.join("weathr_scene_missing.toml"));
+    assert_eq!(missing.unwrap_err().kind(), "ReadError");
+
+    fs::remove_file(scene_path).ok();
+}
@@ -10,6 +10,30 @@ fn test_config_error_kind() {
 
     let error = ConfigError::NoConfigDir;
     assert_eq!(error.kind(), "NoConfigDir");
+
+    let error = ConfigError::InvalidScene {
+        path: "office.toml".to_string(),
+        reason: "no layers".to_string(),
+    };
+    assert_eq!(error.kind(), "InvalidScene");
+
+    let error = ConfigError::InvalidSprite {
+        path: "mascot.toml".to_string(),
+        reason: "no frames".to_string(),
+    };
+    assert_eq!(error.kind(), "InvalidSprite");
+
+    let error = ConfigError::InvalidScript {
+        path: "embers.rhai".to_string(),
+        reason: "syntax error".to_string(),
+    };
+    assert_eq!(error.kind(), "InvalidScript");
+
+    let error = ConfigError::InvalidFps(0);
+    assert_eq!(error.kind(), "InvalidFps");
+
+    let error = ConfigError::InvalidScriptBudget(f64::INFINITY);
+    assert_eq!(error.kind(), "InvalidScriptBudget");
 }
 
 #[test]
@@ -24,6 +48,28 @@ fn test_config_error_display() {
 
     let error = ConfigError::NoConfigDir;
     assert!(error.to_string().contains("config directory"));
+
+    let error = ConfigError::InvalidScene {
+        path: "office.toml".to_string(),
+        reason: "no layers".to_string(),
+    };
+    assert!(error.to_string().contains("office.toml"));
+    assert!(error.to_string().contains("no layers"));
+
+    let error = ConfigError::InvalidSprite {
+        path: "mascot.toml".to_string(),
+        reason: "no frames".to_string(),
+    };
+    assert!(error.to_string().contains("sprite"));
+    assert!(error.to_string().contains("mascot.toml"));
+
+    let error = ConfigError::InvalidFps(500);
+    assert!(error.to_string().contains("500"));
+    assert!(error.to_string().contains("fps"));
+
+    let error = ConfigError::InvalidScriptBudget(-1.0);
+    assert!(error.to_string().contains("-1"));
+    assert!(error.to_string().contains("budget_ms"));
 }
 
 #[test]
@@ -45,8 +45,14 @@ fn test_weather_normalizer_integration_all_wmo_codes() {
             wind_speed: 10.0,
             wind_direction: 180.0,
             cloud_cover: 50.0,
+            cloud_cover_low: None,
+            cloud_cover_mid: None,
+            cloud_cover_high: None,
             pressure: 1013.0,
             visibility: Some(10000.0),
+            snow_depth: None,
+            cape: None,
+            utc_offset_seconds: None,
             is_day: 1,
             moon_phase: None,
             timestamp: "2024-01-01T12:00".to_string(),
@@ -77,8 +83,14 @@ fn test_weather_normalizer_integration_day_night() {
         wind_speed: 10.0,
         wind_direction: 180.0,
         cloud_cover: 0.0,
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: 1013.0,
         visibility: Some(10000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day: 1,
         moon_phase: None,
         timestamp: "2024-01-01T12:00".to_string(),
@@ -98,8 +110,14 @@ fn test_weather_normalizer_integration_day_night() {
         wind_speed: 5.0,
         wind_direction: 180.0,
         cloud_cover: 0.0,
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: 1013.0,
         visibility: Some(10000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day: 0,
         moon_phase: None,
         timestamp: "2024-01-01T00:00".to_string(),
@@ -128,8 +146,14 @@ fn test_weather_normalizer_integration_clear_conditions() {
         wind_speed: 5.0,
         wind_direction: 90.0,
         cloud_cover: 10.0,
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: 1015.0,
         visibility: Some(15000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day: 1,
         moon_phase: None,
         timestamp: "2024-06-15T14:00".to_string(),
@@ -161,8 +185,14 @@ fn test_weather_normalizer_integration_rainy_conditions() {
         wind_speed: 12.0,
         wind_direction: 270.0,
         cloud_cover: 95.0,
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: 1005.0,
         visibility: Some(3000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day: 1,
         moon_phase: None,
         timestamp: "2024-03-20T10:00".to_string(),
@@ -191,8 +221,14 @@ fn test_weather_normalizer_integration_snowy_conditions() {
         wind_speed: 8.0,
         wind_direction: 0.0,
         cloud_cover: 100.0,
+        cloud_cover_low: None,
+        cloud_cover_mid: None,
+        cloud_cover_high: None,
         pressure: 1010.0,
         visibility: Some(1000.0),
+        snow_depth: None,
+        cape: None,
+        utc_offset_seconds: None,
         is_day: 0,
         moon_phase: None,
         timestamp: "2024-01-10T22:00".to_string(),